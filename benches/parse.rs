@@ -0,0 +1,122 @@
+//! Parse throughput of `XcodePbxProject::parse_from_string`.
+//!
+//! Run with `cargo bench --bench parse`. The synthetic projects grow by a factor of ten,
+//! parse time should grow by the same factor. Each input is read into the owned
+//! `XcodePbxProject`, the borrowed `PlistDocument` and through the streaming `PlistObjects`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use xcode_uniffi::plist::{PlistDocument, PlistObjects};
+use xcode_uniffi::XcodePbxProject;
+
+const TEST_PROJECT: &str = include_str!("../test_data/project.pbxproj");
+
+fn bench(name: &str, content: &str, iterations: u32, parse: impl Fn(&str) -> usize) {
+    assert!(parse(content) > 0, "benchmark input must parse");
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(parse(black_box(content)));
+    }
+    let per_iteration = start.elapsed() / iterations;
+    let megabytes = content.len() as f64 / 1_000_000.0;
+    println!(
+        "{:<36} {:>8.2} MB {:>12.2?}/iter {:>8.1} MB/s",
+        name,
+        megabytes,
+        per_iteration,
+        megabytes / per_iteration.as_secs_f64()
+    );
+}
+
+/// Owned `XcodePbxProject`, the representation used for editing.
+fn parse_owned(content: &str) -> usize {
+    XcodePbxProject::parse_from_string(content)
+        .unwrap()
+        .items()
+        .len()
+}
+
+/// Borrowed `PlistDocument`.
+fn parse_borrowed(content: &str) -> usize {
+    PlistDocument::parse(content).unwrap().objects().count()
+}
+
+/// `PlistObjects`, one object at a time.
+fn parse_streaming(content: &str) -> usize {
+    PlistObjects::new(content)
+        .unwrap()
+        .try_fold(0, |count, object| object.map(|_| count + 1))
+        .unwrap()
+}
+
+fn bench_all(name: &str, content: &str, iterations: u32) {
+    bench(&format!("{} owned", name), content, iterations, parse_owned);
+    bench(
+        &format!("{} borrowed", name),
+        content,
+        iterations,
+        parse_borrowed,
+    );
+    bench(
+        &format!("{} streaming", name),
+        content,
+        iterations,
+        parse_streaming,
+    );
+}
+
+/// A project with `files` source files, each with a file reference, a build file and an entry
+/// in the main group and in the sources build phase.
+fn synthetic_project(files: usize) -> String {
+    let id = |kind: usize, i: usize| format!("{:08X}{:016X}", kind, i);
+    let mut out = String::from("// !$*UTF8*$!\n{\n\tarchiveVersion = 1;\n\tclasses = {\n\t};\n\tobjectVersion = 55;\n\tobjects = {\n");
+
+    out.push_str("\n/* Begin PBXBuildFile section */\n");
+    for i in 0..files {
+        out.push_str(&format!(
+            "\t\t{} /* File{i}.swift in Sources */ = {{isa = PBXBuildFile; fileRef = {} /* File{i}.swift */; }};\n",
+            id(1, i),
+            id(2, i)
+        ));
+    }
+    out.push_str("/* End PBXBuildFile section */\n");
+
+    out.push_str("\n/* Begin PBXFileReference section */\n");
+    for i in 0..files {
+        out.push_str(&format!(
+            "\t\t{} /* File{i}.swift */ = {{isa = PBXFileReference; lastKnownFileType = sourcecode.swift; path = \"Sources/File {i}.swift\"; sourceTree = \"<group>\"; }};\n",
+            id(2, i)
+        ));
+    }
+    out.push_str("/* End PBXFileReference section */\n");
+
+    out.push_str("\n/* Begin PBXGroup section */\n");
+    out.push_str(&format!(
+        "\t\t{} = {{\n\t\t\tisa = PBXGroup;\n\t\t\tchildren = (\n",
+        id(3, 0)
+    ));
+    for i in 0..files {
+        out.push_str(&format!("\t\t\t\t{} /* File{i}.swift */,\n", id(2, i)));
+    }
+    out.push_str("\t\t\t);\n\t\t\tsourceTree = \"<group>\";\n\t\t};\n/* End PBXGroup section */\n");
+
+    out.push_str("\n/* Begin PBXSourcesBuildPhase section */\n");
+    out.push_str(&format!("\t\t{} /* Sources */ = {{\n\t\t\tisa = PBXSourcesBuildPhase;\n\t\t\tbuildActionMask = 2147483647;\n\t\t\tfiles = (\n", id(4, 0)));
+    for i in 0..files {
+        out.push_str(&format!(
+            "\t\t\t\t{} /* File{i}.swift in Sources */,\n",
+            id(1, i)
+        ));
+    }
+    out.push_str("\t\t\t);\n\t\t\trunOnlyForDeploymentPostprocessing = 0;\n\t\t};\n/* End PBXSourcesBuildPhase section */\n");
+
+    out.push_str(&format!("\t}};\n\trootObject = {};\n}}\n", id(3, 0)));
+    out
+}
+
+fn main() {
+    bench_all("test_data/project", TEST_PROJECT, 50);
+    bench_all("synthetic 1 MB", &synthetic_project(2_300), 10);
+    bench_all("synthetic 10 MB", &synthetic_project(23_000), 3);
+}
@@ -0,0 +1,51 @@
+//! Xcode framework generator/updater for Rust UniFFI libraries.
+//!
+//! The `xcode_uniffi` binary is a thin command line wrapper around this crate.
+//! Build tooling can use the same pieces directly:
+//!
+//! ```no_run
+//! use xcode_uniffi::{CargoPackage, XcodeProject, XcodeProjectOptions};
+//!
+//! let package = CargoPackage::builder("path/to/rust/project").build()?;
+//! let project = XcodeProject::new(package, XcodeProjectOptions::default());
+//! let pbxproj = project.generate_pbx_project();
+//! println!("{}", pbxproj.serialize());
+//! # Ok::<(), xcode_uniffi::XcodeUniffiError>(())
+//! ```
+//!
+//! Existing projects can be read and written with [`XcodePbxProject`]:
+//!
+//! ```no_run
+//! let text = std::fs::read_to_string("App.xcodeproj/project.pbxproj").unwrap();
+//! let project = xcode_uniffi::XcodePbxProject::parse_from_string(&text)?;
+//! std::fs::write("App.xcodeproj/project.pbxproj", project.serialize()).unwrap();
+//! # Ok::<(), xcode_uniffi::XcodeUniffiError>(())
+//! ```
+//!
+//! `serialize` orders sections and objects like Xcode does, `serialize_lossless` keeps them as parsed.
+
+extern crate core;
+extern crate nom;
+
+mod xcodebind_gen;
+
+pub use xcodebind_gen::{
+    BuildSetting, BuildSettingsOptions, CargoPackage, CargoPackageBuilder, CheckStatus,
+    CommandOutput, CommandRunner, Doctor, DoctorCheck, FileSystem, IdAllocator, LintDiagnostic,
+    ManifestEditor, MemoryFileSystem, MergeConflict, MessageFormat, ObjectMatching, ProjectChange,
+    RealFileSystem, Reporter, Result, Severity, SystemCommandRunner, Xcconfig, XcconfigLine,
+    XcodePbxProject, XcodeProject, XcodeProjectOptions, XcodeUniffiError, REQUIRED_RUST_TARGETS,
+    SUPPORTED_UNIFFI_VERSION,
+};
+
+/// Object model of pbxproj (OpenStep plist) files.
+pub mod plist {
+    pub use crate::xcodebind_gen::{
+        InternalXcodePlistSerializer, PlistData, PlistDocument, PlistEntryRef, PlistItem, PlistKey,
+        PlistKeyValueItem, PlistObjects, PlistRef, PlistSectionItem, PlistString, PlistTupleItem,
+        PlistTupleValue, PlistValue, XcodePlistSerializer,
+    };
+    pub use crate::xcodebind_gen::plist_serde::{
+        from_item, from_plist, from_str, to_item, to_plist, to_string, Deserializer, Serializer,
+    };
+}
@@ -1,132 +1,732 @@
-extern crate core;
-extern crate nom;
-
 use std::env;
 use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::rc::Rc;
 
-use crate::xcodebind_gen::{CargoPackage, XcodeProject};
-use clap::{App, AppSettings, Arg};
-use colored::Colorize;
-
-mod xcodebind_gen;
+use clap::{App, AppSettings, Arg, ArgGroup};
+use similar::TextDiff;
+use xcode_uniffi::plist::{PlistItem, PlistRef};
+use xcode_uniffi::{
+    BuildSettingsOptions, CargoPackage, CargoPackageBuilder, CheckStatus, Doctor, ManifestEditor,
+    MemoryFileSystem, MessageFormat, ObjectMatching, Reporter, Severity, XcodePbxProject,
+    XcodeProject, XcodeProjectOptions, XcodeUniffiError,
+};
 
 fn main() {
-    let current_directory = &env::current_dir().unwrap();
-    // println!(
-    //     "current_directory: {:?} - {:?}",
-    //     current_directory,
-    //     fs::canonicalize("/Users/mjhatamy/workspace/mjhatamy/xcode_uniffi_gen/../.")
-    // );
     let matches = clap::App::new(clap::crate_name!())
         .about(clap::crate_description!())
         .author(clap::crate_authors!())
         .version(clap::crate_version!())
         .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::new("message-format")
+                .long("message-format")
+                .value_name("FMT")
+                .global(true)
+                .possible_values(MessageFormat::VALUES)
+                .default_value("human")
+                .help("Output format. `json` prints one JSON event per line (files written, objects added, warnings)\n\
+                and never prompts for input."),
+        )
         .subcommand(
             App::new("create")
                 .about("Create a new Xcode Framework project\nBy default project will be created at:\n\
                 <Rust Project SRC DIR>/xcode/<Rust Package Name>\nIf you want to use a different path, specify the path using --path/-p subcommand\n\
                 It is highly recommended to use a path inside the rust project folder.(Check documentation)")
-                .arg(
-                    Arg::new("path")
-                        .long("path")
-                        .short('p')
-                        .value_name("path")
-                        .help("Default Path is \"<current directory/xcode/\"\nIt is recommended to create xcode project as a sub-folder of rust project.")
-                ).arg(
-                Arg::new("xcode_framework_name")
-                    .long("name")
-                    .short('n')
-                    .value_name("xcode_framework_name")
-                    .help("Name of the Xcode framework. By default the name of the Framework is equal the Cargo package name\n\
-                    If your cargo project, has multiple packages, then you have to specify the package name using -k/--package-name subcommand")
-            )
-                .arg(Arg::new("cargo")
-                    .long("cargo")
-                    .short('r')
-                    .value_name("cargo")
-                    .help("Path of the rust project. Default: current directory"))
-                .arg(
-                    Arg::new("package-name")
-                        .long("package-name")
-                        .short('k')
-                        .value_name("package-name")
-                        .help("Cargo package name. Used if your Cargo.toml file has multiple packages.")
-                ).arg(
-                Arg::new("lib-name")
-                    .long("lib-name")
-                    .short('l')
-                    .value_name("lib-name")
-                    .help("Name of the library [lib] in the cargo Package.\n\
-                    If your Cargo.toml file has multiple packages, you have to specify corresponding package name as well.")
-            )
+                .args(cargo_package_args())
+                .arg(Arg::new("yes")
+                    .long("yes")
+                    .short('y')
+                    .help("Answer yes to every question, e.g. update an existing project without asking."))
+                .arg(Arg::new("no-input")
+                    .long("no-input")
+                    .help("Never prompt for input. Questions take their default answer (no)."))
+                .arg(Arg::new("force")
+                    .long("force")
+                    .short('f')
+                    .help("Overwrite an existing Xcode project instead of updating it."))
+                .arg(Arg::new("fix-manifest")
+                    .long("fix-manifest")
+                    .help("Add \"staticlib\" to crate-type in the [lib] section of Cargo.toml when it is missing.\n\
+                    Without this flag you are asked before Cargo.toml is changed."))
+                .arg(Arg::new("reproducible")
+                    .long("reproducible")
+                    .help("Leave the generation time out of generated files, so regenerating gives identical bytes.\n\
+                    SOURCE_DATE_EPOCH, when set, pins the time instead."))
+                .arg(Arg::new("dry-run")
+                    .long("dry-run")
+                    .help("Print a unified diff of every file that would be written, without touching the filesystem."))
         ).subcommand(
+        App::new("doctor")
+            .about("Check the Rust toolchain, Cargo.toml and an existing Xcode framework project\n\
+            Exits with status 1 when a check fails.")
+            .args(cargo_package_args())
+    ).subcommand(
+        App::new("convert")
+            .about("Convert a project.pbxproj file to JSON (--to json) or a JSON document back to pbxproj text (--from json)\n\
+            The JSON document is the one `plutil -convert json` writes, every value is a string and comments are dropped.")
+            .arg(Arg::new("input")
+                .required(true)
+                .value_name("FILE")
+                .help("File to convert, `-` reads standard input."))
+            .arg(Arg::new("to")
+                .long("to")
+                .value_name("FORMAT")
+                .possible_values(["json"])
+                .help("Read pbxproj text and write FORMAT."))
+            .arg(Arg::new("from")
+                .long("from")
+                .value_name("FORMAT")
+                .possible_values(["json"])
+                .help("Read FORMAT and write pbxproj text."))
+            .group(ArgGroup::new("direction").args(&["to", "from"]).required(true))
+            .arg(Arg::new("output")
+                .long("output")
+                .short('o')
+                .value_name("FILE")
+                .help("Write to FILE instead of standard output."))
+    ).subcommand(
+        App::new("query")
+            .about("Print or edit the values of a project.pbxproj file found at a path\n\
+            A path is a list of `/` separated keys, array indexes or `*`, each optionally followed by `[key=value]` filters,\n\
+            e.g. objects/*[isa=PBXNativeTarget]/name. Keys holding `/`, `[`, `]` or `*` are written in double quotes.")
+            .arg(Arg::new("input")
+                .required(true)
+                .value_name("FILE")
+                .help("project.pbxproj file, `-` reads standard input."))
+            .arg(Arg::new("path")
+                .value_name("PATH")
+                .required_unless_present_any(["set", "remove"])
+                .conflicts_with_all(&["set", "remove"])
+                .help("Print every value at PATH, one per line. Dictionaries and arrays are printed on a single line."))
+            .arg(Arg::new("paths")
+                .long("paths")
+                .help("Print `<path> = <value>` with the path of each value."))
+            .arg(Arg::new("set")
+                .long("set")
+                .number_of_values(2)
+                .value_names(&["PATH", "VALUE"])
+                .multiple_occurrences(true)
+                .help("Set every value at PATH to VALUE, adding the key when missing. VALUE is taken as a string,\n\
+                unless it starts with `\"`, `{`, `(` or `<`: then it is read as plist text, e.g. '(\"$(inherited)\", \"-lz\")'."))
+            .arg(Arg::new("remove")
+                .long("remove")
+                .value_name("PATH")
+                .multiple_occurrences(true)
+                .help("Remove every entry or array element at PATH. Removals run after --set."))
+            .arg(Arg::new("in-place")
+                .long("in-place")
+                .short('i')
+                .conflicts_with("output")
+                .help("Write the edited project back to FILE (standard output for `-`)."))
+            .arg(Arg::new("output")
+                .long("output")
+                .short('o')
+                .value_name("FILE")
+                .help("Write the edited project to FILE instead of standard output."))
+    ).subcommand(
+        App::new("lint")
+            .alias("validate")
+            .about("Check the references between the objects of project.pbxproj files\n\
+            Reports dangling references, objects nothing refers to, source files no build phase compiles,\n\
+            files built twice and targets without a configuration list. Exits with status 1 when an error is found.")
+            .arg(Arg::new("input")
+                .required(true)
+                .multiple_values(true)
+                .value_name("FILE")
+                .help("project.pbxproj files to check, `-` reads standard input."))
+    ).subcommand(
+        App::new("merge")
+            .about("Three-way merge of project.pbxproj files, usable as a git merge driver (`xcode_uniffi merge %O %A %B`)\n\
+            Objects added on either side are kept, dictionaries are merged key by key and arrays such as `files` or `children`\n\
+            as ordered sets. Values changed differently on both sides are reported as conflicts and keep our side;\n\
+            the command then exits with status 1.")
+            .arg(Arg::new("base")
+                .required(true)
+                .value_name("BASE")
+                .help("Common ancestor (%O)."))
+            .arg(Arg::new("ours")
+                .required(true)
+                .value_name("OURS")
+                .help("Our version (%A), overwritten with the result unless --output is given."))
+            .arg(Arg::new("theirs")
+                .required(true)
+                .value_name("THEIRS")
+                .help("Their version (%B)."))
+            .arg(Arg::new("output")
+                .long("output")
+                .short('o')
+                .value_name("FILE")
+                .help("Write the result to FILE, `-` writes to standard output."))
+    ).subcommand(
+        App::new("diff")
+            .about("Compare two project.pbxproj files by meaning rather than text\n\
+            Prints targets, build phases and files added, removed, renamed, reordered or moved between groups, and build settings\n\
+            changed in each configuration. Exits with status 1 when the projects differ.")
+            .arg(Arg::new("old")
+                .required(true)
+                .value_name("OLD")
+                .help("project.pbxproj file to compare from, `-` reads standard input."))
+            .arg(Arg::new("new")
+                .required(true)
+                .value_name("NEW")
+                .help("project.pbxproj file to compare to."))
+            .arg(Arg::new("by-name")
+                .long("by-name")
+                .help("Match objects by name and files by path instead of by ID, e.g. to compare a regenerated project\n\
+                with a hand-edited one."))
+    ).subcommand(
+        App::new("fmt")
+            .about("Rewrite project.pbxproj files in the format Xcode writes\n\
+            Tabs, `/* Begin X section */` markers, build files and file references on a single line, objects sorted by ID\n\
+            and object ID comments derived from the objects.")
+            .arg(Arg::new("input")
+                .required(true)
+                .multiple_values(true)
+                .value_name("FILE")
+                .help("project.pbxproj files to format in place, `-` reads standard input and writes standard output."))
+            .arg(Arg::new("check")
+                .long("check")
+                .help("Don't write anything, print the diff of each file that is not formatted and exit with status 1 if there is one."))
+    ).subcommand(
+        App::new("show-settings")
+            .about("Print the build settings of a target the way `xcodebuild -showBuildSettings` does, without Xcode\n\
+            Project and target settings are layered over their xcconfig files, `$(inherited)` and `$(NAME)` references\n\
+            are expanded and `[sdk=...]` conditions are evaluated. Only settings set in the project are known.")
+            .arg(Arg::new("input")
+                .required(true)
+                .value_name("PROJECT")
+                .help("`.xcodeproj` directory or its project.pbxproj file."))
+            .arg(Arg::new("target")
+                .long("target")
+                .short('t')
+                .value_name("NAME")
+                .help("Target to show the settings of. Without it the settings of the project are shown."))
+            .arg(Arg::new("configuration")
+                .long("configuration")
+                .short('c')
+                .value_name("NAME")
+                .default_value("Debug")
+                .help("Build configuration."))
+            .arg(Arg::new("sdk")
+                .long("sdk")
+                .value_name("SDK")
+                .help("SDK matched by `[sdk=...]` conditions, e.g. iphonesimulator. Defaults to SDKROOT."))
+            .arg(Arg::new("arch")
+                .long("arch")
+                .value_name("ARCH")
+                .help("Architecture matched by `[arch=...]` conditions, e.g. arm64."))
+            .arg(Arg::new("setting")
+                .long("setting")
+                .short('s')
+                .value_name("NAME")
+                .multiple_occurrences(true)
+                .help("Only print this setting, can be repeated."))
+    ).subcommand(
         App::new("update")
-            .about("Updates existing Xcode framework project")
-            .setting(AppSettings::ArgRequiredElseHelp)
+            .about("Update an existing Xcode framework project, e.g. after adding a UDL file\n\
+            Objects for new UDL files are added and missing source files are written. Changes made in Xcode are kept,\n\
+            values that differ from the generated ones are reported as warnings.")
+            .args(cargo_package_args())
+            .arg(Arg::new("dry-run")
+                .long("dry-run")
+                .help("Print a unified diff of every file that would be written, without touching the filesystem."))
+    );
+
+    let matches = matches.get_matches();
+    let message_format = matches
+        .value_of("message-format")
+        .and_then(MessageFormat::from_name)
+        .unwrap_or(MessageFormat::Human);
+    if env::var_os("NO_COLOR").is_some() || message_format == MessageFormat::Json {
+        colored::control::set_override(false);
+    }
+    let reporter = Reporter::new(message_format);
+
+    let result = match matches.subcommand() {
+        Some(("create", sub_matches)) => create(sub_matches, message_format),
+        Some(("doctor", sub_matches)) => doctor(sub_matches, message_format),
+        Some(("convert", sub_matches)) => convert(sub_matches, message_format),
+        Some(("query", sub_matches)) => query(sub_matches, message_format),
+        Some(("lint", sub_matches)) => lint(sub_matches, message_format),
+        Some(("merge", sub_matches)) => merge(sub_matches, message_format),
+        Some(("diff", sub_matches)) => diff(sub_matches, message_format),
+        Some(("fmt", sub_matches)) => fmt(sub_matches, message_format),
+        Some(("show-settings", sub_matches)) => show_settings(sub_matches, message_format),
+        Some(("update", sub_matches)) => update(sub_matches, message_format),
+        other => Err(XcodeUniffiError::UnsupportedCommand(
+            other.map(|(name, _)| name).unwrap_or_default().to_string(),
+        )),
+    };
+    if let Err(e) = result {
+        reporter.failure(&e);
+        exit(1);
+    }
+}
+
+fn canonicalize(path: &str) -> xcode_uniffi::Result<PathBuf> {
+    fs::canonicalize(path).map_err(|e| xcode_uniffi::XcodeUniffiError::Io {
+        path: PathBuf::from(path),
+        source: e,
+    })
+}
+
+/// Arguments locating the Rust package and the Xcode project, shared by `create` and `doctor`.
+fn cargo_package_args() -> Vec<Arg<'static>> {
+    vec![
+        Arg::new("path")
+            .long("path")
+            .short('p')
+            .value_name("path")
+            .help("Default Path is \"<current directory/xcode/\"\nIt is recommended to create xcode project as a sub-folder of rust project."),
+        Arg::new("xcode_framework_name")
+            .long("name")
+            .short('n')
+            .value_name("xcode_framework_name")
+            .help("Name of the Xcode framework. By default the name of the Framework is equal the Cargo package name\n\
+            If your cargo project, has multiple packages, then you have to specify the package name using -k/--package-name subcommand"),
+        Arg::new("cargo")
+            .long("cargo")
+            .short('r')
+            .value_name("cargo")
+            .help("Path of the rust project. Default: current directory"),
+        Arg::new("package-name")
+            .long("package-name")
+            .short('k')
+            .value_name("package-name")
+            .help("Cargo package name. Used if your Cargo.toml file has multiple packages."),
+        Arg::new("lib-name")
+            .long("lib-name")
+            .short('l')
+            .value_name("lib-name")
+            .help("Name of the library [lib] in the cargo Package.\n\
+            If your Cargo.toml file has multiple packages, you have to specify corresponding package name as well."),
+    ]
+}
+
+fn cargo_package_builder(
+    sub_matches: &clap::ArgMatches,
+) -> xcode_uniffi::Result<CargoPackageBuilder> {
+    let cargo_path = match sub_matches.value_of("cargo") {
+        Some(path) => canonicalize(path)?,
+        None => env::current_dir().map_err(|e| xcode_uniffi::XcodeUniffiError::Io {
+            path: PathBuf::from("."),
+            source: e,
+        })?,
+    };
+    let mut builder = CargoPackage::builder(cargo_path);
+    if let Some(path) = sub_matches.value_of("path") {
+        builder = builder.framework_path(canonicalize(path)?);
+    }
+    if let Some(name) = sub_matches.value_of("package-name") {
+        builder = builder.package_name(name);
+    }
+    if let Some(name) = sub_matches.value_of("lib-name") {
+        builder = builder.lib_name(name);
+    }
+    if let Some(name) = sub_matches.value_of("xcode_framework_name") {
+        builder = builder.framework_name(name);
+    }
+    Ok(builder)
+}
+
+fn create(
+    sub_matches: &clap::ArgMatches,
+    message_format: MessageFormat,
+) -> xcode_uniffi::Result<()> {
+    let reporter = Reporter::new(message_format);
+    let builder = cargo_package_builder(sub_matches)?;
+    let options = XcodeProjectOptions::default()
+        .assume_yes(sub_matches.is_present("yes"))
+        .interactive(!sub_matches.is_present("no-input") && !reporter.is_json())
+        .force(sub_matches.is_present("force"))
+        .dry_run(sub_matches.is_present("dry-run"))
+        .reproducible(sub_matches.is_present("reproducible"))
+        .source_date_epoch(source_date_epoch())
+        .message_format(message_format);
+
+    let cargo = match builder.clone().build() {
+        Err(XcodeUniffiError::MissingStaticlib { manifest_path, .. })
+            if !sub_matches.is_present("dry-run")
+                && (sub_matches.is_present("fix-manifest")
+                    || options.confirm("Cargo.toml does not build a static library. Add \"staticlib\" to crate-type")) =>
+        {
+            if ManifestEditor::add_staticlib_crate_type(&manifest_path)? {
+                reporter.file_written(&manifest_path);
+            }
+            builder.build()?
+        }
+        result => result?,
+    };
+    for warning in cargo.check_uniffi_dependencies() {
+        reporter.warning(warning.as_str());
+    }
+
+    if sub_matches.is_present("dry-run") {
+        dry_run(&reporter, |fs| {
+            XcodeProject::with_file_system(cargo, options, fs).create()?;
+            Ok(())
+        })?;
+    } else {
+        XcodeProject::new(cargo, options).create()?;
+    }
+    Ok(())
+}
+
+fn update(
+    sub_matches: &clap::ArgMatches,
+    message_format: MessageFormat,
+) -> xcode_uniffi::Result<()> {
+    let reporter = Reporter::new(message_format);
+    let cargo = cargo_package_builder(sub_matches)?.build()?;
+    let options = XcodeProjectOptions::default()
+        .dry_run(sub_matches.is_present("dry-run"))
+        .source_date_epoch(source_date_epoch())
+        .message_format(message_format);
+    for warning in cargo.check_uniffi_dependencies() {
+        reporter.warning(warning.as_str());
+    }
+
+    if sub_matches.is_present("dry-run") {
+        dry_run(&reporter, |fs| {
+            XcodeProject::with_file_system(cargo, options, fs).update()?;
+            Ok(())
+        })?;
+    } else {
+        XcodeProject::new(cargo, options).update()?;
+    }
+    Ok(())
+}
+
+/// Runs `generate` against an in-memory copy of the disk and reports the diff of each file it
+/// would change.
+fn dry_run(
+    reporter: &Reporter,
+    generate: impl FnOnce(Rc<MemoryFileSystem>) -> xcode_uniffi::Result<()>,
+) -> xcode_uniffi::Result<()> {
+    let fs = Rc::new(MemoryFileSystem::overlay());
+    generate(fs.clone())?;
+    let diffs = fs.diff_against_disk();
+    for (path, diff) in &diffs {
+        reporter.file_diff(path, diff);
+    }
+    reporter.info(
+        format!(
+            "Dry run: {} of {} files would change",
+            diffs.len(),
+            fs.written_files().len()
+        )
+        .as_str(),
     );
+    Ok(())
+}
+
+fn doctor(
+    sub_matches: &clap::ArgMatches,
+    message_format: MessageFormat,
+) -> xcode_uniffi::Result<()> {
+    let reporter = Reporter::new(message_format);
+    let checks = Doctor::with_system().run(cargo_package_builder(sub_matches)?);
+    for check in &checks {
+        reporter.doctor_check(check);
+    }
+    if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn convert(
+    sub_matches: &clap::ArgMatches,
+    message_format: MessageFormat,
+) -> xcode_uniffi::Result<()> {
+    let content = read_input(sub_matches.value_of("input").unwrap_or("-"))?;
+
+    let output = if sub_matches.is_present("to") {
+        let json = XcodePbxProject::parse_from_string(&content)?.to_json()?;
+        let mut text = serde_json::to_string_pretty(&json)
+            .map_err(|e| XcodeUniffiError::Convert(e.to_string()))?;
+        text.push('\n');
+        text
+    } else {
+        let json: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| XcodeUniffiError::Convert(format!("invalid JSON: {}", e)))?;
+        XcodePbxProject::from_json(&json)?.serialize()
+    };
+
+    write_output(
+        sub_matches.value_of("output"),
+        &output,
+        &Reporter::new(message_format),
+    )
+}
 
-    match matches.get_matches().subcommand() {
-        Some(("create", sub_matches)) => {
-            let cargo_manifest_path = sub_matches.value_of("cargo").map_or_else(
-                || current_directory.join("Cargo.toml").as_path().to_owned(),
-                |f| {
-                    let path = fs::canonicalize(f)
-                        .expect("Unable to get path from path command");
-                    if path.ends_with("Cargo.toml") {
-                        path
-                    } else {
-                        path.join("Cargo.toml")
-                    }
-                },
-            );
-
-            let xcode_framework_path = match sub_matches.value_of("path") {
-                Some(path) => fs::canonicalize(path)
-                    .expect("Unable to get path from path command"),
-                None => {
-                    if cargo_manifest_path.ends_with("Cargo.toml") {
-                        match cargo_manifest_path.parent() {
-                            Some(parent_path) => parent_path.join("xcode"),
-                            None => {
-                                eprintln!(
-                                    "Unable to detect Xcode framework path.\n\
-                                It may be due to an invalid Rust(Cargo) project/Cargo.toml Path"
-                                );
-                                exit(1);
-                            }
-                        }
-                    } else {
-                        cargo_manifest_path.join("xcode").as_path().to_path_buf()
-                    }
-                }
+fn query(
+    sub_matches: &clap::ArgMatches,
+    message_format: MessageFormat,
+) -> xcode_uniffi::Result<()> {
+    let input = sub_matches.value_of("input").unwrap_or("-");
+    let mut project = XcodePbxProject::parse_from_string(&read_input(input)?)?;
+
+    if let Some(path) = sub_matches.value_of("path") {
+        for (value_path, value) in project.query(path)? {
+            let value = match value.as_str() {
+                Some(value) => value.to_string(),
+                None => value.into_value().serialize_single_line(),
             };
+            if sub_matches.is_present("paths") {
+                println!("{} = {}", value_path, value);
+            } else {
+                println!("{}", value);
+            }
+        }
+        return Ok(());
+    }
 
-            let package_name = sub_matches.value_of("package-name").map(str::to_string);
-            let lib_name = sub_matches.value_of("lib-name").map(str::to_string);
-            let xcode_framework_name = sub_matches
-                .value_of("xcode_framework_name")
-                .map(str::to_string);
-
-            if !cargo_manifest_path.exists() {
-                eprintln!(
-                    "{} {:?}",
-                    "No Cargo.toml file found at:".red(),
-                    cargo_manifest_path
-                );
-                exit(1);
+    let output = if sub_matches.is_present("in-place") {
+        Some(input).filter(|input| *input != "-")
+    } else {
+        sub_matches.value_of("output")
+    };
+    let reporter = Reporter::new(message_format).to_stderr(output.is_none());
+    let sets: Vec<&str> = sub_matches.values_of("set").into_iter().flatten().collect();
+    for set in sets.chunks(2) {
+        if project.set(set[0], parse_value(set[1])?)? == 0 {
+            reporter.warning(format!("Nothing matches {}", set[0]).as_str());
+        }
+    }
+    for path in sub_matches.values_of("remove").into_iter().flatten() {
+        if project.remove(path)? == 0 {
+            reporter.warning(format!("Nothing matches {}", path).as_str());
+        }
+    }
+    write_output(output, &project.serialize(), &reporter)
+}
+
+fn lint(sub_matches: &clap::ArgMatches, message_format: MessageFormat) -> xcode_uniffi::Result<()> {
+    let reporter = Reporter::new(message_format);
+    let mut failed = false;
+    let (mut errors, mut warnings) = (0, 0);
+    for input in sub_matches.values_of("input").into_iter().flatten() {
+        let project = match read_input(input)
+            .and_then(|content| XcodePbxProject::parse_from_string(&content))
+        {
+            Ok(project) => project,
+            Err(e) => {
+                reporter.failure(&e);
+                failed = true;
+                continue;
+            }
+        };
+        for diagnostic in project.lint() {
+            match diagnostic.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+            reporter.lint_diagnostic(Path::new(input), &diagnostic);
+        }
+    }
+    reporter.info(format!("{} errors, {} warnings", errors, warnings).as_str());
+    if failed || errors > 0 {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn merge(
+    sub_matches: &clap::ArgMatches,
+    message_format: MessageFormat,
+) -> xcode_uniffi::Result<()> {
+    let read = |name: &str| {
+        let input = sub_matches.value_of(name).unwrap_or("-");
+        XcodePbxProject::parse_from_string(&read_input(input)?)
+    };
+    let (base, ours, theirs) = (read("base")?, read("ours")?, read("theirs")?);
+    let (merged, conflicts) = XcodePbxProject::merge(&base, &ours, &theirs);
+
+    let output = match sub_matches.value_of("output") {
+        Some("-") => None,
+        Some(output) => Some(output),
+        None => sub_matches.value_of("ours"),
+    };
+    let reporter = Reporter::new(message_format).to_stderr(output.is_none());
+    write_output(output, &merged.serialize(), &reporter)?;
+    for conflict in &conflicts {
+        reporter.merge_conflict(conflict);
+    }
+    if !conflicts.is_empty() {
+        reporter.error(format!("{}, our side was kept", count(conflicts.len(), "conflict")).as_str());
+        exit(1);
+    }
+    Ok(())
+}
+
+fn diff(sub_matches: &clap::ArgMatches, message_format: MessageFormat) -> xcode_uniffi::Result<()> {
+    let read = |name: &str| {
+        let input = sub_matches.value_of(name).unwrap_or("-");
+        XcodePbxProject::parse_from_string(&read_input(input)?)
+    };
+    let (old, new) = (read("old")?, read("new")?);
+    let matching = if sub_matches.is_present("by-name") {
+        ObjectMatching::Name
+    } else {
+        ObjectMatching::Id
+    };
+
+    let reporter = Reporter::new(message_format);
+    let changes = old.diff(&new, matching);
+    for change in &changes {
+        reporter.project_change(change);
+    }
+    if !changes.is_empty() {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn fmt(sub_matches: &clap::ArgMatches, message_format: MessageFormat) -> xcode_uniffi::Result<()> {
+    let check = sub_matches.is_present("check");
+    let mut inputs = sub_matches.values_of("input").into_iter().flatten();
+    // formatted standard input goes to stdout, keep the events out of it
+    let reporter = Reporter::new(message_format).to_stderr(inputs.any(|i| i == "-"));
+    let (mut failed, mut unformatted) = (false, 0);
+    for input in sub_matches.values_of("input").into_iter().flatten() {
+        let formatted = read_input(input).and_then(|content| {
+            let formatted = XcodePbxProject::parse_from_string(&content)?.serialize();
+            Ok((content, formatted))
+        });
+        let (content, formatted) = match formatted {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                reporter.failure(&e);
+                failed = true;
+                continue;
             }
-            let cargo = CargoPackage::new(
-                &cargo_manifest_path,
-                package_name,
-                lib_name,
-                xcode_framework_name,
-                &xcode_framework_path,
-            );
-            XcodeProject::new(cargo).create();
-            //println!("sub command create detected {:?}", cargo_manifest_path);
+        };
+        if check {
+            if content != formatted {
+                unformatted += 1;
+                let separator = if input.starts_with('/') { "" } else { "/" };
+                let diff = TextDiff::from_lines(&content, &formatted)
+                    .unified_diff()
+                    .header(
+                        &format!("a{}{}", separator, input),
+                        &format!("b{}{}", separator, input),
+                    )
+                    .to_string();
+                reporter.file_diff(Path::new(input), &diff);
+            }
+        } else if input == "-" {
+            print!("{}", formatted);
+        } else if content != formatted {
+            write_output(Some(input), &formatted, &reporter)?;
+        }
+    }
+    if unformatted > 0 {
+        reporter.error(format!("{} not formatted", count(unformatted, "file")).as_str());
+    }
+    if failed || unformatted > 0 {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn show_settings(
+    sub_matches: &clap::ArgMatches,
+    message_format: MessageFormat,
+) -> xcode_uniffi::Result<()> {
+    // paths in the settings are absolute, as xcodebuild shows them
+    let input = canonicalize(sub_matches.value_of("input").unwrap_or("."))?;
+    let (xcodeproj, pbxproj) = if input.is_dir() {
+        (input.clone(), input.join("project.pbxproj"))
+    } else {
+        (input.parent().unwrap_or(&input).to_path_buf(), input.clone())
+    };
+    let project = XcodePbxProject::parse_from_string(&read_input(&pbxproj.to_string_lossy())?)?;
+
+    let configuration = sub_matches.value_of("configuration").unwrap_or("Debug");
+    let mut options = BuildSettingsOptions::new(configuration)
+        .xcodeproj(&xcodeproj)
+        .message_format(message_format);
+    if let Some(target) = sub_matches.value_of("target") {
+        options = options.target(target);
+    }
+    if let Some(sdk) = sub_matches.value_of("sdk") {
+        options = options.sdk(sdk);
+    }
+    if let Some(arch) = sub_matches.value_of("arch") {
+        options = options.arch(arch);
+    }
+    for name in sub_matches.values_of("setting").unwrap_or_default() {
+        options = options.setting(name);
+    }
+    let settings = project.build_settings(&options)?;
+
+    Reporter::new(message_format).build_settings(
+        sub_matches.value_of("target"),
+        configuration,
+        &settings,
+    );
+    Ok(())
+}
+
+/// A `--set` value: plist text when it starts like a quoted string, dictionary, array or data,
+/// a plain string otherwise.
+fn parse_value(text: &str) -> xcode_uniffi::Result<PlistItem> {
+    if text.starts_with(['"', '{', '(', '<']) {
+        Ok(PlistRef::parse(text)?.into_value())
+    } else {
+        Ok(PlistRef::String(text.into(), None).into_value())
+    }
+}
+
+/// `SOURCE_DATE_EPOCH`, the time reproducible builds write instead of the current one.
+fn source_date_epoch() -> Option<i64> {
+    env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse().ok())
+}
+
+/// `n` followed by `noun`, in the plural unless `n` is 1.
+fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
+
+/// Content of `input`, `-` reads standard input.
+fn read_input(input: &str) -> xcode_uniffi::Result<String> {
+    let content = if input == "-" {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(input)
+    };
+    content.map_err(|e| XcodeUniffiError::Io {
+        path: PathBuf::from(input),
+        source: e,
+    })
+}
+
+/// Writes `content` to `output`, or to standard output when there is none.
+fn write_output(
+    output: Option<&str>,
+    content: &str,
+    reporter: &Reporter,
+) -> xcode_uniffi::Result<()> {
+    match output {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            fs::write(&path, content).map_err(|e| XcodeUniffiError::Io {
+                path: path.clone(),
+                source: e,
+            })?;
+            reporter.file_written(&path);
         }
-        _ => unreachable!(),
+        None => print!("{}", content),
     }
+    Ok(())
 }
@@ -0,0 +1,865 @@
+//! Offline evaluation of the build settings of a target, like `xcodebuild -showBuildSettings`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::xcconfig::{read_xcconfig, setting_value};
+use super::{
+    BuildSetting, FileSystem, MessageFormat, RealFileSystem, Reporter, Result, XcodePbxProject,
+    XcodeUniffiError,
+};
+use crate::xcodebind_gen::types::*;
+
+/// Groups nested deeper than this are taken for a cycle.
+const MAX_GROUP_DEPTH: usize = 32;
+
+/// Target, configuration and SDK `XcodePbxProject::build_settings` evaluates the settings for.
+///
+/// ```
+/// let options = xcode_uniffi::BuildSettingsOptions::new("Debug")
+///     .target("MyLib_ios")
+///     .sdk("iphonesimulator")
+///     .xcodeproj("xcode/MyLib/MyLib.xcodeproj");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BuildSettingsOptions {
+    pub(crate) configuration: String,
+    pub(crate) target: Option<String>,
+    pub(crate) sdk: Option<String>,
+    pub(crate) arch: Option<String>,
+    pub(crate) xcodeproj: PathBuf,
+    pub(crate) settings: Vec<String>,
+    pub(crate) reporter: Reporter,
+}
+
+impl BuildSettingsOptions {
+    /// Settings of the project itself in `configuration`, until a target is given.
+    pub fn new(configuration: &str) -> Self {
+        BuildSettingsOptions {
+            configuration: configuration.to_string(),
+            target: None,
+            sdk: None,
+            arch: None,
+            xcodeproj: PathBuf::from("Project.xcodeproj"),
+            settings: vec![],
+            reporter: Reporter::new(MessageFormat::Human),
+        }
+    }
+
+    pub fn target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    /// SDK matched by `[sdk=...]` conditions, e.g. `iphonesimulator`. Defaults to `SDKROOT`.
+    pub fn sdk(mut self, sdk: &str) -> Self {
+        self.sdk = Some(sdk.to_string());
+        self
+    }
+
+    /// Architecture matched by `[arch=...]` conditions. Without one they never match.
+    pub fn arch(mut self, arch: &str) -> Self {
+        self.arch = Some(arch.to_string());
+        self
+    }
+
+    /// Path of the `.xcodeproj` directory: `PROJECT_NAME` is its name, `SRCROOT` its parent
+    /// directory, and xcconfig files are found relative to it.
+    pub fn xcodeproj(mut self, xcodeproj: impl AsRef<Path>) -> Self {
+        self.xcodeproj = xcodeproj.as_ref().to_path_buf();
+        self
+    }
+
+    /// Evaluates only `name` and the settings it refers to, can be given several times.
+    /// Without it every setting of the project is evaluated.
+    pub fn setting(mut self, name: &str) -> Self {
+        self.settings.push(name.to_string());
+        self
+    }
+
+    /// Format of the warnings about unreadable xcconfig files and settings referring to
+    /// themselves.
+    pub fn message_format(mut self, format: MessageFormat) -> Self {
+        self.reporter = Reporter::new(format);
+        self
+    }
+}
+
+fn string<'a>(object: &'a PlistRef, key: &str) -> Option<&'a str> {
+    object.get(key).and_then(PlistRef::as_str)
+}
+
+/// `*` matching any run of characters, the only wildcard of setting conditions.
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == value,
+        Some((prefix, rest)) => value.strip_prefix(prefix).is_some_and(|value| {
+            (0..=value.len())
+                .filter(|&index| value.is_char_boundary(index))
+                .any(|index| matches_pattern(rest, &value[index..]))
+        }),
+    }
+}
+
+/// Applies a `$(NAME:operator)` operator, unknown operators leave the value as it is.
+fn apply_operator(value: String, operator: &str) -> String {
+    let identifier = |value: &str, keep: &dyn Fn(char) -> bool, replacement: char| -> String {
+        let mut identifier: String = value
+            .chars()
+            .map(|c| if keep(c) { c } else { replacement })
+            .collect();
+        if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+            identifier.insert(0, '_');
+        }
+        identifier
+    };
+    let path = Path::new(&value);
+    match operator.split_once('=') {
+        Some(("default", default)) if value.is_empty() => return default.to_string(),
+        Some(_) => return value,
+        None => {}
+    }
+    match operator {
+        "lower" => value.to_lowercase(),
+        "upper" => value.to_uppercase(),
+        "c99extidentifier" | "identifier" => {
+            identifier(&value, &|c| c.is_alphanumeric() || c == '_', '_')
+        }
+        "rfc1034identifier" => value
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect(),
+        "dir" => match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => format!("{}/", parent.display()),
+            _ => "./".to_string(),
+        },
+        "file" => path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        "base" => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        "suffix" => path
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default(),
+        _ => value,
+    }
+}
+
+/// Settings of every level, lowest first: built-in settings, then the xcconfig and the
+/// `buildSettings` of the project configuration, then the ones of the target configuration.
+struct Evaluator {
+    levels: Vec<Vec<BuildSetting>>,
+    conditions: HashMap<&'static str, String>,
+    /// Value of every setting evaluated so far, by name and level.
+    values: HashMap<(String, usize), String>,
+    /// Settings being evaluated, a setting found here again refers to itself.
+    evaluating: HashSet<(String, usize)>,
+    /// Settings found to refer to themselves, they evaluate to an empty string.
+    cycles: Vec<String>,
+}
+
+impl Evaluator {
+    /// Value of the most specific assignment of `name` at `level` whose conditions hold, the
+    /// last one when several are as specific.
+    fn assignment(&self, level: usize, name: &str) -> Option<&str> {
+        self.levels[level]
+            .iter()
+            .filter(|setting| setting.name == name)
+            .filter(|setting| {
+                setting.conditions.iter().all(|(variable, pattern)| {
+                    self.conditions
+                        .get(variable.as_str())
+                        .is_some_and(|value| matches_pattern(pattern, value))
+                })
+            })
+            .max_by_key(|setting| setting.conditions.len())
+            .map(|setting| setting.value.as_str())
+    }
+
+    /// Value of `name` from the levels below `level`.
+    fn value(&mut self, name: &str, level: usize) -> String {
+        let key = (name.to_string(), level);
+        if let Some(value) = self.values.get(&key) {
+            return value.clone();
+        }
+        if !self.evaluating.insert(key.clone()) {
+            if !self.cycles.iter().any(|cycle| cycle == name) {
+                self.cycles.push(name.to_string());
+            }
+            return String::new();
+        }
+        let assignment = (0..level)
+            .rev()
+            .find_map(|level| Some((level, self.assignment(level, name)?.to_string())));
+        let value = match assignment {
+            Some((level, value)) => self.expand(&value, name, level),
+            None => String::new(),
+        };
+        self.evaluating.remove(&key);
+        self.values.insert(key, value.clone());
+        value
+    }
+
+    /// Expands the `$(NAME)` and `${NAME}` references of the value `name` has at `level`.
+    /// `$(inherited)`, or `name` itself, is the value from the levels below.
+    fn expand(&mut self, value: &str, name: &str, level: usize) -> String {
+        let mut expanded = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find('$') {
+            expanded.push_str(&rest[..start]);
+            let reference = &rest[start + 1..];
+            let close = match reference.chars().next() {
+                Some('(') => ')',
+                Some('{') => '}',
+                _ => {
+                    expanded.push('$');
+                    rest = reference;
+                    continue;
+                }
+            };
+            let end = match closing(reference, close) {
+                Some(end) => end,
+                None => {
+                    expanded.push_str(&rest[start..]);
+                    rest = "";
+                    break;
+                }
+            };
+            // `$(FOO_$(CONFIGURATION))`: references in the name first
+            let inner = self.expand(&reference[1..end], name, level);
+            let mut parts = inner.split(':');
+            let variable = parts.next().unwrap_or_default();
+            let mut value = if variable == "inherited" || variable == name {
+                self.value(name, level)
+            } else {
+                let top = self.levels.len();
+                self.value(variable, top)
+            };
+            for operator in parts {
+                value = apply_operator(value, operator);
+            }
+            expanded.push_str(&value);
+            rest = &reference[end + 1..];
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+}
+
+/// Index of the `close` matching the opening character of `reference`.
+fn closing(reference: &str, close: char) -> Option<usize> {
+    let open = reference.chars().next()?;
+    let mut depth = 0;
+    for (index, c) in reference.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(index);
+            }
+        }
+    }
+    None
+}
+
+/// Objects of a project, with the parent of every group child.
+struct Objects<'b, 'a> {
+    objects: HashMap<&'b str, &'b PlistRef<'a>>,
+    parents: HashMap<&'b str, &'b str>,
+}
+
+impl<'b, 'a> Objects<'b, 'a> {
+    fn new(root: &'b PlistRef<'a>) -> Self {
+        let objects: HashMap<&str, &PlistRef> = root
+            .get("objects")
+            .and_then(PlistRef::as_dictionary)
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| (entry.key.as_ref(), &entry.value))
+            .collect();
+        let mut parents = HashMap::new();
+        for (&id, object) in &objects {
+            let children = object.get("children").and_then(PlistRef::as_array);
+            for child in children
+                .unwrap_or_default()
+                .iter()
+                .filter_map(PlistRef::as_str)
+            {
+                parents.insert(child, id);
+            }
+        }
+        Objects { objects, parents }
+    }
+
+    fn get(&self, id: &str) -> Option<&'b PlistRef<'a>> {
+        self.objects.get(id).copied()
+    }
+
+    /// Path of a file or group relative to the project directory, `None` when it is relative
+    /// to a build directory or the SDK.
+    fn path(&self, id: &str, depth: usize) -> Option<PathBuf> {
+        let object = self.get(id)?;
+        let path = PathBuf::from(string(object, "path").unwrap_or_default());
+        match string(object, "sourceTree").unwrap_or("<group>") {
+            "<absolute>" => Some(path),
+            "SOURCE_ROOT" => Some(path),
+            "<group>" if depth < MAX_GROUP_DEPTH => match self.parents.get(id) {
+                Some(parent) => Some(self.path(parent, depth + 1)?.join(path)),
+                None => Some(path),
+            },
+            _ => None,
+        }
+    }
+
+    /// `buildSettings` and xcconfig settings of the configuration named `name` in the
+    /// configuration list of `owner`.
+    fn configuration_levels(
+        &self,
+        owner: &PlistRef,
+        name: &str,
+        project_dir: &Path,
+        file_system: &dyn FileSystem,
+        reporter: &Reporter,
+        described: &str,
+    ) -> Result<[Vec<BuildSetting>; 2]> {
+        let configurations: Vec<&PlistRef> = string(owner, "buildConfigurationList")
+            .and_then(|list| self.get(list))
+            .and_then(|list| list.get("buildConfigurations"))
+            .and_then(PlistRef::as_array)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| id.as_str().and_then(|id| self.get(id)))
+            .collect();
+        let configuration = configurations
+            .iter()
+            .find(|configuration| string(configuration, "name") == Some(name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = configurations
+                    .iter()
+                    .filter_map(|configuration| string(configuration, "name"))
+                    .collect();
+                XcodeUniffiError::BuildSettings(format!(
+                    "{} has no configuration named '{}', it has: {}",
+                    described,
+                    name,
+                    names.join(", ")
+                ))
+            })?;
+
+        let xcconfig = match string(configuration, "baseConfigurationReference") {
+            Some(reference) => {
+                let path = self.path(reference, 0).ok_or_else(|| {
+                    XcodeUniffiError::BuildSettings(format!(
+                        "the xcconfig file {} of {} is not relative to the project",
+                        reference, described
+                    ))
+                })?;
+                // xcodebuild goes on without the settings of a missing file, e.g. one CocoaPods
+                // has not generated yet
+                match read_xcconfig(file_system, &project_dir.join(path)) {
+                    Err(error @ XcodeUniffiError::Io { .. }) => {
+                        reporter.warning(
+                            format!("{}, the settings of {} ignore it", error, described).as_str(),
+                        );
+                        vec![]
+                    }
+                    read => read?,
+                }
+            }
+            None => vec![],
+        };
+        let settings = configuration
+            .get("buildSettings")
+            .and_then(PlistRef::as_dictionary)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|entry| {
+                let (name, conditions) = BuildSetting::parse_key(&entry.key)?;
+                Some(BuildSetting {
+                    name,
+                    conditions,
+                    value: setting_value(&entry.value),
+                })
+            })
+            .collect();
+        Ok([xcconfig, settings])
+    }
+}
+
+impl XcodePbxProject {
+    /// Effective build settings of the target and configuration of `options`, like
+    /// `xcodebuild -showBuildSettings` without Xcode: project settings, then target settings,
+    /// each over the xcconfig file of its `baseConfigurationReference`, with `$(inherited)`,
+    /// `$(NAME)` references, `:lower`/`:c99extidentifier`/... operators and `[sdk=...]`,
+    /// `[arch=...]` and `[config=...]` conditions evaluated.
+    ///
+    /// Only the settings set in the project or its xcconfig files are known, along with
+    /// `PROJECT_NAME`, `SRCROOT`, `TARGET_NAME`, `CONFIGURATION`, `PLATFORM_NAME` and a few
+    /// other built-in ones. Xcconfig files are read from disk, one that can't be read is
+    /// reported as a warning and left out. Settings referring to themselves are reported as a
+    /// warning too and evaluate to an empty string.
+    pub fn build_settings(
+        &self,
+        options: &BuildSettingsOptions,
+    ) -> Result<BTreeMap<String, String>> {
+        self.build_settings_with_file_system(options, &RealFileSystem {})
+    }
+
+    /// `build_settings` reading xcconfig files from `file_system`.
+    pub fn build_settings_with_file_system(
+        &self,
+        options: &BuildSettingsOptions,
+        file_system: &dyn FileSystem,
+    ) -> Result<BTreeMap<String, String>> {
+        let root = PlistRef::Dictionary(PlistEntryRef::from_items(self.items()));
+        let objects = Objects::new(&root);
+        let project = root
+            .get("rootObject")
+            .and_then(PlistRef::as_str)
+            .and_then(|id| objects.get(id))
+            .ok_or_else(|| {
+                XcodeUniffiError::BuildSettings("the project has no rootObject".to_string())
+            })?;
+        let mut project_dir = options
+            .xcodeproj
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .to_path_buf();
+        if let Some(directory) = string(project, "projectDirPath").filter(|d| !d.is_empty()) {
+            project_dir = project_dir.join(directory);
+        }
+
+        let mut levels = vec![vec![]];
+        levels.extend(objects.configuration_levels(
+            project,
+            &options.configuration,
+            &project_dir,
+            file_system,
+            &options.reporter,
+            "the project",
+        )?);
+        if let Some(name) = &options.target {
+            let targets: Vec<&PlistRef> = project
+                .get("targets")
+                .and_then(PlistRef::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|id| id.as_str().and_then(|id| objects.get(id)))
+                .collect();
+            let target = targets
+                .iter()
+                .find(|target| string(target, "name") == Some(name))
+                .ok_or_else(|| {
+                    let names: Vec<&str> = targets
+                        .iter()
+                        .filter_map(|target| string(target, "name"))
+                        .collect();
+                    XcodeUniffiError::BuildSettings(format!(
+                        "no target named '{}', the project has: {}",
+                        name,
+                        names.join(", ")
+                    ))
+                })?;
+            levels.extend(objects.configuration_levels(
+                target,
+                &options.configuration,
+                &project_dir,
+                file_system,
+                &options.reporter,
+                &format!("target '{}'", name),
+            )?);
+        }
+
+        let mut evaluator = Evaluator {
+            levels,
+            conditions: HashMap::new(),
+            values: HashMap::new(),
+            evaluating: HashSet::new(),
+            cycles: vec![],
+        };
+        evaluator
+            .conditions
+            .insert("config", options.configuration.clone());
+        let sdk = match &options.sdk {
+            Some(sdk) => sdk.clone(),
+            None => evaluator.value("SDKROOT", evaluator.levels.len()),
+        };
+        if let Some(arch) = &options.arch {
+            evaluator.conditions.insert("arch", arch.clone());
+        }
+
+        let project_name = options
+            .xcodeproj
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let project_dir = project_dir.display().to_string();
+        let mut built_in = vec![
+            ("ACTION", "build".to_string()),
+            ("CONFIGURATION", options.configuration.clone()),
+            ("PROJECT_DIR", project_dir.clone()),
+            ("PROJECT_FILE_PATH", options.xcodeproj.display().to_string()),
+            ("PROJECT_NAME", project_name),
+            ("SOURCE_ROOT", project_dir.clone()),
+            ("SRCROOT", project_dir),
+        ];
+        if !sdk.is_empty() {
+            let platform = sdk.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+            let effective_platform = match platform {
+                "macosx" => String::new(),
+                platform => format!("-{}", platform),
+            };
+            built_in.push(("EFFECTIVE_PLATFORM_NAME", effective_platform));
+            built_in.push(("PLATFORM_NAME", platform.to_string()));
+            built_in.push(("SDK_NAME", sdk.clone()));
+            evaluator.conditions.insert("sdk", sdk);
+        }
+        if let Some(arch) = &options.arch {
+            built_in.push(("CURRENT_ARCH", arch.clone()));
+        }
+        if let Some(target) = &options.target {
+            built_in.push(("TARGET_NAME", target.clone()));
+        }
+        evaluator.levels[0] = built_in
+            .into_iter()
+            .map(|(name, value)| BuildSetting {
+                name: name.to_string(),
+                conditions: vec![],
+                value,
+            })
+            .collect();
+        // `SDKROOT` was evaluated without the built-in settings and the `sdk` condition
+        evaluator.values.clear();
+        evaluator.cycles.clear();
+
+        let names: Vec<String> = if options.settings.is_empty() {
+            evaluator
+                .levels
+                .iter()
+                .flatten()
+                .map(|setting| setting.name.clone())
+                .collect()
+        } else {
+            options.settings.clone()
+        };
+        let top = evaluator.levels.len();
+        let settings = names
+            .into_iter()
+            .map(|name| {
+                // an empty `$(inherited)` leaves a space behind
+                let value = evaluator.value(&name, top).trim().to_string();
+                (name, value)
+            })
+            .collect();
+        if !evaluator.cycles.is_empty() {
+            options.reporter.warning(
+                format!(
+                    "{} refer to themselves and are left empty",
+                    evaluator.cycles.join(", ")
+                )
+                .as_str(),
+            );
+        }
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xcodebind_gen::{CargoPackage, MemoryFileSystem, XcodeProject, XcodeProjectOptions};
+    use std::rc::Rc;
+
+    /// Project and `App` target with a Debug and a Release configuration, the Debug ones based
+    /// on xcconfig files in the `Config` group. `PROJECT` and `TARGET` are replaced by the
+    /// `buildSettings` of the project and target configurations.
+    const OBJECTS: &str = "\
+        G = {isa = PBXGroup; children = (C); sourceTree = \"<group>\"; };
+        C = {isa = PBXGroup; children = (PX, TX); path = Config; sourceTree = \"<group>\"; };
+        P = {isa = PBXProject; buildConfigurationList = PL; mainGroup = G; targets = (T); };
+        PD = {isa = XCBuildConfiguration; baseConfigurationReference = PX; buildSettings = {PROJECT}; name = Debug; };
+        PL = {isa = XCConfigurationList; buildConfigurations = (PD, PR); };
+        PR = {isa = XCBuildConfiguration; buildSettings = {PROJECT}; name = Release; };
+        PX = {isa = PBXFileReference; path = Project.xcconfig; sourceTree = \"<group>\"; };
+        T = {isa = PBXNativeTarget; buildConfigurationList = TL; name = App; };
+        TD = {isa = XCBuildConfiguration; baseConfigurationReference = TX; buildSettings = {TARGET}; name = Debug; };
+        TL = {isa = XCConfigurationList; buildConfigurations = (TD, TR); };
+        TR = {isa = XCBuildConfiguration; buildSettings = {TARGET}; name = Release; };
+        TX = {isa = PBXFileReference; path = Target.xcconfig; sourceTree = \"<group>\"; };
+    ";
+
+    fn parse(project: &str, target: &str) -> XcodePbxProject {
+        let objects = OBJECTS
+            .replace("PROJECT", project)
+            .replace("TARGET", target);
+        let content = format!(
+            "// !$*UTF8*$!\n{{\n\tobjects = {{\n{}\n\t}};\n\trootObject = P;\n}}\n",
+            objects
+        );
+        XcodePbxProject::parse_from_string(&content).unwrap()
+    }
+
+    /// Files system holding the xcconfig files of the project and of the target.
+    fn xcconfigs(project: &str, target: &str) -> MemoryFileSystem {
+        let fs = MemoryFileSystem::new();
+        fs.write(Path::new("/work/Config/Project.xcconfig"), project)
+            .unwrap();
+        fs.write(Path::new("/work/Config/Target.xcconfig"), target)
+            .unwrap();
+        fs
+    }
+
+    fn options() -> BuildSettingsOptions {
+        BuildSettingsOptions::new("Debug")
+            .target("App")
+            .xcodeproj("/work/App.xcodeproj")
+    }
+
+    fn evaluate(
+        project: &XcodePbxProject,
+        fs: &MemoryFileSystem,
+        options: &BuildSettingsOptions,
+    ) -> BTreeMap<String, String> {
+        project
+            .build_settings_with_file_system(options, fs)
+            .unwrap()
+    }
+
+    fn setting<'s>(settings: &'s BTreeMap<String, String>, name: &str) -> &'s str {
+        settings.get(name).map(String::as_str).unwrap_or_else(|| {
+            panic!("{} is not set", name);
+        })
+    }
+
+    #[test]
+    fn target_settings_override_the_xcconfigs_and_the_project() {
+        let project = parse("A = project; B = project; C = project;", "A = target;");
+        let fs = xcconfigs(
+            "A = project-xcconfig\nB = project-xcconfig\nC = project-xcconfig\nD = project-xcconfig\n",
+            "A = target-xcconfig\nB = target-xcconfig\n",
+        );
+        let settings = evaluate(&project, &fs, &options());
+        assert_eq!(setting(&settings, "A"), "target");
+        assert_eq!(setting(&settings, "B"), "target-xcconfig");
+        assert_eq!(setting(&settings, "C"), "project");
+        assert_eq!(setting(&settings, "D"), "project-xcconfig");
+
+        let project_only = evaluate(
+            &project,
+            &fs,
+            &BuildSettingsOptions::new("Debug").xcodeproj("/work/App.xcodeproj"),
+        );
+        assert_eq!(setting(&project_only, "A"), "project");
+        assert!(!project_only.contains_key("TARGET_NAME"));
+    }
+
+    #[test]
+    fn inherited_is_the_value_of_the_level_below() {
+        let project = parse(
+            "FLAGS = (\"$(inherited)\", \"-project\");",
+            "FLAGS = \"$(inherited) -target\"; OTHER = \"$(FLAGS) $(UNSET) $(inherited)\";",
+        );
+        let fs = xcconfigs(
+            "FLAGS = -project-xcconfig\n",
+            "FLAGS = $(FLAGS) -target-xcconfig\n",
+        );
+        let settings = evaluate(&project, &fs, &options());
+        assert_eq!(
+            setting(&settings, "FLAGS"),
+            "-project-xcconfig -project -target-xcconfig -target"
+        );
+        assert_eq!(
+            setting(&settings, "OTHER"),
+            "-project-xcconfig -project -target-xcconfig -target"
+        );
+    }
+
+    #[test]
+    fn the_most_specific_matching_condition_wins() {
+        let project = parse(
+            "\"LIBS[sdk=iphone*]\" = iphone; \"LIBS[sdk=iphonesimulator*]\" = simulator; \
+             \"LIBS[sdk=iphonesimulator*][arch=arm64]\" = arm64-simulator; LIBS = any; \
+             \"MODE[config=Debug]\" = debug; MODE = other; SDKROOT = iphoneos;",
+            "",
+        );
+        let fs = xcconfigs("", "");
+        let evaluate_with = |options: BuildSettingsOptions| {
+            let settings = evaluate(&project, &fs, &options);
+            (
+                setting(&settings, "LIBS").to_string(),
+                setting(&settings, "MODE").to_string(),
+            )
+        };
+        assert_eq!(evaluate_with(options()), ("iphone".into(), "debug".into()));
+        assert_eq!(
+            evaluate_with(options().sdk("iphonesimulator17.0")),
+            ("simulator".into(), "debug".into())
+        );
+        assert_eq!(
+            evaluate_with(options().sdk("iphonesimulator").arch("arm64")),
+            ("arm64-simulator".into(), "debug".into())
+        );
+        assert_eq!(
+            evaluate_with(options().sdk("macosx").arch("arm64")),
+            ("any".into(), "debug".into())
+        );
+        let mut release = options();
+        release.configuration = "Release".to_string();
+        assert_eq!(evaluate_with(release), ("iphone".into(), "other".into()));
+    }
+
+    #[test]
+    fn operators_transform_the_value() {
+        let project = parse(
+            "NAME = \"My App-2\"; FILE = /src/lib/my.module.swift; EMPTY = \"\";",
+            "",
+        );
+        let fs = xcconfigs(
+            "",
+            "LOWER = $(NAME:lower)\n\
+             UPPER = $(NAME:upper)\n\
+             IDENTIFIER = $(NAME:c99extidentifier)\n\
+             RFC1034 = $(NAME:rfc1034identifier)\n\
+             DIR = $(FILE:dir)\n\
+             BASE = $(FILE:base)\n\
+             SUFFIX = $(FILE:suffix)\n\
+             FILE_NAME = $(FILE:file)\n\
+             DEFAULT = $(EMPTY:default=fallback)\n\
+             NOT_DEFAULT = $(NAME:default=fallback)\n\
+             CHAINED = $(EMPTY:default=Some Name:lower:identifier)\n\
+             NESTED = $(TARGET_$(CONFIGURATION:upper))\n\
+             TARGET_DEBUG = debug-value\n",
+        );
+        let settings = evaluate(&project, &fs, &options());
+        let expected = [
+            ("LOWER", "my app-2"),
+            ("UPPER", "MY APP-2"),
+            ("IDENTIFIER", "My_App_2"),
+            ("RFC1034", "My-App-2"),
+            ("DIR", "/src/lib/"),
+            ("BASE", "my.module"),
+            ("SUFFIX", ".swift"),
+            ("FILE_NAME", "my.module.swift"),
+            ("DEFAULT", "fallback"),
+            ("NOT_DEFAULT", "My App-2"),
+            ("CHAINED", "some_name"),
+            ("NESTED", "debug-value"),
+        ];
+        for (name, value) in expected {
+            assert_eq!(setting(&settings, name), value, "{}", name);
+        }
+    }
+
+    #[test]
+    fn built_in_settings_describe_the_project_and_target() {
+        let project = parse("", "PRODUCT_NAME = \"$(TARGET_NAME)\";");
+        let fs = xcconfigs("", "");
+        let settings = evaluate(&project, &fs, &options().sdk("iphonesimulator"));
+        assert_eq!(setting(&settings, "PRODUCT_NAME"), "App");
+        assert_eq!(setting(&settings, "PROJECT_NAME"), "App");
+        assert_eq!(setting(&settings, "SRCROOT"), "/work");
+        assert_eq!(setting(&settings, "PLATFORM_NAME"), "iphonesimulator");
+        assert_eq!(
+            setting(&settings, "EFFECTIVE_PLATFORM_NAME"),
+            "-iphonesimulator"
+        );
+    }
+
+    #[test]
+    fn a_missing_xcconfig_is_skipped() {
+        let project = parse("A = project;", "B = target;");
+        let fs = MemoryFileSystem::new();
+        fs.write(Path::new("/work/Config/Project.xcconfig"), "C = xcconfig\n")
+            .unwrap();
+        let settings = evaluate(&project, &fs, &options());
+        assert_eq!(setting(&settings, "A"), "project");
+        assert_eq!(setting(&settings, "B"), "target");
+        assert_eq!(setting(&settings, "C"), "xcconfig");
+    }
+
+    #[test]
+    fn invalid_xcconfig_is_an_error() {
+        let project = parse("", "");
+        let fs = xcconfigs("", "#include \"Missing.xcconfig\n");
+        let error = project
+            .build_settings_with_file_system(&options(), &fs)
+            .unwrap_err();
+        assert_eq!(error.kind(), "xcconfig");
+    }
+
+    #[test]
+    fn settings_referring_to_each_other_are_left_empty() {
+        let project = parse(
+            "A = \"$(B)$(B)\"; B = \"$(A)$(A)\"; C = \"x $(A) y\"; SELF = \"$(SELF) z\";",
+            "",
+        );
+        let fs = xcconfigs("", "");
+        let settings = evaluate(&project, &fs, &options());
+        assert_eq!(setting(&settings, "A"), "");
+        assert_eq!(setting(&settings, "B"), "");
+        assert_eq!(setting(&settings, "C"), "x  y");
+        // `$(SELF)` in the value of `SELF` is the value of the level below
+        assert_eq!(setting(&settings, "SELF"), "z");
+    }
+
+    #[test]
+    fn only_the_selected_settings_are_evaluated() {
+        let project = parse("A = \"$(B)\"; B = b; C = c;", "");
+        let fs = xcconfigs("", "");
+        let settings = evaluate(&project, &fs, &options().setting("A").setting("UNSET"));
+        assert_eq!(
+            settings,
+            BTreeMap::from([
+                ("A".to_string(), "b".to_string()),
+                ("UNSET".to_string(), String::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn generated_project_links_the_simulator_libraries() {
+        let cargo_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/cargo/my_lib");
+        let package = CargoPackage::builder(&cargo_dir)
+            .framework_path(cargo_dir.join("xcode"))
+            .build()
+            .unwrap();
+        let fs = Rc::new(MemoryFileSystem::new());
+        XcodeProject::with_file_system(package, XcodeProjectOptions::default(), fs.clone())
+            .create()
+            .unwrap();
+        let (pbxproj, content) = fs
+            .written_files()
+            .into_iter()
+            .find(|(path, _)| path.ends_with("project.pbxproj"))
+            .unwrap();
+        let project = XcodePbxProject::parse_from_string(&content).unwrap();
+        let xcodeproj = pbxproj.parent().unwrap();
+        let settings = evaluate(
+            &project,
+            &fs,
+            &BuildSettingsOptions::new("Debug")
+                .target("MyLib_ios")
+                .sdk("iphonesimulator")
+                .xcodeproj(xcodeproj)
+                .setting("LIBRARY_SEARCH_PATHS"),
+        );
+        assert_eq!(
+            setting(&settings, "LIBRARY_SEARCH_PATHS"),
+            format!(
+                "{}/xcode/MyLib/../../target/universal/debug/ios_sim",
+                cargo_dir.display()
+            )
+        );
+    }
+}
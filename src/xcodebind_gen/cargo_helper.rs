@@ -1,45 +1,173 @@
-use cargo_metadata::Target;
-use colored::Colorize;
+use super::{
+    CommandRunner, FileSystem, RealFileSystem, Result, SystemCommandRunner, XcodeUniffiError,
+};
+use cargo_metadata::{
+    Dependency, DependencyKind, MetadataCommand, Package, Target, Version, VersionReq,
+};
 use convert_case::{Case, Casing};
 use pathdiff::diff_paths;
+use std::env;
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
-use std::process::exit;
+
+/// UniFFI release the generated build scripts are written for (see README).
+pub const SUPPORTED_UNIFFI_VERSION: &str = "0.16.0";
 
 #[derive(Debug, std::cmp::Eq, std::cmp::PartialEq)]
-pub(crate) struct CargoPackage {
-    pub(crate) xcode_framework_name: String,
-    pub(crate) xcode_framework_path: PathBuf,
-    pub(crate) package_name: String,
-    pub(crate) target_name: String,
-    pub(crate) lib_src_path: PathBuf,
-    pub(crate) manifest_path: PathBuf,
-    pub(crate) cargo_base_dir: PathBuf,
-    pub(crate) cargo_relative_path_to_xcode_project: PathBuf,
-    pub(crate) udl_absolute_files_path: Vec<(PathBuf, String)>,
-    pub(crate) udl_relative_files_path: Vec<(PathBuf, String)>,
-    pub(crate) base_bundle_identifier: String,
+pub struct CargoPackage {
+    pub xcode_framework_name: String,
+    pub xcode_framework_path: PathBuf,
+    pub package_name: String,
+    pub target_name: String,
+    pub lib_src_path: PathBuf,
+    pub manifest_path: PathBuf,
+    pub cargo_base_dir: PathBuf,
+    pub cargo_relative_path_to_xcode_project: PathBuf,
+    pub udl_absolute_files_path: Vec<(PathBuf, String)>,
+    pub udl_relative_files_path: Vec<(PathBuf, String)>,
+    pub base_bundle_identifier: String,
+    /// Version requirement of the `uniffi` dependency, `None` if the package does not depend on it.
+    pub uniffi_version_req: Option<String>,
+    /// Version requirement of the `uniffi_build` build-dependency.
+    pub uniffi_build_version_req: Option<String>,
+}
+
+/// Collects the inputs of `CargoPackage::new`, filling in the same defaults as the command line.
+///
+/// ```no_run
+/// let package = xcode_uniffi::CargoPackage::builder("path/to/rust/project")
+///     .framework_name("MyFastAlgo")
+///     .build()?;
+/// # Ok::<(), xcode_uniffi::XcodeUniffiError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct CargoPackageBuilder {
+    manifest_path: PathBuf,
+    package_name: Option<String>,
+    lib_name: Option<String>,
+    xcode_framework_name: Option<String>,
+    xcode_framework_path: Option<PathBuf>,
+}
+
+impl CargoPackageBuilder {
+    /// Cargo package to use when Cargo.toml contains more than one package.
+    pub fn package_name(mut self, name: impl Into<String>) -> Self {
+        self.package_name = Some(name.into());
+        self
+    }
+
+    /// Name of the `[lib]` target inside the package.
+    pub fn lib_name(mut self, name: impl Into<String>) -> Self {
+        self.lib_name = Some(name.into());
+        self
+    }
+
+    /// Xcode framework name, defaults to the library name in Pascal case.
+    pub fn framework_name(mut self, name: impl Into<String>) -> Self {
+        self.xcode_framework_name = Some(name.into());
+        self
+    }
+
+    /// Directory the Xcode project is generated in, defaults to `<cargo dir>/xcode`.
+    pub fn framework_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.xcode_framework_path = Some(path.into());
+        self
+    }
+
+    pub fn build(self) -> Result<CargoPackage> {
+        self.build_with(&SystemCommandRunner {}, &RealFileSystem {})
+    }
+
+    /// Same as `build`, running `cargo metadata` through `runner` and looking for the manifest
+    /// and the UDL files in `fs`.
+    pub fn build_with(
+        self,
+        runner: &dyn CommandRunner,
+        fs: &dyn FileSystem,
+    ) -> Result<CargoPackage> {
+        let manifest_path = if self.manifest_path.ends_with("Cargo.toml") {
+            self.manifest_path
+        } else {
+            self.manifest_path.join("Cargo.toml")
+        };
+        if !fs.exists(&manifest_path) {
+            return Err(XcodeUniffiError::ManifestNotFound(manifest_path));
+        }
+        let xcode_framework_path = match self.xcode_framework_path {
+            Some(path) => path,
+            None => match manifest_path.parent() {
+                Some(parent_path) => parent_path.join("xcode"),
+                None => return Err(XcodeUniffiError::ManifestNotFound(manifest_path)),
+            },
+        };
+        CargoPackage::load(
+            &manifest_path,
+            self.package_name,
+            self.lib_name,
+            self.xcode_framework_name,
+            &xcode_framework_path,
+            runner,
+            fs,
+        )
+    }
 }
 
 impl CargoPackage {
-    pub(crate) fn new(
+    /// Starts a builder for the Cargo.toml at `manifest_path` (the file or its directory).
+    pub fn builder(manifest_path: impl Into<PathBuf>) -> CargoPackageBuilder {
+        CargoPackageBuilder {
+            manifest_path: manifest_path.into(),
+            package_name: None,
+            lib_name: None,
+            xcode_framework_name: None,
+            xcode_framework_path: None,
+        }
+    }
+
+    pub fn new(
         cargo_manifest_path: &Path,
         package_name: Option<String>,
         lib_name: Option<String>,
         xcode_framework_name: Option<String>,
         xcode_framework_path: &Path,
-    ) -> Self {
-        let (cargo_package_name, cargo_lib_name, lib_src_path) =
-            CargoPackage::validate_cargo_and_return(cargo_manifest_path, package_name, lib_name);
-
-        let cargo_base_dir = if cargo_manifest_path.ends_with("Cargo.toml") {
-            cargo_manifest_path
-                .parent()
-                .expect("Cargo.toml file has no Parent folder !!!!!!!!")
-                .to_path_buf()
-        } else {
-            cargo_manifest_path.to_path_buf()
-        };
+    ) -> Result<Self> {
+        CargoPackage::load(
+            cargo_manifest_path,
+            package_name,
+            lib_name,
+            xcode_framework_name,
+            xcode_framework_path,
+            &SystemCommandRunner {},
+            &RealFileSystem {},
+        )
+    }
+
+    fn load(
+        cargo_manifest_path: &Path,
+        package_name: Option<String>,
+        lib_name: Option<String>,
+        xcode_framework_name: Option<String>,
+        xcode_framework_path: &Path,
+        runner: &dyn CommandRunner,
+        fs: &dyn FileSystem,
+    ) -> Result<Self> {
+        let cargo_package = CargoPackage::validate_cargo_and_return(
+            cargo_manifest_path,
+            package_name,
+            lib_name,
+            runner,
+        )?;
+        let (cargo_package_name, cargo_lib_name, lib_src_path) = (
+            cargo_package.name.clone(),
+            cargo_package.targets[0].name.clone(),
+            PathBuf::from(&cargo_package.targets[0].src_path),
+        );
+        let uniffi_version_req =
+            CargoPackage::dependency_req(&cargo_package, "uniffi", DependencyKind::Normal);
+        let uniffi_build_version_req =
+            CargoPackage::dependency_req(&cargo_package, "uniffi_build", DependencyKind::Build);
+
+        let cargo_base_dir = CargoPackage::manifest_dir(cargo_manifest_path)?;
 
         // Convert Xcode Project case to Pascal Format
         let xcode_framework_name = match xcode_framework_name {
@@ -54,25 +182,19 @@ impl CargoPackage {
             xcode_framework_path.join(&xcode_framework_name)
         };
         let cargo_relative_path_to_xcode_project =
-            diff_paths(&cargo_base_dir, &xcode_framework_path)
-                .expect("Unable to get relative path to Cargo");
-        // println!(
-        //     "cargo_relative_path_to_xcode_project: {:?}",
-        //     cargo_relative_path_to_xcode_project
-        // );
+            CargoPackage::relative_path(&cargo_base_dir, &xcode_framework_path)?;
 
-        let udl_files_path = CargoPackage::find_udl_files(&cargo_manifest_path);
+        let udl_files_path = CargoPackage::find_udl_files(cargo_manifest_path, fs)?;
 
         let mut udl_relative_files_path: Vec<(PathBuf, String)> = vec![];
         for (udl_path, filename) in &udl_files_path {
             udl_relative_files_path.push((
-                diff_paths(udl_path, &xcode_framework_path).expect("Getting relative path failed."),
+                CargoPackage::relative_path(udl_path, &xcode_framework_path)?,
                 filename.clone(),
             ));
         }
-        //println!("udl_relative_files_path: {:?}", udl_relative_files_path);
 
-        CargoPackage {
+        Ok(CargoPackage {
             xcode_framework_name,
             xcode_framework_path,
             package_name: cargo_package_name,
@@ -84,173 +206,348 @@ impl CargoPackage {
             udl_absolute_files_path: udl_files_path,
             udl_relative_files_path,
             base_bundle_identifier: "com.example".to_string(),
+            uniffi_version_req,
+            uniffi_build_version_req,
+        })
+    }
+
+    /// Problems with the `uniffi`/`uniffi_build` dependencies, each with a remedy.
+    /// Both must be present and accept `SUPPORTED_UNIFFI_VERSION`.
+    pub fn check_uniffi_dependencies(&self) -> Vec<String> {
+        let supported = Version::parse(SUPPORTED_UNIFFI_VERSION).expect("valid version");
+        let mut warnings = vec![];
+        for (name, section, req) in [
+            ("uniffi", "[dependencies]", &self.uniffi_version_req),
+            ("uniffi_build", "[build-dependencies]", &self.uniffi_build_version_req),
+        ] {
+            match req {
+                None => warnings.push(format!(
+                    "`{}` is missing from {} in {:?}. Add: {} = \"{}\"",
+                    name, section, self.manifest_path, name, SUPPORTED_UNIFFI_VERSION
+                )),
+                Some(req) => {
+                    let compatible = VersionReq::parse(req)
+                        .map(|r| r.matches(&supported))
+                        .unwrap_or(false);
+                    if !compatible {
+                        warnings.push(format!(
+                            "`{} = \"{}\"` does not accept UniFFI {}, the generated project may not build. Use: {} = \"{}\"",
+                            name, req, SUPPORTED_UNIFFI_VERSION, name, SUPPORTED_UNIFFI_VERSION
+                        ));
+                    }
+                }
+            }
         }
+        if let (Some(uniffi), Some(uniffi_build)) =
+            (&self.uniffi_version_req, &self.uniffi_build_version_req)
+        {
+            if uniffi != uniffi_build {
+                warnings.push(format!(
+                    "`uniffi = \"{}\"` and `uniffi_build = \"{}\"` should use the same version.",
+                    uniffi, uniffi_build
+                ));
+            }
+        }
+        warnings
+    }
+
+    fn dependency_req(package: &Package, name: &str, kind: DependencyKind) -> Option<String> {
+        package
+            .dependencies
+            .iter()
+            .find(|d: &&Dependency| d.name == name && d.kind == kind)
+            .map(|d| d.req.to_string())
     }
 
     fn validate_cargo_and_return(
         cargo_manifest_path: &Path,
         package_name: Option<String>,
         lib_name: Option<String>,
-    ) -> (String, String, PathBuf) {
-        let mut cargo_cmd = cargo_metadata::MetadataCommand::new();
-        cargo_cmd.no_deps();
-        cargo_cmd.manifest_path(cargo_manifest_path);
-        let mut cargo_metadata = match cargo_cmd.exec() {
-            Ok(m) => m,
-            Err(e) => {
-                eprintln!(
-                    "error: Can't parse cargo metadata in {:?} because: {}",
-                    &cargo_manifest_path, e
-                );
-                exit(1);
-            }
+        runner: &dyn CommandRunner,
+    ) -> Result<Package> {
+        let metadata_failed = |message: String| XcodeUniffiError::MetadataFailed {
+            manifest_path: cargo_manifest_path.to_path_buf(),
+            message,
         };
-        let mut cargo_package = match package_name {
-            Some(name) => {
-                cargo_metadata.packages.retain(|f| f.name == name);
-                match cargo_metadata.packages.into_iter().next() {
-                    Some(package) => package,
-                    None => {
-                        eprintln!(
-                            "\nCheck your project Cargo.toml file or Specify a correct path.\n{} '{}' {} {:?}",
-                                "Specified package name".red(),
-                                name.bold(),
-                                "not found in your Cargo.toml file at:".red(),
-                                cargo_manifest_path
-                        );
-                        exit(1)
-                    }
-                }
-            }
-            None => {
-                match cargo_metadata.packages.into_iter().next() {
-                    Some(package) => package,
-                    None => {
-                        eprintln!("{}\nCheck your project Cargo.toml file or Specify path to the correct \
-                file or folder.\nPath:{:?}", "No Rust package found in your Cargo.toml file.".red(),
-                              cargo_manifest_path);
-                        exit(1);
-                    }
-                }
+        // the command `cargo_metadata::MetadataCommand` runs, through `runner`
+        let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+        let manifest_path = cargo_manifest_path.to_string_lossy();
+        let output = runner
+            .run(
+                &cargo,
+                &[
+                    "metadata",
+                    "--format-version",
+                    "1",
+                    "--no-deps",
+                    "--manifest-path",
+                    &manifest_path,
+                ],
+            )
+            .map_err(|e| metadata_failed(e.to_string()))?;
+        if !output.success {
+            return Err(metadata_failed(output.stderr.trim().to_string()));
+        }
+        let json = output
+            .stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .ok_or_else(|| metadata_failed("cargo metadata printed no JSON".to_string()))?;
+        let mut cargo_metadata =
+            MetadataCommand::parse(json).map_err(|e| metadata_failed(e.to_string()))?;
+        if let Some(name) = &package_name {
+            cargo_metadata.packages.retain(|f| &f.name == name);
+        }
+        let mut cargo_package = cargo_metadata.packages.into_iter().next().ok_or_else(|| {
+            XcodeUniffiError::PackageNotFound {
+                manifest_path: cargo_manifest_path.to_path_buf(),
+                package_name,
             }
-        };
+        })?;
 
-        let cargo_target = match lib_name {
-            Some(ln) => {
-                cargo_package.targets.retain(|f| f.name == ln);
-                if cargo_package.targets.is_empty() {
-                    eprintln!(
-                        "\n{} {} {}",
-                        "No library named: ".red(),
-                        ln.yellow().underline(),
-                        "found in all targets.".red()
-                    );
-                    eprintln!("Check provided library name and try again.\n");
-                    exit(1);
-                }
-                CargoPackage::target_is_valid(
-                    cargo_package.targets,
-                    &cargo_package.name,
-                    cargo_manifest_path,
-                )
+        if let Some(ln) = lib_name {
+            cargo_package.targets.retain(|f| f.name == ln);
+            if cargo_package.targets.is_empty() {
+                return Err(XcodeUniffiError::NoLibTarget {
+                    manifest_path: cargo_manifest_path.to_path_buf(),
+                    package_name: cargo_package.name,
+                    lib_name: Some(ln),
+                });
             }
-            None => CargoPackage::target_is_valid(
-                cargo_package.targets,
-                &cargo_package.name,
-                cargo_manifest_path,
-            ),
-        };
-        //println!("Detected package: {:?}", cargo_target);
-        (
-            cargo_package.name,
-            cargo_target.name,
-            PathBuf::from(cargo_target.src_path),
-        )
+        }
+        // Report the package's own manifest, which differs from the given one in workspaces
+        let package_manifest_path = PathBuf::from(&cargo_package.manifest_path);
+        let cargo_target = CargoPackage::target_is_valid(
+            std::mem::take(&mut cargo_package.targets),
+            &cargo_package.name,
+            &package_manifest_path,
+        )?;
+        cargo_package.targets = vec![cargo_target];
+        Ok(cargo_package)
     }
 
     fn target_is_valid(
         mut targets: Vec<Target>,
         package_name: &str,
         cargo_manifest_path: &Path,
-    ) -> Target {
-        targets.retain(|f| f.kind.iter().any(|x| (x == "staticlib" || x == "lib")));
+    ) -> Result<Target> {
+        // Target kinds follow crate-type, a `[lib]` with only `cdylib` still is a library target.
+        targets.retain(|f| {
+            f.kind
+                .iter()
+                .any(|x| matches!(x.as_str(), "lib" | "rlib" | "dylib" | "cdylib" | "staticlib"))
+        });
         if targets.is_empty() {
-            eprintln!("\nNo target of type: [\"lib\"] found in cargo package.name: '{}' in file:{:?} \n{}\n\n",
-            package_name.blue(), cargo_manifest_path, "Xcode framework project could only be created for Cargo 'library' targets.".red().bold());
-            exit(1);
+            return Err(XcodeUniffiError::NoLibTarget {
+                manifest_path: cargo_manifest_path.to_path_buf(),
+                package_name: package_name.to_string(),
+                lib_name: None,
+            });
         }
         targets.retain(|f| f.crate_types.contains(&String::from("staticlib")));
-        match targets.into_iter().next() {
-            Some(some_target) => some_target,
-            None => {
-                eprintln!("No crate_type of type: [\"staticlib\"] found in cargo package.name: '{}' in file:{:?} \n{}\n\n",
-            package_name.blue(), cargo_manifest_path, ". Xcode framework project could only be created for Cargo library with crate_type containing 'staticlib'.".red().bold());
-                exit(1);
-            }
+        targets
+            .into_iter()
+            .next()
+            .ok_or_else(|| XcodeUniffiError::MissingStaticlib {
+                manifest_path: cargo_manifest_path.to_path_buf(),
+                package_name: package_name.to_string(),
+            })
+    }
+
+    /// Directory of the Cargo.toml at `cargo_manifest_path` (the file or its directory).
+    fn manifest_dir(cargo_manifest_path: &Path) -> Result<PathBuf> {
+        if !cargo_manifest_path.ends_with("Cargo.toml") {
+            return Ok(cargo_manifest_path.to_path_buf());
         }
+        cargo_manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| XcodeUniffiError::ManifestNotFound(cargo_manifest_path.to_path_buf()))
     }
 
-    fn find_udl_files(lib_src_path: &Path) -> Vec<(PathBuf, String)> {
-        let expected_udl_files_path = if lib_src_path.ends_with("Cargo.toml") {
-            lib_src_path
-                .parent()
-                .expect("Cargo.toml file has no Parent folder !!!!!!!!")
-                .join("src")
-        } else {
-            lib_src_path.join("src")
-        };
+    /// `path` relative to `base`, both must be absolute or both relative.
+    fn relative_path(path: &Path, base: &Path) -> Result<PathBuf> {
+        diff_paths(path, base).ok_or_else(|| XcodeUniffiError::NoRelativePath {
+            path: path.to_path_buf(),
+            base: base.to_path_buf(),
+        })
+    }
 
-        let udl_files_path: Vec<(PathBuf, String)> =
-            match std::fs::read_dir(expected_udl_files_path) {
-                Ok(paths) => {
-                    paths
-                        .filter(|f| f.is_ok())
-                        .map(|f| f.expect("Failed to convert Directory Entry to path").path())
-                        .map(|f| {
-                            match (f.file_name(), f.extension()) {
-                                (Some(file_name), Some(ext)) => {
-                                    if ext == OsStr::new("udl") {
-                                        //println!("path: {:?}", f);
-                                        let m = file_name
-                                            .to_str()
-                                            .expect("Unable to get file name from UDL file path")
-                                            .to_string();
-                                        (Some(f.to_path_buf()), Some(m))
-                                    } else {
-                                        (None, None)
-                                    }
-                                }
-                                _ => (None, None),
-                            }
-                        })
-                }
-                Err(e) => {
-                    eprintln!(
-                        "Unable to open existing Xcode Project Directory at: {:?}\nError: {:?}",
-                        lib_src_path, e
-                    );
-                    exit(1);
+    fn find_udl_files(lib_src_path: &Path, fs: &dyn FileSystem) -> Result<Vec<(PathBuf, String)>> {
+        let expected_udl_files_path = CargoPackage::manifest_dir(lib_src_path)?.join("src");
+
+        let paths = fs
+            .read_dir(&expected_udl_files_path)
+            .map_err(|e| XcodeUniffiError::io(&expected_udl_files_path, e))?;
+        let mut udl_files_path: Vec<(PathBuf, String)> = paths
+            .into_iter()
+            .filter_map(|f| match (f.file_name(), f.extension()) {
+                (Some(file_name), Some(ext)) if ext == OsStr::new("udl") => {
+                    let m = file_name.to_str()?.to_string();
+                    Some((f.to_path_buf(), m))
                 }
-            }
-            .filter(|(a, _)| a.is_some())
-            .map(|(a, b)| {
-                (
-                    a.expect("UDL file paths must not be empty"),
-                    b.expect("Unable to get file name from UDL file path"),
-                )
+                _ => None,
             })
-            .collect::<Vec<(PathBuf, String)>>();
+            .collect();
         if udl_files_path.is_empty() {
-            eprintln!("\n{}\n", "No UDL files found.".red());
-            eprintln!(
-                "Refer to Rust UniFFI: {}\n\n",
-                "https://mozilla.github.io/uniffi-rs/udl_file_spec.html"
-                    .underline()
-                    .bright_blue()
-            );
-            exit(1);
+            return Err(XcodeUniffiError::NoUdlFiles(expected_udl_files_path));
         }
+        // read_dir order is platform dependent
+        udl_files_path.sort();
+
+        Ok(udl_files_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cargo project under `test_data/cargo`.
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("test_data/cargo")
+            .join(name)
+    }
+
+    #[test]
+    fn valid_package() {
+        let package = CargoPackage::builder(fixture("my_lib")).build().unwrap();
+        assert_eq!(package.package_name, "my_lib");
+        assert_eq!(package.target_name, "my_lib");
+        assert_eq!(package.xcode_framework_name, "MyLib");
+        assert_eq!(
+            package.xcode_framework_path,
+            fixture("my_lib").join("xcode/MyLib")
+        );
+        assert_eq!(
+            package.cargo_relative_path_to_xcode_project,
+            Path::new("../..")
+        );
+        assert_eq!(
+            package.udl_relative_files_path,
+            [(
+                PathBuf::from("../../src/my_lib.udl"),
+                "my_lib.udl".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn missing_manifest() {
+        let error = CargoPackage::builder(fixture("missing"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(&error, XcodeUniffiError::ManifestNotFound(path)
+                if *path == fixture("missing/Cargo.toml")));
+        assert_eq!(error.kind(), "manifest-not-found");
+    }
+
+    #[test]
+    fn virtual_workspace() {
+        let error = CargoPackage::builder(fixture("virtual_workspace"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            XcodeUniffiError::PackageNotFound {
+                package_name: None,
+                ..
+            }
+        ));
+        assert_eq!(error.kind(), "package-not-found");
+    }
+
+    #[test]
+    fn wrong_package_name() {
+        let error = CargoPackage::builder(fixture("my_lib"))
+            .package_name("other")
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            XcodeUniffiError::PackageNotFound { package_name: Some(name), .. } if name == "other"
+        ));
+        assert_eq!(error.kind(), "package-not-found");
+    }
+
+    #[test]
+    fn no_lib_target() {
+        let error = CargoPackage::builder(fixture("no_lib"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            XcodeUniffiError::NoLibTarget { package_name, lib_name: None, .. } if package_name == "no_lib"
+        ));
+        assert_eq!(error.kind(), "no-lib-target");
+    }
+
+    #[test]
+    fn wrong_lib_name() {
+        let error = CargoPackage::builder(fixture("my_lib"))
+            .lib_name("other")
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            XcodeUniffiError::NoLibTarget { lib_name: Some(name), .. } if name == "other"
+        ));
+        assert_eq!(error.kind(), "no-lib-target");
+    }
+
+    #[test]
+    fn missing_staticlib() {
+        let error = CargoPackage::builder(fixture("missing_staticlib"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            XcodeUniffiError::MissingStaticlib { manifest_path, package_name }
+                if *manifest_path == fixture("missing_staticlib/Cargo.toml")
+                    && package_name == "missing_staticlib"
+        ));
+        assert_eq!(error.kind(), "missing-staticlib");
+    }
+
+    #[test]
+    fn no_udl_files() {
+        let error = CargoPackage::builder(fixture("no_udl"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            XcodeUniffiError::NoUdlFiles(directory) if *directory == fixture("no_udl/src")
+        ));
+        assert_eq!(error.kind(), "no-udl-files");
+    }
+
+    #[test]
+    fn malformed_manifest() {
+        let error = CargoPackage::builder(fixture("malformed"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            XcodeUniffiError::MetadataFailed { manifest_path, .. }
+                if *manifest_path == fixture("malformed/Cargo.toml")
+        ));
+        assert_eq!(error.kind(), "metadata-failed");
+    }
 
-        udl_files_path
+    #[test]
+    fn paths_that_have_no_relative_path() {
+        // tests run in the package directory
+        let error = CargoPackage::builder("test_data/cargo/my_lib")
+            .framework_path(fixture("my_lib/xcode"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            XcodeUniffiError::NoRelativePath { path, base }
+                if *path == Path::new("test_data/cargo/my_lib")
+                    && *base == fixture("my_lib/xcode/MyLib")
+        ));
+        assert_eq!(error.kind(), "no-relative-path");
     }
 }
@@ -1,8 +1,29 @@
 use colored::Colorize;
-use std::io::{stdin, stdout, Write};
+use std::io::{stdin, stdout, IsTerminal, Write};
 
 pub(crate) struct CommandLineParser {}
 
+/// Decides how yes/no questions are answered.
+/// `--yes` answers every question with yes, `--no-input` (or a stdin that is not a terminal)
+/// takes the default answer (no) without blocking.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PromptPolicy {
+    pub(crate) assume_yes: bool,
+    pub(crate) no_input: bool,
+}
+
+impl PromptPolicy {
+    pub(crate) fn confirm(&self, msg: &str) -> bool {
+        if self.assume_yes {
+            true
+        } else if self.no_input || !stdin().is_terminal() {
+            false
+        } else {
+            CommandLineParser::get_user_yes_or_no_input(msg)
+        }
+    }
+}
+
 impl CommandLineParser {
     pub(crate) fn get_user_yes_or_no_input(msg: &str) -> bool {
         let mut input_str = String::new();
@@ -0,0 +1,598 @@
+use super::{
+    CargoPackage, CargoPackageBuilder, FileSystem, RealFileSystem, XcodePbxProject,
+    XcodeUniffiError, SUPPORTED_UNIFFI_VERSION,
+};
+use std::io;
+use std::process::Command;
+
+/// Rust targets needed to build the universal libraries (see README).
+pub const REQUIRED_RUST_TARGETS: [&str; 5] = [
+    "x86_64-apple-darwin",
+    "aarch64-apple-darwin",
+    "x86_64-apple-ios",
+    "aarch64-apple-ios",
+    "aarch64-apple-ios-sim",
+];
+
+/// Output of an external command.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs external programs for `Doctor` and `CargoPackageBuilder::build_with`. Implement it to
+/// fake `rustup`, `uniffi-bindgen` and `cargo metadata`.
+pub trait CommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput>;
+}
+
+pub struct SystemCommandRunner {}
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let output = Command::new(program).args(args).output()?;
+        Ok(CommandOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, std::cmp::Eq, std::cmp::PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "fail",
+        }
+    }
+}
+
+/// Result of a single `doctor` check.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// What to do about a warning or failure.
+    pub remedy: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: &'static str, message: String) -> Self {
+        DoctorCheck {
+            name,
+            status: CheckStatus::Pass,
+            message,
+            remedy: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: String, remedy: String) -> Self {
+        DoctorCheck {
+            name,
+            status: CheckStatus::Warn,
+            message,
+            remedy: Some(remedy),
+        }
+    }
+
+    fn fail(name: &'static str, message: String, remedy: String) -> Self {
+        DoctorCheck {
+            name,
+            status: CheckStatus::Fail,
+            message,
+            remedy: Some(remedy),
+        }
+    }
+}
+
+/// Checks the toolchain and the Rust/Xcode project pair.
+pub struct Doctor<'a> {
+    runner: &'a dyn CommandRunner,
+    fs: &'a dyn FileSystem,
+}
+
+impl<'a> Doctor<'a> {
+    pub fn new(runner: &'a dyn CommandRunner, fs: &'a dyn FileSystem) -> Self {
+        Doctor { runner, fs }
+    }
+
+    pub fn with_system() -> Doctor<'static> {
+        Doctor {
+            runner: &SystemCommandRunner {},
+            fs: &RealFileSystem {},
+        }
+    }
+
+    /// Runs every check. Project checks are skipped when the Cargo package is unusable.
+    pub fn run(&self, package: CargoPackageBuilder) -> Vec<DoctorCheck> {
+        let mut checks = vec![self.check_rust_targets(), self.check_uniffi_bindgen()];
+        match package.build_with(self.runner, self.fs) {
+            Ok(package) => {
+                checks.push(DoctorCheck::pass(
+                    "cargo-manifest",
+                    format!(
+                        "Library '{}' of package '{}' builds a staticlib",
+                        package.target_name, package.package_name
+                    ),
+                ));
+                checks.push(DoctorCheck::pass(
+                    "udl-files",
+                    format!(
+                        "Found {} UDL file(s)",
+                        package.udl_absolute_files_path.len()
+                    ),
+                ));
+                checks.extend(package.check_uniffi_dependencies().into_iter().map(|w| {
+                    DoctorCheck::warn(
+                        "uniffi-dependencies",
+                        w,
+                        format!("Use UniFFI {}", SUPPORTED_UNIFFI_VERSION),
+                    )
+                }));
+                checks.extend(self.check_xcode_project(&package));
+            }
+            Err(e) => checks.push(Doctor::cargo_failure(e)),
+        }
+        checks
+    }
+
+    pub fn check_rust_targets(&self) -> DoctorCheck {
+        let name = "rust-targets";
+        let output = match self
+            .runner
+            .run("rustup", &["target", "list", "--installed"])
+        {
+            Ok(output) if output.success => output,
+            Ok(output) => {
+                return DoctorCheck::fail(
+                    name,
+                    format!(
+                        "`rustup target list --installed` failed: {}",
+                        output.stderr.trim()
+                    ),
+                    "Install rustup from https://rustup.rs".to_string(),
+                )
+            }
+            Err(e) => {
+                return DoctorCheck::fail(
+                    name,
+                    format!("Unable to run rustup: {}", e),
+                    "Install rustup from https://rustup.rs".to_string(),
+                )
+            }
+        };
+        let installed: Vec<&str> = output.stdout.lines().map(str::trim).collect();
+        let missing: Vec<&str> = REQUIRED_RUST_TARGETS
+            .iter()
+            .filter(|t| !installed.contains(t))
+            .copied()
+            .collect();
+        if missing.is_empty() {
+            DoctorCheck::pass(name, "All Apple rust targets are installed".to_string())
+        } else {
+            DoctorCheck::fail(
+                name,
+                format!("Missing rust targets: {}", missing.join(", ")),
+                format!("rustup target add {}", missing.join(" ")),
+            )
+        }
+    }
+
+    pub fn check_uniffi_bindgen(&self) -> DoctorCheck {
+        let name = "uniffi-bindgen";
+        let remedy = format!(
+            "cargo install uniffi_bindgen --version {}",
+            SUPPORTED_UNIFFI_VERSION
+        );
+        match self.runner.run("uniffi-bindgen", &["--version"]) {
+            Ok(output) if output.success => {
+                // e.g. "uniffi-bindgen 0.16.0"
+                let version = output
+                    .stdout
+                    .split_whitespace()
+                    .last()
+                    .unwrap_or("")
+                    .to_string();
+                if version == SUPPORTED_UNIFFI_VERSION {
+                    DoctorCheck::pass(name, format!("uniffi-bindgen {}", version))
+                } else {
+                    DoctorCheck::warn(
+                        name,
+                        format!(
+                            "uniffi-bindgen {} is installed, {} is supported",
+                            version, SUPPORTED_UNIFFI_VERSION
+                        ),
+                        remedy,
+                    )
+                }
+            }
+            _ => DoctorCheck::fail(name, "uniffi-bindgen is not installed".to_string(), remedy),
+        }
+    }
+
+    /// Compares an existing project.pbxproj with what `create` would generate for `package`.
+    pub fn check_xcode_project(&self, package: &CargoPackage) -> Vec<DoctorCheck> {
+        let name = "xcode-project";
+        let pbxproj_path = package
+            .xcode_framework_path
+            .join(format!("{}.xcodeproj", package.xcode_framework_name))
+            .join("project.pbxproj");
+        if !self.fs.exists(&pbxproj_path) {
+            return vec![DoctorCheck::warn(
+                name,
+                format!("No Xcode project at {:?}", pbxproj_path),
+                "Run `xcode_uniffi create` (pass --path/--name if the project lives elsewhere)"
+                    .to_string(),
+            )];
+        }
+        let project = match self
+            .fs
+            .read_to_string(&pbxproj_path)
+            .map_err(|e| XcodeUniffiError::io(&pbxproj_path, e))
+            .and_then(|content| XcodePbxProject::parse_from_string(&content))
+        {
+            Ok(project) => project,
+            Err(e) => {
+                return vec![DoctorCheck::fail(
+                    name,
+                    e.to_string(),
+                    "Restore project.pbxproj from version control or recreate it with --force"
+                        .to_string(),
+                )]
+            }
+        };
+        let values = project.leaf_values();
+        let mut checks = vec![DoctorCheck::pass(name, format!("Found {:?}", pbxproj_path))];
+
+        let product_names: Vec<&str> = values
+            .iter()
+            .filter(|(k, v)| *k == "PRODUCT_NAME" && !v.starts_with("$("))
+            .map(|(_, v)| *v)
+            .collect();
+        if product_names
+            .iter()
+            .all(|v| *v == package.xcode_framework_name)
+        {
+            checks.push(DoctorCheck::pass(
+                "framework-name",
+                format!("Framework product name is {}", package.xcode_framework_name),
+            ));
+        } else {
+            checks.push(DoctorCheck::warn(
+                "framework-name",
+                format!(
+                    "PRODUCT_NAME {:?} does not match framework name {}",
+                    product_names, package.xcode_framework_name
+                ),
+                "Pass the framework name with --name/-n or regenerate the project with --force"
+                    .to_string(),
+            ));
+        }
+
+        let relative_path = package
+            .cargo_relative_path_to_xcode_project
+            .to_string_lossy()
+            .to_string();
+        let expected_search_path = format!("$(SRCROOT)/{}/target/universal/", relative_path);
+        let search_paths: Vec<&str> = values
+            .iter()
+            .filter(|(k, _)| k.starts_with("LIBRARY_SEARCH_PATHS"))
+            .map(|(_, v)| *v)
+            .collect();
+        let wrong_search_paths: Vec<&&str> = search_paths
+            .iter()
+            .filter(|v| !v.starts_with(&expected_search_path))
+            .collect();
+        if search_paths.is_empty() || !wrong_search_paths.is_empty() {
+            checks.push(DoctorCheck::fail(
+                "library-search-paths",
+                format!(
+                    "LIBRARY_SEARCH_PATHS {:?} do not point to {}",
+                    wrong_search_paths, expected_search_path
+                ),
+                "Regenerate the project with `xcode_uniffi create --force`".to_string(),
+            ));
+        } else {
+            checks.push(DoctorCheck::pass(
+                "library-search-paths",
+                format!("Library search paths point to {}", expected_search_path),
+            ));
+        }
+
+        let expected_script = format!(
+            "$SRCROOT/{}/rust-xc-universal-binary.sh lib{}.a {}",
+            relative_path, package.target_name, package.package_name
+        );
+        let scripts: Vec<&str> = values
+            .iter()
+            .filter(|(k, _)| *k == "shellScript")
+            .map(|(_, v)| *v)
+            .collect();
+        if !scripts.is_empty() && scripts.iter().all(|s| s.contains(&expected_script)) {
+            checks.push(DoctorCheck::pass(
+                "cargo-relative-path",
+                format!("Build phases call {}", expected_script),
+            ));
+        } else {
+            checks.push(DoctorCheck::fail(
+                "cargo-relative-path",
+                format!(
+                    "Shell script build phases do not call `{}`, the Cargo project may have moved",
+                    expected_script
+                ),
+                "Regenerate the project with `xcode_uniffi create --force`".to_string(),
+            ));
+        }
+        checks
+    }
+
+    fn cargo_failure(error: XcodeUniffiError) -> DoctorCheck {
+        let (name, remedy) = match &error {
+            XcodeUniffiError::MissingStaticlib { .. } => {
+                ("cargo-manifest", "Run `xcode_uniffi create --fix-manifest`")
+            }
+            XcodeUniffiError::NoUdlFiles(_) => (
+                "udl-files",
+                "See https://mozilla.github.io/uniffi-rs/udl_file_spec.html",
+            ),
+            _ => ("cargo-manifest", "Fix Cargo.toml and run doctor again"),
+        };
+        DoctorCheck::fail(name, error.to_string(), remedy.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xcodebind_gen::{CargoPackage, MemoryFileSystem, XcodeProject, XcodeProjectOptions};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::rc::Rc;
+
+    const MANIFEST: &str = "/work/my_lib/Cargo.toml";
+
+    /// Answers every program with a canned output, `cargo metadata` under the name `cargo`.
+    /// Programs without an answer are not installed.
+    #[derive(Default)]
+    struct FakeRunner {
+        outputs: HashMap<&'static str, CommandOutput>,
+    }
+
+    impl FakeRunner {
+        fn with(mut self, program: &'static str, success: bool, stdout: &str) -> Self {
+            let output = CommandOutput {
+                success,
+                stdout: stdout.to_string(),
+                stderr: if success { "" } else { "error: failed" }.to_string(),
+            };
+            self.outputs.insert(program, output);
+            self
+        }
+
+        /// Toolchain and package every check passes with.
+        fn healthy() -> Self {
+            FakeRunner::default()
+                .with("rustup", true, &REQUIRED_RUST_TARGETS.join("\n"))
+                .with("uniffi-bindgen", true, "uniffi-bindgen 0.16.0\n")
+                .with("cargo", true, &metadata(&["staticlib"], true))
+        }
+    }
+
+    impl CommandRunner for FakeRunner {
+        fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+            let program = match args.first() {
+                Some(&"metadata") => "cargo",
+                _ => program,
+            };
+            self.outputs
+                .get(program)
+                .cloned()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, program.to_string()))
+        }
+    }
+
+    /// `cargo metadata --no-deps` output for the `my_lib` package at `MANIFEST`.
+    fn metadata(crate_types: &[&str], uniffi: bool) -> String {
+        let dependency = |name: &str, kind: Option<&str>| {
+            json!({
+                "name": name,
+                "req": "^0.16.0",
+                "kind": kind,
+                "optional": false,
+                "uses_default_features": true,
+                "features": [],
+            })
+        };
+        let dependencies = match uniffi {
+            true => vec![
+                dependency("uniffi", None),
+                dependency("uniffi_build", Some("build")),
+            ],
+            false => vec![],
+        };
+        json!({
+            "packages": [{
+                "name": "my_lib",
+                "version": "0.1.0",
+                "id": "my_lib 0.1.0 (path+file:///work/my_lib)",
+                "dependencies": dependencies,
+                "targets": [{
+                    "name": "my_lib",
+                    "kind": crate_types,
+                    "crate_types": crate_types,
+                    "src_path": "/work/my_lib/src/lib.rs",
+                }],
+                "features": {},
+                "manifest_path": MANIFEST,
+            }],
+            "workspace_members": ["my_lib 0.1.0 (path+file:///work/my_lib)"],
+            "workspace_root": "/work/my_lib",
+            "target_directory": "/work/my_lib/target",
+            "version": 1,
+        })
+        .to_string()
+    }
+
+    /// Package sources, and the Xcode project `create` generates for them when `with_project`.
+    fn file_system(runner: &FakeRunner, with_project: bool) -> Rc<MemoryFileSystem> {
+        let fs = Rc::new(MemoryFileSystem::new());
+        fs.write(Path::new(MANIFEST), "").unwrap();
+        fs.write(Path::new("/work/my_lib/src/lib.rs"), "").unwrap();
+        fs.write(
+            Path::new("/work/my_lib/src/my_lib.udl"),
+            "namespace my_lib {};",
+        )
+        .unwrap();
+        if with_project {
+            let package = CargoPackage::builder(MANIFEST)
+                .build_with(runner, &*fs)
+                .unwrap();
+            XcodeProject::with_file_system(package, XcodeProjectOptions::default(), fs.clone())
+                .create()
+                .unwrap();
+        }
+        fs
+    }
+
+    fn status(checks: &[DoctorCheck], name: &str) -> Vec<CheckStatus> {
+        checks
+            .iter()
+            .filter(|check| check.name == name)
+            .map(|check| check.status)
+            .collect()
+    }
+
+    fn run(runner: &FakeRunner, fs: &MemoryFileSystem) -> Vec<DoctorCheck> {
+        Doctor::new(runner, fs).run(CargoPackage::builder(MANIFEST))
+    }
+
+    #[test]
+    fn healthy_project_passes_every_check() {
+        let runner = FakeRunner::healthy();
+        let fs = file_system(&runner, true);
+        let checks = run(&runner, &fs);
+        for check in &checks {
+            assert_eq!(check.status, CheckStatus::Pass, "{:?}", check);
+        }
+        let names: Vec<&str> = checks.iter().map(|check| check.name).collect();
+        assert_eq!(
+            names,
+            [
+                "rust-targets",
+                "uniffi-bindgen",
+                "cargo-manifest",
+                "udl-files",
+                "xcode-project",
+                "framework-name",
+                "library-search-paths",
+                "cargo-relative-path",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_rust_targets_fail() {
+        let runner = FakeRunner::healthy().with("rustup", true, "x86_64-apple-darwin\n");
+        let check = Doctor::new(&runner, &MemoryFileSystem::new()).check_rust_targets();
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check
+            .remedy
+            .unwrap()
+            .starts_with("rustup target add aarch64-apple-darwin"));
+
+        let runner = FakeRunner::healthy().with("rustup", false, "");
+        let check = Doctor::new(&runner, &MemoryFileSystem::new()).check_rust_targets();
+        assert_eq!(check.status, CheckStatus::Fail);
+
+        let runner = FakeRunner::default();
+        let check = Doctor::new(&runner, &MemoryFileSystem::new()).check_rust_targets();
+        assert_eq!(check.status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn uniffi_bindgen_version_is_checked() {
+        let runner = FakeRunner::healthy().with("uniffi-bindgen", true, "uniffi-bindgen 0.17.0");
+        let check = Doctor::new(&runner, &MemoryFileSystem::new()).check_uniffi_bindgen();
+        assert_eq!(check.status, CheckStatus::Warn);
+
+        let runner = FakeRunner::default();
+        let check = Doctor::new(&runner, &MemoryFileSystem::new()).check_uniffi_bindgen();
+        assert_eq!(check.status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn missing_staticlib_fails_the_manifest_check() {
+        let runner = FakeRunner::healthy().with("cargo", true, &metadata(&["cdylib"], true));
+        let fs = file_system(&runner, false);
+        let checks = run(&runner, &fs);
+        assert_eq!(status(&checks, "cargo-manifest"), [CheckStatus::Fail]);
+        assert!(status(&checks, "xcode-project").is_empty());
+    }
+
+    #[test]
+    fn failing_cargo_metadata_fails_the_manifest_check() {
+        let runner = FakeRunner::healthy().with("cargo", false, "");
+        let fs = file_system(&runner, false);
+        let checks = run(&runner, &fs);
+        assert_eq!(status(&checks, "cargo-manifest"), [CheckStatus::Fail]);
+        assert!(checks[2].message.contains("error: failed"));
+    }
+
+    #[test]
+    fn missing_udl_files_fail() {
+        let runner = FakeRunner::healthy();
+        let fs = MemoryFileSystem::new();
+        fs.write(Path::new(MANIFEST), "").unwrap();
+        fs.write(Path::new("/work/my_lib/src/lib.rs"), "").unwrap();
+        let checks = run(&runner, &fs);
+        assert_eq!(status(&checks, "udl-files"), [CheckStatus::Fail]);
+    }
+
+    #[test]
+    fn missing_uniffi_dependencies_warn() {
+        let runner = FakeRunner::healthy().with("cargo", true, &metadata(&["staticlib"], false));
+        let fs = file_system(&runner, true);
+        let checks = run(&runner, &fs);
+        assert_eq!(
+            status(&checks, "uniffi-dependencies"),
+            [CheckStatus::Warn, CheckStatus::Warn]
+        );
+    }
+
+    #[test]
+    fn missing_xcode_project_warns() {
+        let runner = FakeRunner::healthy();
+        let fs = file_system(&runner, false);
+        let checks = run(&runner, &fs);
+        assert_eq!(status(&checks, "xcode-project"), [CheckStatus::Warn]);
+    }
+
+    #[test]
+    fn moved_cargo_project_fails_the_path_checks() {
+        let runner = FakeRunner::healthy();
+        let fs = file_system(&runner, true);
+        let pbxproj = Path::new("/work/my_lib/xcode/MyLib/MyLib.xcodeproj/project.pbxproj");
+        let moved = fs
+            .read_to_string(pbxproj)
+            .unwrap()
+            .replace("../..", "../../..");
+        fs.write(pbxproj, &moved).unwrap();
+        let checks = run(&runner, &fs);
+        assert_eq!(status(&checks, "xcode-project"), [CheckStatus::Pass]);
+        assert_eq!(status(&checks, "library-search-paths"), [CheckStatus::Fail]);
+        assert_eq!(status(&checks, "cargo-relative-path"), [CheckStatus::Fail]);
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Errors returned by project generation and pbxproj parsing.
+#[derive(Debug)]
+pub enum XcodeUniffiError {
+    /// No Cargo.toml at the given path.
+    ManifestNotFound(PathBuf),
+    /// `cargo metadata` failed, usually because Cargo.toml is malformed.
+    MetadataFailed { manifest_path: PathBuf, message: String },
+    /// The requested package (or any package, when `package_name` is `None`) is not in the manifest.
+    PackageNotFound {
+        manifest_path: PathBuf,
+        package_name: Option<String>,
+    },
+    /// The package has no `[lib]` target (or none named `lib_name`).
+    NoLibTarget {
+        manifest_path: PathBuf,
+        package_name: String,
+        lib_name: Option<String>,
+    },
+    /// The library target does not list `staticlib` in its `crate-type`.
+    MissingStaticlib {
+        manifest_path: PathBuf,
+        package_name: String,
+    },
+    /// No `*.udl` file in the package `src` directory.
+    NoUdlFiles(PathBuf),
+    /// Cargo.toml could not be edited by `ManifestEditor`.
+    ManifestEdit { manifest_path: PathBuf, message: String },
+    /// The Xcode framework path exists but is a file.
+    ProjectPathIsFile(PathBuf),
+    /// Another `.xcodeproj` with a different name lives in the framework directory.
+    ConflictingProject { directory: PathBuf, name: String },
+    /// `update` found no `<framework name>.xcodeproj` in the framework directory.
+    ProjectNotFound(PathBuf),
+    /// `path` can't be written relative to `base`, e.g. one is absolute and the other relative.
+    NoRelativePath { path: PathBuf, base: PathBuf },
+    /// The pbxproj text is not a valid OpenStep plist.
+    Parse(String),
+    /// A project could not be converted to or from another format, e.g. JSON.
+    Convert(String),
+    /// A path given to `XcodePbxProject::query`, `set` or `remove` is malformed.
+    Query(String),
+    /// Build settings could not be evaluated, e.g. the target or configuration does not exist.
+    BuildSettings(String),
+    /// An xcconfig file is malformed or its `#include`s form a cycle.
+    Xcconfig(String),
+    /// The command line named a subcommand this build does not handle.
+    UnsupportedCommand(String),
+    Io { path: PathBuf, source: io::Error },
+}
+
+pub type Result<T> = std::result::Result<T, XcodeUniffiError>;
+
+impl XcodeUniffiError {
+    pub(crate) fn io(path: &Path, source: io::Error) -> Self {
+        XcodeUniffiError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    /// Stable, machine readable name of the error, used by `--message-format json`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            XcodeUniffiError::ManifestNotFound(_) => "manifest-not-found",
+            XcodeUniffiError::MetadataFailed { .. } => "metadata-failed",
+            XcodeUniffiError::PackageNotFound { .. } => "package-not-found",
+            XcodeUniffiError::NoLibTarget { .. } => "no-lib-target",
+            XcodeUniffiError::MissingStaticlib { .. } => "missing-staticlib",
+            XcodeUniffiError::NoUdlFiles(_) => "no-udl-files",
+            XcodeUniffiError::ManifestEdit { .. } => "manifest-edit",
+            XcodeUniffiError::ProjectPathIsFile(_) => "project-path-is-file",
+            XcodeUniffiError::ConflictingProject { .. } => "conflicting-project",
+            XcodeUniffiError::ProjectNotFound(_) => "project-not-found",
+            XcodeUniffiError::NoRelativePath { .. } => "no-relative-path",
+            XcodeUniffiError::Parse(_) => "parse",
+            XcodeUniffiError::Convert(_) => "convert",
+            XcodeUniffiError::Query(_) => "query",
+            XcodeUniffiError::BuildSettings(_) => "build-settings",
+            XcodeUniffiError::Xcconfig(_) => "xcconfig",
+            XcodeUniffiError::UnsupportedCommand(_) => "unsupported-command",
+            XcodeUniffiError::Io { .. } => "io",
+        }
+    }
+}
+
+impl Display for XcodeUniffiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            XcodeUniffiError::ManifestNotFound(path) => write!(
+                f,
+                "No Cargo.toml file found at: {:?}\n\
+                Run the command inside your Rust project or pass its path with --cargo/-r.",
+                path
+            ),
+            XcodeUniffiError::MetadataFailed {
+                manifest_path,
+                message,
+            } => write!(
+                f,
+                "Can't parse cargo metadata in {:?} because: {}\n\
+                Check that `cargo metadata --no-deps` succeeds for this manifest.",
+                manifest_path, message
+            ),
+            XcodeUniffiError::PackageNotFound {
+                manifest_path,
+                package_name: Some(name),
+            } => write!(
+                f,
+                "Specified package name '{}' not found in your Cargo.toml file at: {:?}\n\
+                Check the name passed with --package-name/-k.",
+                name, manifest_path
+            ),
+            XcodeUniffiError::PackageNotFound {
+                manifest_path,
+                package_name: None,
+            } => write!(
+                f,
+                "No Rust package found in your Cargo.toml file at: {:?}\n\
+                Point --cargo/-r at the Cargo.toml of your library package, not at a virtual workspace manifest.",
+                manifest_path
+            ),
+            XcodeUniffiError::NoLibTarget {
+                manifest_path,
+                package_name,
+                lib_name: Some(lib_name),
+            } => write!(
+                f,
+                "No library named '{}' found in cargo package '{}' in {:?}\n\
+                Check the name passed with --lib-name/-l, it must match `name` in the [lib] section.",
+                lib_name, package_name, manifest_path
+            ),
+            XcodeUniffiError::NoLibTarget {
+                manifest_path,
+                package_name,
+                lib_name: None,
+            } => write!(
+                f,
+                "No target of type [\"lib\"] found in cargo package '{}' in {:?}\n\
+                Xcode framework project could only be created for Cargo 'library' targets, add a [lib] section with src/lib.rs.",
+                package_name, manifest_path
+            ),
+            XcodeUniffiError::MissingStaticlib {
+                manifest_path,
+                package_name,
+            } => write!(
+                f,
+                "No crate_type of type [\"staticlib\"] found in cargo package '{}' in {:?}\n\
+                Run again with --fix-manifest or add the following to the [lib] section of Cargo.toml:\n\n\
+                crate-type = [\"staticlib\", \"cdylib\"]",
+                package_name, manifest_path
+            ),
+            XcodeUniffiError::NoUdlFiles(directory) => write!(
+                f,
+                "No UDL files found in {:?}\n\
+                Describe your interface in a *.udl file next to lib.rs. \
+                Refer to Rust UniFFI: https://mozilla.github.io/uniffi-rs/udl_file_spec.html",
+                directory
+            ),
+            XcodeUniffiError::ManifestEdit {
+                manifest_path,
+                message,
+            } => write!(
+                f,
+                "Unable to update {:?}: {}\nEdit the [lib] section by hand.",
+                manifest_path, message
+            ),
+            XcodeUniffiError::ProjectPathIsFile(path) => write!(
+                f,
+                "Specified Xcode project Path is a file and not a directory: {:?}\n\
+                Xcode framework path must be directory path and not a file path.",
+                path
+            ),
+            XcodeUniffiError::ConflictingProject { directory, name } => write!(
+                f,
+                "An other xcode framework project exist in the same directory with different name: {} in {:?}",
+                name, directory
+            ),
+            XcodeUniffiError::ProjectNotFound(path) => write!(
+                f,
+                "No Xcode project to update found at: {:?}\n\
+                Create it first with `xcode_uniffi create`, or pass its directory with --path/-p.",
+                path
+            ),
+            XcodeUniffiError::NoRelativePath { path, base } => write!(
+                f,
+                "Unable to get the path of {:?} relative to the Xcode project directory {:?}\n\
+                Pass absolute paths with --cargo/-r and --path/-p.",
+                path, base
+            ),
+            XcodeUniffiError::Parse(message) => {
+                write!(f, "Xcode Project plist format is invalid. {}", message)
+            }
+            XcodeUniffiError::Convert(message) => {
+                write!(f, "Unable to convert the project: {}", message)
+            }
+            XcodeUniffiError::Query(message) => write!(f, "Invalid path: {}", message),
+            XcodeUniffiError::BuildSettings(message) => {
+                write!(f, "Unable to evaluate build settings: {}", message)
+            }
+            XcodeUniffiError::Xcconfig(message) => write!(f, "Invalid xcconfig file: {}", message),
+            XcodeUniffiError::UnsupportedCommand(name) => {
+                write!(f, "The `{}` command is not supported.", name)
+            }
+            XcodeUniffiError::Io { path, source } => write!(f, "{:?}: {}", path, source),
+        }
+    }
+}
+
+impl std::error::Error for XcodeUniffiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            XcodeUniffiError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use similar::TextDiff;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Filesystem operations used while generating a project.
+/// `XcodeProject` only touches the disk through this trait, so generation can run against
+/// memory (dry runs, tests) as well as against the real filesystem.
+pub trait FileSystem {
+    fn exists(&self, path: &Path) -> bool;
+    fn is_dir(&self, path: &Path) -> bool;
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()>;
+}
+
+pub struct RealFileSystem {}
+
+impl FileSystem for RealFileSystem {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect()
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()> {
+        std::fs::write(path, contents)
+    }
+}
+
+/// Keeps every write in memory.
+/// With `read_through` enabled, paths that were not written are read from the real disk,
+/// which is what `--dry-run` needs to compare generated files against existing ones.
+#[derive(Default)]
+pub struct MemoryFileSystem {
+    files: RefCell<BTreeMap<PathBuf, String>>,
+    dirs: RefCell<BTreeSet<PathBuf>>,
+    read_through: bool,
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        MemoryFileSystem::default()
+    }
+
+    /// Memory filesystem layered over the real disk.
+    pub fn overlay() -> Self {
+        MemoryFileSystem {
+            read_through: true,
+            ..MemoryFileSystem::default()
+        }
+    }
+
+    /// All files written so far, sorted by path.
+    pub fn written_files(&self) -> Vec<(PathBuf, String)> {
+        self.files
+            .borrow()
+            .iter()
+            .map(|(path, contents)| (path.clone(), contents.clone()))
+            .collect()
+    }
+
+    /// Unified diff of every written file against its current contents on disk.
+    /// Files that would not change are skipped.
+    pub fn diff_against_disk(&self) -> Vec<(PathBuf, String)> {
+        let disk = RealFileSystem {};
+        self.written_files()
+            .into_iter()
+            .filter_map(|(path, contents)| {
+                let (old, old_header) = match disk.read_to_string(&path) {
+                    Ok(old) => (old, format!("a{}", path.display())),
+                    Err(_) => (String::new(), "/dev/null".to_string()),
+                };
+                if old == contents {
+                    return None;
+                }
+                let diff = TextDiff::from_lines(&old, &contents)
+                    .unified_diff()
+                    .header(&old_header, &format!("b{}", path.display()))
+                    .to_string();
+                Some((path, diff))
+            })
+            .collect()
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn exists(&self, path: &Path) -> bool {
+        self.files.borrow().contains_key(path)
+            || self.dirs.borrow().contains(path)
+            || (self.read_through && path.exists())
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.dirs.borrow().contains(path) || (self.read_through && path.is_dir())
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut entries: BTreeSet<PathBuf> = if self.read_through && path.is_dir() {
+            RealFileSystem {}.read_dir(path)?.into_iter().collect()
+        } else if self.dirs.borrow().contains(path) {
+            BTreeSet::new()
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{:?} is not a directory", path),
+            ));
+        };
+        let files = self.files.borrow();
+        let dirs = self.dirs.borrow();
+        entries.extend(
+            files
+                .keys()
+                .chain(dirs.iter())
+                .filter(|p| p.parent() == Some(path))
+                .cloned(),
+        );
+        Ok(entries.into_iter().collect())
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        if let Some(contents) = self.files.borrow().get(path) {
+            return Ok(contents.clone());
+        }
+        if self.read_through {
+            return std::fs::read_to_string(path);
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{:?} not found", path),
+        ))
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut dirs = self.dirs.borrow_mut();
+        for ancestor in path.ancestors() {
+            if ancestor.as_os_str().is_empty() {
+                break;
+            }
+            dirs.insert(ancestor.to_path_buf());
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent)?;
+        }
+        self.files
+            .borrow_mut()
+            .insert(path.to_path_buf(), contents.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_file_system_keeps_writes_in_memory() {
+        let fs = MemoryFileSystem::new();
+        let path = Path::new("/work/App/App.xcodeproj/project.pbxproj");
+        fs.write(path, "// !$*UTF8*$!\n").unwrap();
+
+        assert_eq!(fs.read_to_string(path).unwrap(), "// !$*UTF8*$!\n");
+        assert!(fs.is_dir(Path::new("/work/App/App.xcodeproj")));
+        assert!(fs.exists(Path::new("/work")));
+        assert!(!fs.is_dir(path));
+        assert_eq!(
+            fs.read_dir(Path::new("/work/App")).unwrap(),
+            [PathBuf::from("/work/App/App.xcodeproj")]
+        );
+        assert!(!Path::new("/work/App").exists());
+
+        let missing = fs
+            .read_to_string(Path::new("/work/App/missing"))
+            .unwrap_err();
+        assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+        assert_eq!(
+            fs.read_dir(path).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(
+            fs.written_files(),
+            [(path.to_path_buf(), "// !$*UTF8*$!\n".to_string())]
+        );
+    }
+
+    #[test]
+    fn overlay_reads_through_and_diffs_against_disk() {
+        let test_data = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data");
+        let project = test_data.join("project.pbxproj");
+        let on_disk = std::fs::read_to_string(&project).unwrap();
+        let fs = MemoryFileSystem::overlay();
+        assert_eq!(fs.read_to_string(&project).unwrap(), on_disk);
+        assert!(fs.read_dir(&test_data).unwrap().contains(&project));
+
+        // an unchanged file has no diff, a changed one shadows the disk
+        let unchanged = test_data.join("value_forms.pbxproj");
+        fs.write(&unchanged, &std::fs::read_to_string(&unchanged).unwrap())
+            .unwrap();
+        fs.write(&project, &on_disk.replace("HiTel", "Renamed"))
+            .unwrap();
+        let new_file = test_data.join("new.xcconfig");
+        fs.write(&new_file, "SWIFT_VERSION = 5.0\n").unwrap();
+        assert!(fs.read_to_string(&project).unwrap().contains("Renamed"));
+        assert!(!new_file.exists());
+
+        let diffs = fs.diff_against_disk();
+        let paths: Vec<&PathBuf> = diffs.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, [&new_file, &project]);
+        assert!(diffs[0].1.starts_with("--- /dev/null\n"));
+        assert!(diffs[1]
+            .1
+            .starts_with(&format!("--- a{}\n", project.display())));
+        assert!(diffs[1].1.contains("\n+\t\t\tproductName = Renamed;\n"));
+    }
+}
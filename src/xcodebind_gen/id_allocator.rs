@@ -0,0 +1,394 @@
+use super::{PlistItem, XcodePbxProject};
+use crc::{Crc, CRC_64_ECMA_182};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Hands out the 24 hex digit object IDs of a pbxproj object graph.
+///
+/// IDs are derived from a CRC of the seed (the Cargo target name), the object kind and the object
+/// name, so regenerating a project yields the same IDs. Asking twice for the same kind and name
+/// returns the same ID. When a derived ID is already taken, by another object or by an ID
+/// passed to `reserve`, it is rehashed with an increasing salt until it is free.
+pub struct IdAllocator {
+    crc: Crc<u64>,
+    id_base: u64,
+    allocated: RefCell<BTreeMap<(String, String), String>>,
+    used: RefCell<BTreeSet<String>>,
+    legacy: RefCell<BTreeSet<String>>,
+}
+
+impl IdAllocator {
+    pub fn new(seed: &str) -> Self {
+        let crc = Crc::<u64>::new(&CRC_64_ECMA_182);
+        let id_base = crc.checksum(seed.as_bytes());
+        IdAllocator {
+            crc,
+            id_base,
+            allocated: RefCell::new(BTreeMap::new()),
+            used: RefCell::new(BTreeSet::new()),
+            legacy: RefCell::new(BTreeSet::new()),
+        }
+    }
+
+    /// Marks `id` as taken, e.g. by an object of an existing project that is kept as is.
+    pub fn reserve(&self, id: &str) {
+        self.used.borrow_mut().insert(id.to_string());
+    }
+
+    /// Reserves the ID of every object in the `objects` dictionary of `project`.
+    pub fn reserve_project(&self, project: &XcodePbxProject) {
+        for id in object_ids(project) {
+            self.reserve(&id);
+        }
+    }
+
+    /// Lets `allocate` return the IDs older versions derived for the objects of `project`.
+    /// They cut the name part of the ID short instead of padding it, so about one object in 16
+    /// of a project they created has an ID that regenerating must keep.
+    pub fn reuse_legacy_ids(&self, project: &XcodePbxProject) {
+        self.legacy.borrow_mut().extend(object_ids(project));
+    }
+
+    pub fn is_used(&self, id: &str) -> bool {
+        self.used.borrow().contains(id)
+    }
+
+    /// ID of the object `name` of the given `kind` (usually its `isa`).
+    pub fn allocate(&self, kind: &str, name: &str) -> String {
+        let key = (kind.to_string(), name.to_string());
+        if let Some(id) = self.allocated.borrow().get(&key) {
+            return id.clone();
+        }
+        let mut used = self.used.borrow_mut();
+        let legacy = self.legacy.borrow();
+        let mut salt = 0;
+        let id = loop {
+            let (kind, name) = self.derive(kind, name, salt);
+            let legacy_id = legacy_id(kind, name);
+            if legacy.contains(&legacy_id) && !used.contains(&legacy_id) {
+                break legacy_id;
+            }
+            let id = format!("CA60{:08X}{:012X}", kind, name >> 16);
+            if !used.contains(&id) {
+                break id;
+            }
+            salt += 1;
+        };
+        used.insert(id.clone());
+        self.allocated.borrow_mut().insert(key, id.clone());
+        id
+    }
+
+    /// CRCs of the kind and of the name; the top 48 bits of the name CRC are 12 hex digits,
+    /// which makes IDs 24 digits long.
+    fn derive(&self, kind: &str, name: &str, salt: u32) -> (u32, u64) {
+        let mut digest = self.crc.digest();
+        digest.update(&self.id_base.to_le_bytes());
+        digest.update(kind.as_bytes());
+        let kind = digest.finalize();
+
+        let mut digest = self.crc.digest();
+        digest.update(name.as_bytes());
+        if salt > 0 {
+            digest.update(&salt.to_le_bytes());
+        }
+        (kind as u32, digest.finalize())
+    }
+}
+
+/// ID of older versions: the first 12 significant hex digits of the name CRC.
+fn legacy_id(kind: u32, name: u64) -> String {
+    let mut id = format!("CA60{:08X}{:012X}", kind, name);
+    id.truncate(24);
+    id
+}
+
+fn object_ids(project: &XcodePbxProject) -> Vec<String> {
+    let mut ids = vec![];
+    for item in project.items() {
+        let objects = match item {
+            PlistItem::Item(kv) if kv.key.name == "objects" => kv.value.as_ref(),
+            _ => continue,
+        };
+        if let PlistItem::Array(items) = objects {
+            for item in items {
+                match item {
+                    PlistItem::SectionItem(section) => {
+                        for object in &section.items {
+                            if let PlistItem::Item(object) = object {
+                                ids.push(object.key.name.to_string());
+                            }
+                        }
+                    }
+                    PlistItem::Item(object) => ids.push(object.key.name.to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const KINDS: [&str; 6] = [
+        "PBXBuildFile",
+        "PBXFileReference",
+        "PBXGroup",
+        "PBXNativeTarget",
+        "XCBuildConfiguration",
+        "XCConfigurationList",
+    ];
+
+    /// `(kind, name)` pairs standing in for the objects of many projects.
+    fn inputs(count: usize) -> Vec<(&'static str, String)> {
+        (0..count)
+            .map(|i| (KINDS[i % KINDS.len()], format!("file_{}.udl-{}", i, i % 7)))
+            .collect()
+    }
+
+    fn is_object_id(id: &str) -> bool {
+        id.len() == 24
+            && id
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() && !c.is_lowercase())
+    }
+
+    #[test]
+    fn ids_are_deterministic() {
+        let first = IdAllocator::new("my_lib");
+        let second = IdAllocator::new("my_lib");
+        for (kind, name) in inputs(1_000) {
+            let id = first.allocate(kind, &name);
+            assert_eq!(first.allocate(kind, &name), id);
+            assert_eq!(second.allocate(kind, &name), id);
+        }
+        assert_ne!(
+            IdAllocator::new("other_lib").allocate("PBXGroup", "Sources"),
+            IdAllocator::new("my_lib").allocate("PBXGroup", "Sources")
+        );
+    }
+
+    #[test]
+    fn ids_are_unique_and_fixed_width() {
+        let ids = IdAllocator::new("my_lib");
+        let inputs = inputs(100_000);
+        let allocated: HashSet<String> = inputs
+            .iter()
+            .map(|(kind, name)| ids.allocate(kind, name))
+            .collect();
+        assert_eq!(allocated.len(), inputs.len());
+        for id in &allocated {
+            assert!(is_object_id(id), "{}", id);
+        }
+        // short names give small CRCs, which must still be padded
+        for name in ["", "a", "0"] {
+            assert!(is_object_id(&ids.allocate("PBXGroup", name)));
+        }
+    }
+
+    #[test]
+    fn reserved_ids_are_never_allocated() {
+        let inputs = inputs(1_000);
+        let taken: Vec<String> = {
+            let ids = IdAllocator::new("my_lib");
+            inputs
+                .iter()
+                .map(|(kind, name)| ids.allocate(kind, name))
+                .collect()
+        };
+
+        let ids = IdAllocator::new("my_lib");
+        for id in &taken {
+            ids.reserve(id);
+        }
+        let again: Vec<String> = inputs
+            .iter()
+            .map(|(kind, name)| ids.allocate(kind, name))
+            .collect();
+        for id in &again {
+            assert!(!taken.contains(id));
+            assert!(is_object_id(id));
+        }
+        assert_eq!(again.iter().collect::<HashSet<_>>().len(), again.len());
+
+        // rehashing is deterministic too
+        let other = IdAllocator::new("my_lib");
+        for id in &taken {
+            other.reserve(id);
+        }
+        assert_eq!(other.allocate(inputs[0].0, &inputs[0].1), again[0]);
+    }
+
+    #[test]
+    fn reserve_project_reserves_every_object() {
+        let ids = IdAllocator::new("my_lib");
+        let group = ids.allocate("PBXGroup", "Sources");
+        let file = ids.allocate("PBXFileReference", "my_lib.udl");
+        let project = XcodePbxProject::parse_from_string(&format!(
+            "// !$*UTF8*$!\n{{\n\tobjects = {{\n\n/* Begin PBXGroup section */\n\t\t{} = {{isa = PBXGroup; children = ({}); }};\n/* End PBXGroup section */\n\t\t{} = {{isa = PBXFileReference; path = my_lib.udl; }};\n\t}};\n}}\n",
+            group, file, file
+        ))
+        .unwrap();
+
+        let ids = IdAllocator::new("my_lib");
+        ids.reserve_project(&project);
+        assert!(ids.is_used(&group));
+        assert!(ids.is_used(&file));
+        assert_ne!(ids.allocate("PBXGroup", "Sources"), group);
+        assert_ne!(ids.allocate("PBXFileReference", "my_lib.udl"), file);
+    }
+
+    #[test]
+    fn legacy_ids_of_an_existing_project_are_reused() {
+        let ids = IdAllocator::new("my_lib");
+        let (kind, name) = inputs(1_000)
+            .into_iter()
+            .find(|(kind, name)| {
+                let (kind, name) = ids.derive(kind, name, 0);
+                legacy_id(kind, name) != format!("CA60{:08X}{:012X}", kind, name >> 16)
+            })
+            .unwrap();
+        let (kind_crc, name_crc) = ids.derive(kind, &name, 0);
+        let legacy = legacy_id(kind_crc, name_crc);
+        assert!(is_object_id(&legacy));
+        let project = XcodePbxProject::parse_from_string(&format!(
+            "// !$*UTF8*$!\n{{\n\tobjects = {{\n\t\t{} = {{isa = {}; }};\n\t}};\n}}\n",
+            legacy, kind
+        ))
+        .unwrap();
+
+        let ids = IdAllocator::new("my_lib");
+        ids.reuse_legacy_ids(&project);
+        assert_eq!(ids.allocate(kind, &name), legacy);
+        // reserved, e.g. because the object is not the generated one, it is not reused
+        let ids = IdAllocator::new("my_lib");
+        ids.reuse_legacy_ids(&project);
+        ids.reserve_project(&project);
+        assert_ne!(ids.allocate(kind, &name), legacy);
+    }
+
+    fn project_with(objects: &[(String, &str)]) -> XcodePbxProject {
+        let objects: String = objects
+            .iter()
+            .map(|(id, kind)| format!("\t\t{} = {{isa = {}; }};\n", id, kind))
+            .collect();
+        XcodePbxProject::parse_from_string(&format!(
+            "// !$*UTF8*$!\n{{\n\tobjects = {{\n{}\t}};\n}}\n",
+            objects
+        ))
+        .unwrap()
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::collection::{btree_set, vec};
+        use proptest::prelude::*;
+        use proptest::sample::select;
+
+        /// Distinct `(kind, name)` pairs with arbitrary names.
+        fn objects(max: usize) -> impl Strategy<Value = Vec<(&'static str, String)>> {
+            btree_set((select(KINDS.to_vec()), any::<String>()), 1..max)
+                .prop_map(|objects| objects.into_iter().collect())
+        }
+
+        fn object_id() -> impl Strategy<Value = String> {
+            "CA60[0-9A-F]{20}|[0-9A-F]{24}"
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(64))]
+
+            #[test]
+            fn allocated_ids_are_unique_and_stable(seed in ".{0,24}", objects in objects(300)) {
+                let ids = IdAllocator::new(&seed);
+                let again = IdAllocator::new(&seed);
+                let mut allocated = HashSet::new();
+                for (kind, name) in &objects {
+                    let id = ids.allocate(kind, name);
+                    prop_assert!(is_object_id(&id), "{}", id);
+                    prop_assert_eq!(&again.allocate(kind, name), &id);
+                    prop_assert_eq!(&ids.allocate(kind, name), &id);
+                    prop_assert!(allocated.insert(id));
+                }
+            }
+
+            #[test]
+            fn collisions_are_rehashed_around_reserved_ids(
+                seed in "[a-z_]{1,16}",
+                objects in objects(100),
+                taken in vec(any::<prop::sample::Index>(), 0..100),
+                random_ids in vec(object_id(), 0..50),
+            ) {
+                // reserve the IDs a first allocator derived for some objects, plus random ones
+                let first = IdAllocator::new(&seed);
+                let derived: Vec<String> = objects
+                    .iter()
+                    .map(|(kind, name)| first.allocate(kind, name))
+                    .collect();
+                let reserved: BTreeSet<String> = taken
+                    .iter()
+                    .map(|index| index.get(&derived).clone())
+                    .chain(random_ids)
+                    .collect();
+
+                let ids = IdAllocator::new(&seed);
+                let again = IdAllocator::new(&seed);
+                for id in &reserved {
+                    ids.reserve(id);
+                    again.reserve(id);
+                }
+                let mut allocated = HashSet::new();
+                for ((kind, name), first_id) in objects.iter().zip(&derived) {
+                    let id = ids.allocate(kind, name);
+                    prop_assert!(is_object_id(&id), "{}", id);
+                    prop_assert!(!reserved.contains(&id));
+                    if !reserved.contains(first_id) {
+                        prop_assert_eq!(&id, first_id);
+                    }
+                    prop_assert_eq!(&again.allocate(kind, name), &id);
+                    prop_assert!(allocated.insert(id));
+                }
+            }
+
+            #[test]
+            fn legacy_ids_are_reused_for_their_objects(
+                seed in "[a-z_]{1,16}",
+                objects in objects(100),
+                kept in vec(any::<bool>(), 100),
+            ) {
+                let derive = IdAllocator::new(&seed);
+                let legacy: Vec<Option<String>> = objects
+                    .iter()
+                    .zip(&kept)
+                    .map(|((kind, name), kept)| {
+                        let (kind, name) = derive.derive(kind, name, 0);
+                        kept.then(|| legacy_id(kind, name))
+                    })
+                    .collect();
+                let legacy_objects: Vec<(String, &str)> = legacy
+                    .iter()
+                    .zip(&objects)
+                    .filter_map(|(id, (kind, _))| id.clone().map(|id| (id, *kind)))
+                    .collect();
+                let distinct: HashSet<&String> = legacy_objects.iter().map(|(id, _)| id).collect();
+                prop_assume!(distinct.len() == legacy_objects.len());
+
+                let ids = IdAllocator::new(&seed);
+                ids.reuse_legacy_ids(&project_with(&legacy_objects));
+                let mut allocated = HashSet::new();
+                for ((kind, name), legacy) in objects.iter().zip(&legacy) {
+                    let id = ids.allocate(kind, name);
+                    prop_assert!(is_object_id(&id), "{}", id);
+                    if let Some(legacy) = legacy {
+                        prop_assert_eq!(&id, legacy);
+                    }
+                    prop_assert!(allocated.insert(id));
+                }
+            }
+        }
+    }
+}
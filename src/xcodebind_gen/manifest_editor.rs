@@ -0,0 +1,149 @@
+use super::{Result, XcodeUniffiError};
+use std::path::Path;
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+/// Crate types written when the `[lib]` section has no `crate-type` yet (see README).
+const DEFAULT_CRATE_TYPES: [&str; 2] = ["staticlib", "cdylib"];
+
+/// Keys Cargo accepts for the crate types of `[lib]`.
+const CRATE_TYPE_KEYS: [&str; 2] = ["crate-type", "crate_type"];
+
+/// Edits Cargo.toml in place, keeping its formatting and comments.
+pub struct ManifestEditor {}
+
+impl ManifestEditor {
+    /// Adds `staticlib` to `crate-type` of the `[lib]` section, creating either when missing.
+    /// Returns `false` if the manifest already builds a static library.
+    pub fn add_staticlib_crate_type(manifest_path: &Path) -> Result<bool> {
+        let content = std::fs::read_to_string(manifest_path)
+            .map_err(|e| XcodeUniffiError::io(manifest_path, e))?;
+        match ManifestEditor::with_staticlib_crate_type(manifest_path, &content)? {
+            Some(content) => {
+                std::fs::write(manifest_path, content)
+                    .map_err(|e| XcodeUniffiError::io(manifest_path, e))?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// `content` of the manifest at `manifest_path` with `staticlib` added to the crate types
+    /// of `[lib]`, spelled `crate-type` or `crate_type`. `None` if it is already there.
+    fn with_staticlib_crate_type(manifest_path: &Path, content: &str) -> Result<Option<String>> {
+        let error = |message: String| XcodeUniffiError::ManifestEdit {
+            manifest_path: manifest_path.to_path_buf(),
+            message,
+        };
+        let mut document: DocumentMut = content
+            .parse()
+            .map_err(|e: toml_edit::TomlError| error(e.to_string()))?;
+
+        if !document.contains_key("lib") {
+            document.insert("lib", Item::Table(Table::new()));
+        }
+        let lib = document["lib"]
+            .as_table_like_mut()
+            .ok_or_else(|| error("`lib` is not a table".to_string()))?;
+
+        let key = CRATE_TYPE_KEYS
+            .into_iter()
+            .find(|key| lib.contains_key(key));
+        match key {
+            None => {
+                let crate_types: Array = DEFAULT_CRATE_TYPES.iter().copied().collect();
+                lib.insert("crate-type", value(crate_types));
+            }
+            Some(key) => {
+                let crate_types = lib
+                    .get_mut(key)
+                    .and_then(Item::as_array_mut)
+                    .ok_or_else(|| error(format!("`lib.{}` is not an array", key)))?;
+                if crate_types.iter().any(|f| f.as_str() == Some("staticlib")) {
+                    return Ok(None);
+                }
+                crate_types.push("staticlib");
+            }
+        }
+        Ok(Some(document.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(content: &str) -> Result<Option<String>> {
+        ManifestEditor::with_staticlib_crate_type(Path::new("Cargo.toml"), content)
+    }
+
+    const PACKAGE: &str = "[package]\nname = \"my_lib\" # the crate\nversion = \"0.1.0\"\n";
+
+    #[test]
+    fn missing_lib_section_is_added() {
+        assert_eq!(
+            add(PACKAGE).unwrap().unwrap(),
+            format!(
+                "{}\n[lib]\ncrate-type = [\"staticlib\", \"cdylib\"]\n",
+                PACKAGE
+            )
+        );
+    }
+
+    #[test]
+    fn lib_section_without_crate_type_gets_one() {
+        let content = format!("{}\n[lib]\nname = \"my_lib\"\n", PACKAGE);
+        assert_eq!(
+            add(&content).unwrap().unwrap(),
+            format!("{}crate-type = [\"staticlib\", \"cdylib\"]\n", content)
+        );
+    }
+
+    #[test]
+    fn staticlib_is_appended_to_an_existing_array() {
+        for key in CRATE_TYPE_KEYS {
+            let content = format!("{}\n[lib]\n{} = [\"cdylib\"]\n", PACKAGE, key);
+            let expected = format!(
+                "{}\n[lib]\n{} = [\"cdylib\", \"staticlib\"]\n",
+                PACKAGE, key
+            );
+            assert_eq!(add(&content).unwrap().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn staticlib_already_present_is_left_alone() {
+        for key in CRATE_TYPE_KEYS {
+            let content = format!("{}\n[lib]\n{} = [\"staticlib\"]\n", PACKAGE, key);
+            assert_eq!(add(&content).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn comments_and_formatting_are_kept() {
+        let content = "# my library\n[package]\nname   = \"my_lib\"  # aligned\n\n\
+                       [lib]\n# built for Xcode\ncrate-type = [\"cdylib\"] # for Android\n\n\
+                       [dependencies]\nuniffi = \"0.28\" # bindings\n";
+        let edited = add(content).unwrap().unwrap();
+        assert_eq!(
+            edited,
+            "# my library\n[package]\nname   = \"my_lib\"  # aligned\n\n\
+             [lib]\n# built for Xcode\ncrate-type = [\"cdylib\", \"staticlib\"] # for Android\n\n\
+             [dependencies]\nuniffi = \"0.28\" # bindings\n"
+        );
+    }
+
+    #[test]
+    fn invalid_manifests_are_reported() {
+        let kind = |content: &str| add(content).unwrap_err().kind();
+        assert_eq!(kind("[package"), "manifest-edit");
+        assert_eq!(kind("lib = 1\n"), "manifest-edit");
+        let error = add("[lib]\ncrate_type = \"staticlib\"\n").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("`lib.crate_type` is not an array"),
+            "{}",
+            error
+        );
+    }
+}
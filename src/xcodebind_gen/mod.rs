@@ -1,11 +1,27 @@
+mod build_settings;
 mod cargo_helper;
 mod cmd;
+mod doctor;
+mod error;
+mod file_system;
+mod id_allocator;
+mod manifest_editor;
 mod pbxproj_parser;
+mod reporter;
 mod xcode_project;
 mod source_file_generator;
+mod xcconfig;
 
-pub(crate) use cargo_helper::*;
+pub use build_settings::*;
+pub use cargo_helper::*;
 pub(crate) use cmd::*;
-pub(crate) use pbxproj_parser::*;
-pub(crate) use xcode_project::*;
+pub use doctor::*;
+pub use error::*;
+pub use file_system::*;
+pub use id_allocator::*;
+pub use manifest_editor::*;
+pub use pbxproj_parser::*;
+pub use reporter::*;
+pub use xcode_project::*;
 pub(crate) use source_file_generator::*;
+pub use xcconfig::*;
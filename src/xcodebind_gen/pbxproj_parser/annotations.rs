@@ -0,0 +1,289 @@
+//! Comments Xcode derives from the object graph, e.g. `/* Foo.swift in Sources */` after the ID
+//! of a build file or `/* Build configuration list for PBXNativeTarget "App" */`.
+
+use std::collections::HashMap;
+
+use crate::xcodebind_gen::types::*;
+
+use super::XcodePbxProject;
+
+/// Keys whose value is an object ID Xcode writes without a comment.
+const UNANNOTATED_KEYS: [&str; 2] = ["remoteGlobalIDString", "TestTargetID"];
+
+impl XcodePbxProject {
+    /// Sets the comment of every object ID, in `objects` keys and in values, to the one Xcode
+    /// derives from the referenced object: file and group names, `<file> in <phase>` for build
+    /// files, target and configuration names, phase names, `Project object`, ...
+    ///
+    /// IDs whose object gives no name (e.g. a main group without `name` or `path`, or the
+    /// configuration list of the project, named after the `.xcodeproj`) keep their comment.
+    /// `serialize` annotates the text it writes.
+    pub fn annotate(&mut self) {
+        let names = object_names(&PlistRef::Dictionary(PlistEntryRef::from_items(
+            self.items(),
+        )));
+        for item in self.items_mut().iter_mut() {
+            if let PlistItem::Item(kv) = item {
+                if kv.key.name == "objects" {
+                    if let PlistItem::Array(objects) = kv.value.as_mut() {
+                        annotate_keys(objects, &names);
+                    }
+                }
+            }
+            annotate_values(item, "", &names);
+        }
+    }
+}
+
+fn string<'a>(object: &'a PlistRef, key: &str) -> Option<&'a str> {
+    object.get(key).and_then(PlistRef::as_str)
+}
+
+/// `name`, or the last component of `path`.
+fn file_name(object: &PlistRef) -> Option<String> {
+    string(object, "name")
+        .or_else(|| string(object, "path").map(|path| path.rsplit('/').next().unwrap_or(path)))
+        .map(str::to_string)
+}
+
+fn phase_name(isa: &str, object: &PlistRef) -> String {
+    match string(object, "name") {
+        Some(name) => name.to_string(),
+        None => isa
+            .trim_start_matches("PBX")
+            .trim_end_matches("BuildPhase")
+            .to_string(),
+    }
+}
+
+/// Comment of every object that has one, by ID.
+pub(super) fn object_names(root: &PlistRef) -> HashMap<String, String> {
+    let objects: HashMap<&str, &PlistRef> = root
+        .get("objects")
+        .and_then(PlistRef::as_dictionary)
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| (entry.key.as_ref(), &entry.value))
+        .collect();
+
+    let mut phases: HashMap<&str, String> = HashMap::new();
+    let mut owners: HashMap<&str, String> = HashMap::new();
+    for object in objects.values() {
+        let isa = match string(object, "isa") {
+            Some(isa) => isa,
+            None => continue,
+        };
+        if isa.ends_with("BuildPhase") {
+            let files = object.get("files").and_then(PlistRef::as_array);
+            for file in files.unwrap_or_default() {
+                if let Some(id) = file.as_str() {
+                    phases.insert(id, phase_name(isa, object));
+                }
+            }
+        }
+        if let (Some(list), Some(name)) = (
+            string(object, "buildConfigurationList"),
+            string(object, "name"),
+        ) {
+            owners.insert(list, format!("{} \"{}\"", isa, name));
+        }
+    }
+
+    let mut names = HashMap::new();
+    for (&id, object) in &objects {
+        let isa = match string(object, "isa") {
+            Some(isa) => isa,
+            None => continue,
+        };
+        let name = match isa {
+            "PBXProject" => Some("Project object".to_string()),
+            "PBXBuildFile" => {
+                let file = match string(object, "fileRef") {
+                    Some(file_ref) => objects.get(file_ref).and_then(|file| file_name(file)),
+                    None => string(object, "productRef")
+                        .and_then(|product| objects.get(product))
+                        .and_then(|product| string(product, "productName"))
+                        .map(str::to_string),
+                };
+                file.zip(phases.get(id))
+                    .map(|(file, phase)| format!("{} in {}", file, phase))
+            }
+            "XCConfigurationList" => owners
+                .get(id)
+                .map(|owner| format!("Build configuration list for {}", owner)),
+            "PBXFileReference"
+            | "PBXGroup"
+            | "PBXVariantGroup"
+            | "XCVersionGroup"
+            | "PBXReferenceProxy"
+            | "PBXFileSystemSynchronizedRootGroup" => file_name(object),
+            "PBXNativeTarget"
+            | "PBXAggregateTarget"
+            | "PBXLegacyTarget"
+            | "XCBuildConfiguration" => string(object, "name").map(str::to_string),
+            "XCSwiftPackageProductDependency" => string(object, "productName").map(str::to_string),
+            "XCRemoteSwiftPackageReference" => string(object, "repositoryURL").map(|url| {
+                let name = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+                format!("{} \"{}\"", isa, name.trim_end_matches(".git"))
+            }),
+            "XCLocalSwiftPackageReference" => {
+                string(object, "relativePath").map(|path| format!("{} \"{}\"", isa, path))
+            }
+            isa if isa.ends_with("BuildPhase") => Some(phase_name(isa, object)),
+            isa => Some(isa.to_string()),
+        };
+        if let Some(name) = name {
+            names.insert(id.to_string(), name);
+        }
+    }
+    names
+}
+
+/// Comments of the keys of `objects`.
+fn annotate_keys(objects: &mut [PlistItem], names: &HashMap<String, String>) {
+    for item in objects {
+        match item {
+            PlistItem::Item(kv) => {
+                if let Some(name) = names.get(kv.key.name.as_str()) {
+                    kv.key.comment = Some(name.clone());
+                }
+            }
+            PlistItem::SectionItem(section) => annotate_keys(&mut section.items, names),
+            _ => {}
+        }
+    }
+}
+
+/// Comments of the values that are object IDs, `key` being the key holding `item`.
+fn annotate_values(item: &mut PlistItem, key: &str, names: &HashMap<String, String>) {
+    let (value, comment) = match item {
+        PlistItem::Value(value) => (&value.value, &mut value.comment),
+        PlistItem::TupleValue(value) => (&value.value, &mut value.comment),
+        PlistItem::Item(kv) => return annotate_values(&mut kv.value, kv.key.name.as_str(), names),
+        PlistItem::TupleItem(tuple) => {
+            for item in tuple.items.iter_mut() {
+                annotate_values(item, tuple.name.as_str(), names);
+            }
+            return;
+        }
+        PlistItem::SectionItem(PlistSectionItem { items, .. }) | PlistItem::Array(items) => {
+            for item in items.iter_mut() {
+                annotate_values(item, "", names);
+            }
+            return;
+        }
+        PlistItem::Tuple(items) => {
+            for item in items.iter_mut() {
+                annotate_values(item, key, names);
+            }
+            return;
+        }
+        PlistItem::Data(_) => return,
+    };
+    if UNANNOTATED_KEYS.contains(&key) {
+        return;
+    }
+    if let Some(name) = names.get(value.as_str()) {
+        *comment = Some(name.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "// !$*UTF8*$!
+{
+	objects = {
+		BF = {isa = PBXBuildFile; fileRef = FR; };
+		BP = {isa = PBXBuildFile; productRef = PD; };
+		CP = {isa = PBXContainerItemProxy; containerPortal = PR; proxyType = 1; remoteGlobalIDString = TG; remoteInfo = App; };
+		FP = {isa = PBXFrameworksBuildPhase; files = (BP); };
+		FR = {isa = PBXFileReference; path = Sources/main.swift; sourceTree = \"<group>\"; };
+		GR = {isa = PBXGroup; children = (FR); sourceTree = \"<group>\"; };
+		PD = {isa = XCSwiftPackageProductDependency; package = PK; productName = Lib; };
+		PK = {isa = XCRemoteSwiftPackageReference; repositoryURL = \"https://github.com/apple/swift-log.git\"; };
+		PR = {isa = PBXProject; mainGroup = GR; packageReferences = (PK); targets = (TG); };
+		RP = {isa = PBXShellScriptBuildPhase; files = (); name = \"Run Script\"; };
+		SP = {isa = PBXSourcesBuildPhase; files = (BF); };
+		TC = {isa = XCBuildConfiguration; name = Debug; };
+		TD = {isa = PBXTargetDependency; target = TG; targetProxy = CP; };
+		TG = {isa = PBXNativeTarget; buildConfigurationList = TL; buildPhases = (SP, FP, RP); name = App; };
+		TL = {isa = XCConfigurationList; buildConfigurations = (TC); };
+	};
+	rootObject = PR;
+}
+";
+
+    fn names() -> HashMap<String, String> {
+        let project = XcodePbxProject::parse_from_string(CONTENT).unwrap();
+        object_names(&PlistRef::Dictionary(PlistEntryRef::from_items(
+            project.items(),
+        )))
+    }
+
+    #[test]
+    fn object_names_follow_xcode() {
+        let names = names();
+        let name = |id: &str| names.get(id).map(String::as_str);
+        assert_eq!(name("BF"), Some("main.swift in Sources"));
+        assert_eq!(name("BP"), Some("Lib in Frameworks"));
+        assert_eq!(name("CP"), Some("PBXContainerItemProxy"));
+        assert_eq!(name("FP"), Some("Frameworks"));
+        assert_eq!(name("FR"), Some("main.swift"));
+        // a main group without name or path has no comment
+        assert_eq!(name("GR"), None);
+        assert_eq!(name("PD"), Some("Lib"));
+        assert_eq!(
+            name("PK"),
+            Some("XCRemoteSwiftPackageReference \"swift-log\"")
+        );
+        assert_eq!(name("PR"), Some("Project object"));
+        assert_eq!(name("RP"), Some("Run Script"));
+        assert_eq!(name("SP"), Some("Sources"));
+        assert_eq!(name("TC"), Some("Debug"));
+        assert_eq!(name("TD"), Some("PBXTargetDependency"));
+        assert_eq!(name("TG"), Some("App"));
+        assert_eq!(
+            name("TL"),
+            Some("Build configuration list for PBXNativeTarget \"App\"")
+        );
+    }
+
+    #[test]
+    fn serialize_annotates_keys_and_values() {
+        let text = XcodePbxProject::parse_from_string(CONTENT)
+            .unwrap()
+            .serialize();
+        for line in [
+            "BF /* main.swift in Sources */ = {isa = PBXBuildFile; fileRef = FR /* main.swift */; };",
+            "TL /* Build configuration list for PBXNativeTarget \"App\" */ = {",
+            "buildConfigurationList = TL /* Build configuration list for PBXNativeTarget \"App\" */;",
+            "PK /* XCRemoteSwiftPackageReference \"swift-log\" */ = {",
+            "package = PK /* XCRemoteSwiftPackageReference \"swift-log\" */;",
+            "RP /* Run Script */,",
+            "SP /* Sources */,",
+            "FP /* Frameworks */,",
+            "rootObject = PR /* Project object */;",
+            "remoteGlobalIDString = TG;",
+            "target = TG /* App */;",
+        ] {
+            assert!(text.contains(line), "{:?} not in\n{}", line, text);
+        }
+    }
+
+    #[test]
+    fn annotate_replaces_stale_comments() {
+        let content = CONTENT
+            .replace("fileRef = FR;", "fileRef = FR /* old.swift */;")
+            .replace(
+                "remoteGlobalIDString = TG;",
+                "remoteGlobalIDString = TG /* kept */;",
+            );
+        let mut project = XcodePbxProject::parse_from_string(&content).unwrap();
+        project.annotate();
+        let text = project.serialize_lossless();
+        assert!(text.contains("fileRef = FR /* main.swift */;"));
+        assert!(text.contains("remoteGlobalIDString = TG /* kept */;"));
+    }
+}
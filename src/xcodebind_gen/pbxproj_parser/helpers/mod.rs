@@ -1,7 +1,5 @@
 pub use nom_combinators::*;
 pub use string_helper::*;
-pub use vector_helpers::*;
 
 mod nom_combinators;
 mod string_helper;
-mod vector_helpers;
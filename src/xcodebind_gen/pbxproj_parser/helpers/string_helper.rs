@@ -1,23 +1,14 @@
-use nom::IResult;
-
-use super::super::types::*;
-
-pub trait XcodeParserDeserializer {
-    fn get_plist_item<T: PlistItemCustomDeserializeCompatible>(&self) -> IResult<&str, Box<T>>;
-}
-
-impl XcodeParserDeserializer for (&str, (&str, Option<String>, PlistIResultEndReason)) {
-    fn get_plist_item<T: PlistItemCustomDeserializeCompatible>(&self) -> IResult<&str, Box<T>> {
-        let res: Box<T> = match self.1 .2 {
-            PlistIResultEndReason::KeyEnd => Box::new(T::from_plist_result(&self.1)),
-            _ => panic!("Unhandled type"),
-        };
-        Ok((self.0, res))
-    }
-}
-
 pub trait StringSerializer {
     fn as_comment(&self) -> String;
+    /// The comment preceded by a space, or nothing.
+    fn as_spaced_comment(&self) -> String {
+        let comment = self.as_comment();
+        if comment.is_empty() {
+            comment
+        } else {
+            format!(" {}", comment)
+        }
+    }
     fn get_tabs(number_of_tabs: i32) -> String;
 }
 
@@ -0,0 +1,214 @@
+use serde_json::{Map, Value};
+
+use crate::xcodebind_gen::types::*;
+use crate::xcodebind_gen::{Result, XcodeUniffiError};
+
+use super::XcodePbxProject;
+
+/// JSON form of a project, the same document `plutil -convert json` writes: every scalar is a
+/// string, sections and comments are dropped. Like plutil, `<data>` values are rejected.
+impl XcodePbxProject {
+    pub fn to_json(&self) -> Result<Value> {
+        dictionary_to_json(self.items())
+    }
+
+    /// Reads a document written by `to_json` (or `plutil -convert json`). Numbers and booleans
+    /// are taken as strings, and the entries of `objects` are grouped into sections by `isa`.
+    /// There are no comments, `serialize` derives the ones of object IDs again.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        match value {
+            Value::Object(map) => {
+                let mut project = XcodePbxProject::from_items(dictionary_from_json(map)?);
+                project.group_objects_by_isa();
+                Ok(project)
+            }
+            _ => Err(convert_error("the JSON document must be an object")),
+        }
+    }
+}
+
+fn convert_error(message: &str) -> XcodeUniffiError {
+    XcodeUniffiError::Convert(message.to_string())
+}
+
+fn dictionary_to_json(items: &[PlistItem]) -> Result<Value> {
+    let mut map = Map::new();
+    for item in items {
+        match item {
+            PlistItem::Item(item) => {
+                map.insert(
+                    item.key.name.as_str().to_string(),
+                    value_to_json(&item.value)?,
+                );
+            }
+            PlistItem::TupleItem(tuple) => {
+                map.insert(
+                    tuple.name.as_str().to_string(),
+                    array_to_json(&tuple.items)?,
+                );
+            }
+            PlistItem::SectionItem(section) => {
+                if let Value::Object(entries) = dictionary_to_json(&section.items)? {
+                    map.extend(entries);
+                }
+            }
+            _ => return Err(convert_error("dictionary holds an element without a key")),
+        }
+    }
+    Ok(Value::Object(map))
+}
+
+fn array_to_json(items: &[PlistItem]) -> Result<Value> {
+    items
+        .iter()
+        .map(value_to_json)
+        .collect::<Result<Vec<Value>>>()
+        .map(Value::Array)
+}
+
+fn value_to_json(item: &PlistItem) -> Result<Value> {
+    match item {
+        PlistItem::Value(value) => Ok(Value::String(value.value.as_str().to_string())),
+        PlistItem::TupleValue(value) => Ok(Value::String(value.value.as_str().to_string())),
+        PlistItem::Array(items) => dictionary_to_json(items),
+        PlistItem::Tuple(items) => array_to_json(items),
+        PlistItem::Data(_) => Err(convert_error("<data> values have no JSON form")),
+        _ => Err(convert_error("unexpected entry in a value position")),
+    }
+}
+
+fn scalar_from_json(value: &Value) -> Result<PlistString> {
+    match value {
+        Value::String(value) => Ok(PlistString::from(value.as_str())),
+        Value::Number(value) => Ok(PlistString::from(value.to_string())),
+        Value::Bool(value) => Ok(PlistString::from(if *value { "YES" } else { "NO" })),
+        _ => Err(convert_error("null has no plist form")),
+    }
+}
+
+fn dictionary_from_json(map: &Map<String, Value>) -> Result<Vec<PlistItem>> {
+    let mut items = vec![];
+    for (key, value) in map {
+        let item = match value {
+            Value::Array(elements) => PlistItem::TupleItem(PlistTupleItem {
+                name: PlistString::from(key.as_str()),
+                items: array_from_json(elements)?,
+            }),
+            value => PlistItem::Item(PlistKeyValueItem {
+                key: Box::new(PlistKey {
+                    name: PlistString::from(key.as_str()),
+                    comment: None,
+                }),
+                value: Box::new(value_from_json(value)?),
+            }),
+        };
+        items.push(item);
+    }
+    Ok(items)
+}
+
+fn array_from_json(elements: &[Value]) -> Result<Vec<PlistItem>> {
+    elements
+        .iter()
+        .map(|element| match element {
+            Value::Object(_) | Value::Array(_) => value_from_json(element),
+            scalar => Ok(PlistItem::TupleValue(PlistTupleValue {
+                value: scalar_from_json(scalar)?,
+                comment: None,
+            })),
+        })
+        .collect()
+}
+
+fn value_from_json(value: &Value) -> Result<PlistItem> {
+    match value {
+        Value::Object(map) => Ok(PlistItem::Array(dictionary_from_json(map)?)),
+        Value::Array(elements) => Ok(PlistItem::Tuple(array_from_json(elements)?)),
+        scalar => Ok(PlistItem::Value(PlistValue {
+            value: scalar_from_json(scalar)?,
+            comment: None,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn read(name: &str) -> XcodePbxProject {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("test_data")
+            .join(name);
+        XcodePbxProject::parse_from_string(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn projects_round_trip_through_json() {
+        for name in ["project.pbxproj", "proj_1.pbxproj"] {
+            let project = read(name);
+            let json = project.to_json().unwrap();
+            let converted = XcodePbxProject::from_json(&json).unwrap();
+            assert_eq!(converted.to_json().unwrap(), json, "{}", name);
+
+            // only the comment of the project's configuration list, named after the
+            // .xcodeproj, cannot be derived again
+            let (text, converted_text) = (project.serialize(), converted.serialize());
+            let lines: Vec<(&str, &str)> = text
+                .lines()
+                .zip(converted_text.lines())
+                .filter(|(line, converted_line)| line != converted_line)
+                .collect();
+            assert_eq!(text.lines().count(), converted_text.lines().count());
+            assert_eq!(lines.len(), 2, "{}", name);
+            for (line, converted_line) in lines {
+                let comment = line
+                    .find(" /* Build configuration list for PBXProject")
+                    .unwrap();
+                let end = line.find(" */").unwrap() + 3;
+                assert_eq!(
+                    format!("{}{}", &line[..comment], &line[end..]),
+                    converted_line
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scalars_are_strings() {
+        let json = read("project.pbxproj").to_json().unwrap();
+        assert_eq!(json["archiveVersion"], "1");
+        assert_eq!(json["objectVersion"], "52");
+        let project = XcodePbxProject::from_json(&serde_json::json!({
+            "archiveVersion": 1,
+            "classes": {},
+            "objects": {"A": {"isa": "PBXGroup", "children": [], "usesTabs": true}},
+            "rootObject": "A",
+        }))
+        .unwrap();
+        let json = project.to_json().unwrap();
+        assert_eq!(json["archiveVersion"], "1");
+        assert_eq!(json["objects"]["A"]["usesTabs"], "YES");
+    }
+
+    #[test]
+    fn data_values_are_rejected() {
+        let error = read("value_forms.pbxproj").to_json().unwrap_err();
+        assert_eq!(error.kind(), "convert");
+        assert!(error
+            .to_string()
+            .contains("<data> values have no JSON form"));
+    }
+
+    #[test]
+    fn from_json_rejects_null_and_non_objects() {
+        let error = XcodePbxProject::from_json(&serde_json::json!(["a"]))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("must be an object"));
+        let error = XcodePbxProject::from_json(&serde_json::json!({"a": null}))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("null has no plist form"));
+    }
+}
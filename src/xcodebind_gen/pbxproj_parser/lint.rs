@@ -0,0 +1,596 @@
+//! Referential integrity checks over the object graph of a project.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::xcodebind_gen::types::*;
+
+use super::annotations::object_names;
+use super::XcodePbxProject;
+
+/// Keys whose value (a string or an array of strings) holds IDs of objects of the same project.
+/// `remoteGlobalIDString` is left out, it may point into another project.
+const REFERENCE_KEYS: [&str; 25] = [
+    "baseConfigurationReference",
+    "buildConfigurationList",
+    "buildConfigurations",
+    "buildPhases",
+    "buildRules",
+    "children",
+    "containerPortal",
+    "dependencies",
+    "exceptions",
+    "fileRef",
+    "fileSystemSynchronizedGroups",
+    "files",
+    "mainGroup",
+    "package",
+    "packageProductDependencies",
+    "packageReferences",
+    "ProductGroup",
+    "productRef",
+    "productRefGroup",
+    "productReference",
+    "ProjectRef",
+    "remoteRef",
+    "target",
+    "targetProxy",
+    "targets",
+];
+
+/// File types a build phase is expected to compile.
+const COMPILED_FILE_TYPES: [&str; 7] = [
+    "sourcecode.asm",
+    "sourcecode.c.c",
+    "sourcecode.c.objc",
+    "sourcecode.cpp.cpp",
+    "sourcecode.cpp.objcpp",
+    "sourcecode.metal",
+    "sourcecode.swift",
+];
+
+#[derive(
+    Debug, Clone, Copy, std::cmp::Eq, std::cmp::PartialEq, std::cmp::Ord, std::cmp::PartialOrd,
+)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Problem found by `XcodePbxProject::lint`.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct LintDiagnostic {
+    /// Stable name of the check, e.g. `dangling-reference`.
+    pub code: &'static str,
+    pub severity: Severity,
+    /// ID of the object the problem is about, if any.
+    pub object: Option<String>,
+    pub message: String,
+}
+
+impl LintDiagnostic {
+    fn error(code: &'static str, object: Option<&str>, message: String) -> Self {
+        LintDiagnostic {
+            code,
+            severity: Severity::Error,
+            object: object.map(str::to_string),
+            message,
+        }
+    }
+
+    fn warning(code: &'static str, object: Option<&str>, message: String) -> Self {
+        LintDiagnostic {
+            code,
+            severity: Severity::Warning,
+            ..LintDiagnostic::error(code, object, message)
+        }
+    }
+}
+
+fn string<'a>(object: &'a PlistRef, key: &str) -> Option<&'a str> {
+    object.get(key).and_then(PlistRef::as_str)
+}
+
+/// `(key, id)` of every reference held by `value`, in nested dictionaries too.
+fn references<'b>(value: &'b PlistRef, refs: &mut Vec<(&'b str, &'b str)>) {
+    match value {
+        PlistRef::Dictionary(entries) => {
+            for entry in entries {
+                if !REFERENCE_KEYS.contains(&entry.key.as_ref()) {
+                    references(&entry.value, refs);
+                    continue;
+                }
+                match &entry.value {
+                    PlistRef::String(id, _) => refs.push((&entry.key, id)),
+                    PlistRef::Array(elements) => refs.extend(
+                        elements
+                            .iter()
+                            .filter_map(PlistRef::as_str)
+                            .map(|id| (entry.key.as_ref(), id)),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        PlistRef::Array(elements) => {
+            for element in elements {
+                references(element, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl XcodePbxProject {
+    /// Checks that the object graph holds together: `rootObject` is a `PBXProject`, every
+    /// reference points to an existing object, every object is reachable from the project,
+    /// targets have a configuration list, build phases don't build a file twice and source
+    /// files in groups are compiled by some build phase.
+    ///
+    /// Diagnostics are ordered by object ID.
+    pub fn lint(&self) -> Vec<LintDiagnostic> {
+        let root = PlistRef::Dictionary(PlistEntryRef::from_items(self.items()));
+        let mut diagnostics = vec![];
+        let objects: BTreeMap<&str, &PlistRef> = match root.get("objects") {
+            Some(PlistRef::Dictionary(entries)) => entries
+                .iter()
+                .map(|entry| (entry.key.as_ref(), &entry.value))
+                .collect(),
+            _ => {
+                diagnostics.push(LintDiagnostic::error(
+                    "root-object",
+                    None,
+                    "The project has no objects dictionary".to_string(),
+                ));
+                return diagnostics;
+            }
+        };
+        let names = object_names(&root);
+        let describe = |id: &str| match names.get(id) {
+            Some(name) => format!("{} ({})", id, name),
+            None => id.to_string(),
+        };
+        let isa = |id: &str| objects.get(id).and_then(|object| string(object, "isa"));
+
+        match root.get("rootObject").and_then(PlistRef::as_str) {
+            None => diagnostics.push(LintDiagnostic::error(
+                "root-object",
+                None,
+                "rootObject is missing".to_string(),
+            )),
+            Some(id) => match isa(id) {
+                Some("PBXProject") => {}
+                Some(other) => diagnostics.push(LintDiagnostic::error(
+                    "root-object",
+                    Some(id),
+                    format!("rootObject {} is a {}, not a PBXProject", id, other),
+                )),
+                None if objects.contains_key(id) => {}
+                None => diagnostics.push(LintDiagnostic::error(
+                    "root-object",
+                    Some(id),
+                    format!("rootObject {} does not exist", id),
+                )),
+            },
+        }
+
+        let mut refs_by_object: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+        for (&id, object) in &objects {
+            let mut refs = vec![];
+            references(object, &mut refs);
+            refs_by_object.insert(id, refs);
+        }
+
+        let mut phases_of_build_file: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut built_files: HashSet<&str> = HashSet::new();
+        for (&id, object) in &objects {
+            let object_isa = match string(object, "isa") {
+                Some(isa) => isa,
+                None => {
+                    diagnostics.push(LintDiagnostic::error(
+                        "missing-isa",
+                        Some(id),
+                        format!("{} has no isa", id),
+                    ));
+                    continue;
+                }
+            };
+
+            for &(key, target) in &refs_by_object[id] {
+                if key != "buildConfigurationList" && !objects.contains_key(target) {
+                    diagnostics.push(LintDiagnostic::error(
+                        "dangling-reference",
+                        Some(id),
+                        format!(
+                            "{} {} refers to missing object {}",
+                            describe(id),
+                            key,
+                            target
+                        ),
+                    ));
+                }
+            }
+
+            let has_configurations = object_isa == "PBXProject"
+                || object_isa.ends_with("Target") && object_isa.starts_with("PBX");
+            if has_configurations {
+                let message = match string(object, "buildConfigurationList") {
+                    None => Some(format!("{} has no buildConfigurationList", describe(id))),
+                    Some(list) => match isa(list) {
+                        Some("XCConfigurationList") => None,
+                        Some(other) => Some(format!(
+                            "{} buildConfigurationList {} is a {}, not an XCConfigurationList",
+                            describe(id),
+                            list,
+                            other
+                        )),
+                        None => Some(format!(
+                            "{} refers to missing configuration list {}",
+                            describe(id),
+                            list
+                        )),
+                    },
+                };
+                if let Some(message) = message {
+                    diagnostics.push(LintDiagnostic::error(
+                        "missing-configuration-list",
+                        Some(id),
+                        message,
+                    ));
+                }
+            }
+
+            if object_isa.ends_with("BuildPhase") {
+                let mut seen: HashSet<&str> = HashSet::new();
+                let files = object.get("files").and_then(PlistRef::as_array);
+                for build_file in files
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(PlistRef::as_str)
+                {
+                    let phases = phases_of_build_file.entry(build_file).or_default();
+                    if !phases.contains(&id) {
+                        phases.push(id);
+                    }
+                    let file = objects.get(build_file).and_then(|build_file| {
+                        string(build_file, "fileRef").or_else(|| string(build_file, "productRef"))
+                    });
+                    let file = match file {
+                        Some(file) => file,
+                        None => continue,
+                    };
+                    built_files.insert(file);
+                    if !seen.insert(file) {
+                        diagnostics.push(LintDiagnostic::warning(
+                            "duplicate-build-file",
+                            Some(id),
+                            format!("{} builds {} more than once", describe(id), describe(file)),
+                        ));
+                    }
+                }
+            }
+        }
+
+        for (build_file, phases) in &phases_of_build_file {
+            if phases.len() > 1 {
+                diagnostics.push(LintDiagnostic::warning(
+                    "duplicate-build-file",
+                    Some(build_file),
+                    format!(
+                        "{} is listed by several build phases: {}",
+                        describe(build_file),
+                        phases.join(", ")
+                    ),
+                ));
+            }
+        }
+
+        for (&id, object) in &objects {
+            if string(object, "isa") != Some("PBXGroup") {
+                continue;
+            }
+            let children = object.get("children").and_then(PlistRef::as_array);
+            for child in children
+                .unwrap_or_default()
+                .iter()
+                .filter_map(PlistRef::as_str)
+            {
+                let file = match objects.get(child) {
+                    Some(file) if string(file, "isa") == Some("PBXFileReference") => file,
+                    _ => continue,
+                };
+                let file_type =
+                    string(file, "explicitFileType").or_else(|| string(file, "lastKnownFileType"));
+                if file_type.is_some_and(|file_type| COMPILED_FILE_TYPES.contains(&file_type))
+                    && !built_files.contains(child)
+                {
+                    diagnostics.push(LintDiagnostic::warning(
+                        "file-not-in-build-phase",
+                        Some(child),
+                        format!(
+                            "{} is in group {} but no build phase compiles it",
+                            describe(child),
+                            describe(id)
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // everything should be reachable from the project through references, when there is one
+        let mut reachable: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<&str> = root
+            .get("rootObject")
+            .and_then(PlistRef::as_str)
+            .filter(|&id| isa(id) == Some("PBXProject"))
+            .into_iter()
+            .collect();
+        while let Some(id) = queue.pop_front() {
+            if !reachable.insert(id) {
+                continue;
+            }
+            for &(_, target) in refs_by_object
+                .get(id)
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+            {
+                queue.push_back(target);
+            }
+        }
+        if !reachable.is_empty() {
+            for &id in objects.keys() {
+                if !reachable.contains(id) {
+                    diagnostics.push(LintDiagnostic::warning(
+                        "orphan-object",
+                        Some(id),
+                        format!("{} is not referenced from the project", describe(id)),
+                    ));
+                }
+            }
+        }
+
+        diagnostics.sort_by(|a, b| a.object.cmp(&b.object));
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Project holding one app target that compiles `main.swift`.
+    const OBJECTS: &str = "\
+        B = {isa = PBXBuildFile; fileRef = F; };
+        F = {isa = PBXFileReference; lastKnownFileType = sourcecode.swift; path = main.swift; sourceTree = \"<group>\"; };
+        G = {isa = PBXGroup; children = (F); sourceTree = \"<group>\"; };
+        P = {isa = PBXProject; buildConfigurationList = PL; mainGroup = G; targets = (T); };
+        PC = {isa = XCBuildConfiguration; name = Debug; };
+        PL = {isa = XCConfigurationList; buildConfigurations = (PC); };
+        S = {isa = PBXSourcesBuildPhase; files = (B); };
+        T = {isa = PBXNativeTarget; buildConfigurationList = TL; buildPhases = (S); name = App; };
+        TC = {isa = XCBuildConfiguration; name = Debug; };
+        TL = {isa = XCConfigurationList; buildConfigurations = (TC); };
+    ";
+
+    fn lint_with(objects: &str, root_object: &str) -> Vec<LintDiagnostic> {
+        let content = format!(
+            "// !$*UTF8*$!\n{{\n\tobjects = {{\n{}\n\t}};\n{}}}\n",
+            objects, root_object
+        );
+        XcodePbxProject::parse_from_string(&content).unwrap().lint()
+    }
+
+    fn lint(objects: &str) -> Vec<LintDiagnostic> {
+        lint_with(objects, "\trootObject = P;\n")
+    }
+
+    /// `(code, object)` of the diagnostics with `code`.
+    fn found<'d>(diagnostics: &'d [LintDiagnostic], code: &str) -> Vec<(&'d str, Option<&'d str>)> {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.code == code)
+            .map(|diagnostic| (diagnostic.code, diagnostic.object.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn a_consistent_project_is_clean() {
+        assert_eq!(lint(OBJECTS), []);
+    }
+
+    #[test]
+    fn dangling_reference() {
+        let diagnostics = lint(&OBJECTS.replace("fileRef = F;", "fileRef = MISSING;"));
+        assert_eq!(
+            found(&diagnostics, "dangling-reference"),
+            [("dangling-reference", Some("B"))]
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0]
+            .message
+            .contains("fileRef refers to missing object MISSING"));
+    }
+
+    #[test]
+    fn remote_global_ids_are_not_references() {
+        let objects = format!(
+            "{}\tX = {{isa = PBXContainerItemProxy; containerPortal = P; remoteGlobalIDString = ELSEWHERE; }};\n",
+            OBJECTS.replace("buildPhases = (S);", "buildPhases = (S); dependencies = (X);")
+        );
+        assert_eq!(lint(&objects), []);
+    }
+
+    #[test]
+    fn orphan_object() {
+        let objects = format!("{}\tX = {{isa = PBXGroup; children = (); }};\n", OBJECTS);
+        let diagnostics = lint(&objects);
+        assert_eq!(
+            found(&diagnostics, "orphan-object"),
+            [("orphan-object", Some("X"))]
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn duplicate_build_file_in_one_phase() {
+        let objects = format!(
+            "{}\tB2 = {{isa = PBXBuildFile; fileRef = F; }};\n",
+            OBJECTS.replace("files = (B);", "files = (B, B2);")
+        );
+        let diagnostics = lint(&objects);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            found(&diagnostics, "duplicate-build-file"),
+            [("duplicate-build-file", Some("S"))]
+        );
+        assert!(diagnostics[0]
+            .message
+            .contains("builds F (main.swift) more than once"));
+    }
+
+    #[test]
+    fn duplicate_build_file_across_phases() {
+        let objects = format!(
+            "{}\tS2 = {{isa = PBXResourcesBuildPhase; files = (B); }};\n",
+            OBJECTS.replace("buildPhases = (S);", "buildPhases = (S, S2);")
+        );
+        let diagnostics = lint(&objects);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            found(&diagnostics, "duplicate-build-file"),
+            [("duplicate-build-file", Some("B"))]
+        );
+        assert!(diagnostics[0]
+            .message
+            .ends_with("several build phases: S, S2"));
+    }
+
+    #[test]
+    fn file_not_in_build_phase() {
+        let objects = format!(
+            "{}\tF2 = {{isa = PBXFileReference; lastKnownFileType = sourcecode.swift; path = other.swift; }};\n\
+             \tR = {{isa = PBXFileReference; lastKnownFileType = text.plist.xml; path = Info.plist; }};\n",
+            OBJECTS.replace("children = (F);", "children = (F, F2, R);")
+        );
+        let diagnostics = lint(&objects);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            found(&diagnostics, "file-not-in-build-phase"),
+            [("file-not-in-build-phase", Some("F2"))]
+        );
+        assert!(diagnostics[0].message.contains("is in group G"));
+    }
+
+    #[test]
+    fn missing_configuration_list() {
+        let diagnostics = lint(&OBJECTS.replace("buildConfigurationList = TL; ", ""));
+        assert_eq!(
+            found(&diagnostics, "missing-configuration-list"),
+            [("missing-configuration-list", Some("T"))]
+        );
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message == "T (App) has no buildConfigurationList"));
+
+        let diagnostics = lint(&OBJECTS.replace(
+            "buildConfigurationList = TL;",
+            "buildConfigurationList = TC;",
+        ));
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert_eq!(
+            found(&diagnostics, "missing-configuration-list"),
+            [("missing-configuration-list", Some("T"))]
+        );
+        assert_eq!(
+            found(&diagnostics, "orphan-object"),
+            [("orphan-object", Some("TL"))]
+        );
+
+        // a missing list is reported once, not as a dangling reference too
+        let diagnostics = lint(&OBJECTS.replace(
+            "buildConfigurationList = TL;",
+            "buildConfigurationList = NONE;",
+        ));
+        assert_eq!(found(&diagnostics, "dangling-reference"), []);
+        assert_eq!(
+            found(&diagnostics, "missing-configuration-list"),
+            [("missing-configuration-list", Some("T"))]
+        );
+    }
+
+    #[test]
+    fn root_object() {
+        let diagnostics = lint_with(OBJECTS, "");
+        assert_eq!(found(&diagnostics, "root-object"), [("root-object", None)]);
+        assert_eq!(diagnostics[0].message, "rootObject is missing");
+        // without a project nothing can be reached, so orphans are not reported
+        assert_eq!(diagnostics.len(), 1);
+
+        let diagnostics = lint_with(OBJECTS, "\trootObject = T;\n");
+        assert_eq!(
+            found(&diagnostics, "root-object"),
+            [("root-object", Some("T"))]
+        );
+        assert!(diagnostics[0]
+            .message
+            .ends_with("is a PBXNativeTarget, not a PBXProject"));
+
+        let diagnostics = lint_with(OBJECTS, "\trootObject = NONE;\n");
+        assert_eq!(
+            found(&diagnostics, "root-object"),
+            [("root-object", Some("NONE"))]
+        );
+
+        let content = "// !$*UTF8*$!\n{\n\trootObject = P;\n}\n";
+        let diagnostics = XcodePbxProject::parse_from_string(content).unwrap().lint();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "The project has no objects dictionary"
+        );
+    }
+
+    #[test]
+    fn missing_isa() {
+        let diagnostics = lint(&OBJECTS.replace("TC = {isa = XCBuildConfiguration;", "TC = {"));
+        assert_eq!(
+            found(&diagnostics, "missing-isa"),
+            [("missing-isa", Some("TC"))]
+        );
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn diagnostics_are_ordered_by_object() {
+        let objects = format!(
+            "{}\tA = {{isa = PBXGroup; }};\n\tZ = {{name = z; }};\n",
+            OBJECTS.replace("fileRef = F;", "fileRef = MISSING;")
+        );
+        let diagnostics = lint_with(&objects, "\trootObject = P;\n");
+        let objects: Vec<Option<&str>> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.object.as_deref())
+            .collect();
+        assert_eq!(
+            objects,
+            [Some("A"), Some("B"), Some("F"), Some("Z"), Some("Z")]
+        );
+
+        // diagnostics about no object come first
+        let diagnostics = lint_with(&OBJECTS.replace("fileRef = F;", "fileRef = MISSING;"), "");
+        let objects: Vec<Option<&str>> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.object.as_deref())
+            .collect();
+        assert_eq!(objects, [None, Some("B"), Some("F")]);
+    }
+}
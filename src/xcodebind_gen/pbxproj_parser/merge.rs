@@ -0,0 +1,521 @@
+//! Three-way merge of projects at the object level, for use as a git merge driver.
+//!
+//! Dictionaries (the document, `objects` and every object) are merged key by key, arrays of
+//! distinct elements (`files`, `children`, `buildPhases`, ...) as ordered sets and any other
+//! value is taken from the side that changed it. Comments are ignored, `serialize` derives them
+//! again.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::xcodebind_gen::types::*;
+
+use super::plist_path::join;
+use super::XcodePbxProject;
+
+/// Value changed differently on both sides of a merge. Values are single line plist text,
+/// `None` when the side has no value at `path`.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct MergeConflict {
+    /// Path of the value, as taken by `XcodePbxProject::query`.
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+/// Equality ignoring comments and the order of dictionary entries.
+fn same(a: &PlistRef, b: &PlistRef) -> bool {
+    match (a, b) {
+        (PlistRef::String(a, _), PlistRef::String(b, _)) => a == b,
+        (PlistRef::Data(a, _), PlistRef::Data(b, _)) => a == b,
+        (PlistRef::Dictionary(a), PlistRef::Dictionary(b)) => {
+            let b: HashMap<&str, &PlistRef> = b
+                .iter()
+                .map(|entry| (entry.key.as_ref(), &entry.value))
+                .collect();
+            a.len() == b.len()
+                && a.iter().all(|entry| {
+                    b.get(entry.key.as_ref())
+                        .is_some_and(|other| same(&entry.value, other))
+                })
+        }
+        (PlistRef::Array(a), PlistRef::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b))
+        }
+        _ => false,
+    }
+}
+
+fn same_option(a: Option<&PlistRef>, b: Option<&PlistRef>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => same(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Hash of a value agreeing with `same`: comments and the order of dictionary entries are
+/// left out.
+fn fingerprint(value: &PlistRef) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    match value {
+        PlistRef::String(string, _) => (0u8, string.as_ref()).hash(&mut hasher),
+        PlistRef::Data(data, _) => (1u8, data).hash(&mut hasher),
+        PlistRef::Array(elements) => {
+            2u8.hash(&mut hasher);
+            for element in elements {
+                fingerprint(element).hash(&mut hasher);
+            }
+        }
+        PlistRef::Dictionary(entries) => {
+            let entries = entries.iter().fold(0u64, |sum, entry| {
+                let mut hasher = DefaultHasher::new();
+                (entry.key.as_ref(), fingerprint(&entry.value)).hash(&mut hasher);
+                sum.wrapping_add(hasher.finish())
+            });
+            (3u8, entries).hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Positions of the elements of an array, to find an element without comparing it to every
+/// other one.
+struct Positions<'b, 'a> {
+    elements: &'b [PlistRef<'a>],
+    positions: HashMap<u64, Vec<usize>>,
+    /// No element is the `same` as another one.
+    distinct: bool,
+}
+
+impl<'b, 'a> Positions<'b, 'a> {
+    fn new(elements: &'b [PlistRef<'a>]) -> Self {
+        let mut positions = Positions {
+            elements,
+            positions: HashMap::new(),
+            distinct: true,
+        };
+        for (index, element) in elements.iter().enumerate() {
+            positions.distinct &= positions.get(element).is_none();
+            positions
+                .positions
+                .entry(fingerprint(element))
+                .or_default()
+                .push(index);
+        }
+        positions
+    }
+
+    /// Position of the first element that is the `same` as `element`.
+    fn get(&self, element: &PlistRef) -> Option<usize> {
+        self.positions
+            .get(&fingerprint(element))?
+            .iter()
+            .copied()
+            .find(|&index| same(&self.elements[index], element))
+    }
+
+    fn contains(&self, element: &PlistRef) -> bool {
+        self.get(element).is_some()
+    }
+}
+
+fn text(value: &PlistRef) -> String {
+    match value.as_str() {
+        Some(value) => value.to_string(),
+        None => value.clone().into_value().serialize_single_line(),
+    }
+}
+
+fn entries<'b, 'a>(value: Option<&'b PlistRef<'a>>) -> HashMap<&'b str, &'b PlistEntryRef<'a>> {
+    value
+        .and_then(PlistRef::as_dictionary)
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| (entry.key.as_ref(), entry))
+        .collect()
+}
+
+#[derive(Default)]
+struct Merge {
+    conflicts: Vec<MergeConflict>,
+}
+
+impl Merge {
+    fn value<'a>(
+        &mut self,
+        path: &str,
+        base: Option<&PlistRef<'a>>,
+        ours: Option<&PlistRef<'a>>,
+        theirs: Option<&PlistRef<'a>>,
+    ) -> Option<PlistRef<'a>> {
+        if same_option(ours, theirs) || same_option(base, theirs) {
+            return ours.cloned();
+        }
+        if same_option(base, ours) {
+            return theirs.cloned();
+        }
+        match (ours, theirs) {
+            (Some(PlistRef::Dictionary(_)), Some(PlistRef::Dictionary(_)))
+                if base.is_none_or(|base| base.as_dictionary().is_some()) =>
+            {
+                Some(self.dictionary(path, base, ours, theirs))
+            }
+            (Some(PlistRef::Array(our_elements)), Some(PlistRef::Array(their_elements))) => {
+                let base_elements = base.and_then(PlistRef::as_array).unwrap_or_default();
+                let base_positions = Positions::new(base_elements);
+                let our_positions = Positions::new(our_elements);
+                let their_positions = Positions::new(their_elements);
+                if base_positions.distinct && our_positions.distinct && their_positions.distinct {
+                    return Some(PlistRef::Array(merge_elements(
+                        &base_positions,
+                        &our_positions,
+                        &their_positions,
+                    )));
+                }
+                self.conflict(path, base, ours, theirs)
+            }
+            _ => self.conflict(path, base, ours, theirs),
+        }
+    }
+
+    /// Keeps our side.
+    fn conflict<'a>(
+        &mut self,
+        path: &str,
+        base: Option<&PlistRef>,
+        ours: Option<&PlistRef<'a>>,
+        theirs: Option<&PlistRef>,
+    ) -> Option<PlistRef<'a>> {
+        self.conflicts.push(MergeConflict {
+            path: path.to_string(),
+            base: base.map(text),
+            ours: ours.map(text),
+            theirs: theirs.map(text),
+        });
+        ours.cloned()
+    }
+
+    /// Entries in our order, followed by the ones only they added.
+    fn dictionary<'a>(
+        &mut self,
+        path: &str,
+        base: Option<&PlistRef<'a>>,
+        ours: Option<&PlistRef<'a>>,
+        theirs: Option<&PlistRef<'a>>,
+    ) -> PlistRef<'a> {
+        let base_entries = entries(base);
+        let our_entries = entries(ours);
+        let their_entries = entries(theirs);
+        let keys = ours
+            .and_then(PlistRef::as_dictionary)
+            .unwrap_or_default()
+            .iter()
+            .chain(
+                theirs
+                    .and_then(PlistRef::as_dictionary)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|entry| !our_entries.contains_key(entry.key.as_ref())),
+            );
+
+        let mut merged = vec![];
+        for entry in keys {
+            let key = entry.key.as_ref();
+            let value = self.value(
+                &join(path, key),
+                base_entries.get(key).map(|entry| &entry.value),
+                our_entries.get(key).map(|entry| &entry.value),
+                their_entries.get(key).map(|entry| &entry.value),
+            );
+            if let Some(value) = value {
+                merged.push(PlistEntryRef {
+                    value,
+                    ..entry.clone()
+                });
+            }
+        }
+        // new objects join the section of their isa
+        if merged.iter().any(|entry| entry.section.is_some()) {
+            merged.sort_by_key(|entry| entry.section);
+        }
+        PlistRef::Dictionary(merged)
+    }
+}
+
+/// Our elements without the ones they removed, with the ones they added inserted after the
+/// element preceding them on their side.
+fn merge_elements<'a>(
+    base: &Positions<'_, 'a>,
+    ours: &Positions<'_, 'a>,
+    theirs: &Positions<'_, 'a>,
+) -> Vec<PlistRef<'a>> {
+    let kept: Vec<PlistRef<'a>> = ours
+        .elements
+        .iter()
+        .filter(|element| !base.contains(element) || theirs.contains(element))
+        .cloned()
+        .collect();
+    let kept_positions = Positions::new(&kept);
+    // `added[0]` go first, `added[i + 1]` after `kept[i]`
+    let mut added = vec![vec![]; kept.len() + 1];
+    let mut after = 0;
+    for element in theirs.elements {
+        match kept_positions.get(element) {
+            Some(position) => after = position + 1,
+            None if !base.contains(element) => added[after].push(element.clone()),
+            None => {}
+        }
+    }
+
+    let mut added = added.into_iter();
+    let mut merged: Vec<PlistRef<'a>> = added.next().unwrap_or_default();
+    for (element, added) in kept.into_iter().zip(added) {
+        merged.push(element);
+        merged.extend(added);
+    }
+    merged
+}
+
+impl XcodePbxProject {
+    /// Three-way merge of `ours` and `theirs`, two versions of `base`: objects added on either
+    /// side are kept, objects removed on one side and left alone on the other are removed,
+    /// dictionaries are merged key by key and arrays of distinct elements as ordered sets, so
+    /// files added to the same group or build phase on both sides are all kept.
+    ///
+    /// A value changed in different ways on both sides (or changed on one side and removed on
+    /// the other) is a conflict: the merged project keeps our side and the conflict is returned.
+    pub fn merge(
+        base: &XcodePbxProject,
+        ours: &XcodePbxProject,
+        theirs: &XcodePbxProject,
+    ) -> (XcodePbxProject, Vec<MergeConflict>) {
+        let base = PlistRef::Dictionary(PlistEntryRef::from_items(base.items()));
+        let ours = PlistRef::Dictionary(PlistEntryRef::from_items(ours.items()));
+        let theirs = PlistRef::Dictionary(PlistEntryRef::from_items(theirs.items()));
+        let mut merge = Merge::default();
+        let merged = merge.dictionary("", Some(&base), Some(&ours), Some(&theirs));
+        let items = match merged {
+            PlistRef::Dictionary(entries) => PlistEntryRef::into_items(entries),
+            _ => vec![],
+        };
+        (XcodePbxProject::from_items(items), merge.conflicts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "\
+        F1 = {isa = PBXFileReference; path = a.swift; };
+        G = {isa = PBXGroup; children = (F1); name = Sources; };
+        T = {isa = PBXNativeTarget; name = App; productName = App; };
+    ";
+
+    fn project(objects: &str) -> XcodePbxProject {
+        let content = format!(
+            "// !$*UTF8*$!\n{{\n\tobjects = {{\n{}\n\t}};\n\trootObject = T;\n}}\n",
+            objects
+        );
+        XcodePbxProject::parse_from_string(&content).unwrap()
+    }
+
+    fn merge(ours: &str, theirs: &str) -> (XcodePbxProject, Vec<MergeConflict>) {
+        XcodePbxProject::merge(&project(BASE), &project(ours), &project(theirs))
+    }
+
+    fn get(project: &XcodePbxProject, path: &str) -> Option<String> {
+        project.get(path).unwrap().map(|value| text(&value))
+    }
+
+    /// Sorted IDs of the objects of `project`.
+    fn ids(project: &XcodePbxProject) -> Vec<String> {
+        let mut ids: Vec<String> = project
+            .query("objects/*")
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| path.trim_start_matches("objects/").to_string())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn unchanged_sides_merge_to_base() {
+        let (merged, conflicts) = merge(BASE, BASE);
+        assert_eq!(conflicts, []);
+        assert_eq!(merged.serialize(), project(BASE).serialize());
+    }
+
+    #[test]
+    fn disjoint_edits_to_one_object_merge() {
+        let ours = BASE.replace("name = App;", "name = Ours;");
+        let theirs = BASE.replace("productName = App;", "productName = Theirs;");
+        let (merged, conflicts) = merge(&ours, &theirs);
+        assert_eq!(conflicts, []);
+        assert_eq!(get(&merged, "objects/T/name").as_deref(), Some("Ours"));
+        assert_eq!(
+            get(&merged, "objects/T/productName").as_deref(),
+            Some("Theirs")
+        );
+    }
+
+    #[test]
+    fn objects_added_on_both_sides_are_kept() {
+        let ours = format!(
+            "{}\tF2 = {{isa = PBXFileReference; path = b.swift; }};\n",
+            BASE
+        );
+        let theirs = format!(
+            "{}\tF3 = {{isa = PBXFileReference; path = c.swift; }};\n",
+            BASE
+        );
+        let (merged, conflicts) = merge(&ours, &theirs);
+        assert_eq!(conflicts, []);
+        assert_eq!(ids(&merged), ["F1", "F2", "F3", "G", "T"]);
+    }
+
+    #[test]
+    fn the_same_object_added_on_both_sides_is_kept_once() {
+        let added = format!(
+            "{}\tF2 = {{isa = PBXFileReference; path = b.swift; }};\n",
+            BASE
+        );
+        let (merged, conflicts) = merge(&added, &added);
+        assert_eq!(conflicts, []);
+        assert_eq!(ids(&merged), ["F1", "F2", "G", "T"]);
+    }
+
+    #[test]
+    fn files_added_to_one_array_on_both_sides_are_all_kept() {
+        let ours = BASE.replace("children = (F1);", "children = (F1, F2);");
+        let theirs = BASE.replace("children = (F1);", "children = (F0, F1, F3);");
+        let (merged, conflicts) = merge(&ours, &theirs);
+        assert_eq!(conflicts, []);
+        assert_eq!(
+            get(&merged, "objects/G/children").as_deref(),
+            Some("(F0, F1, F3, F2, )")
+        );
+    }
+
+    #[test]
+    fn elements_removed_on_one_side_stay_removed() {
+        let ours = BASE.replace("children = (F1);", "children = (F1, F2);");
+        let theirs = BASE.replace("children = (F1);", "children = ();");
+        let (merged, conflicts) = merge(&ours, &theirs);
+        assert_eq!(conflicts, []);
+        assert_eq!(
+            get(&merged, "objects/G/children").as_deref(),
+            Some("(F2, )")
+        );
+    }
+
+    #[test]
+    fn merge_elements_keeps_their_insertion_point() {
+        let element = |id: &'static str| PlistRef::String(id.into(), None);
+        let [a, b, c, d, e] = ["A", "B", "C", "D", "E"].map(element);
+        let base = [a.clone(), b.clone(), c.clone()];
+        let ours = [a.clone(), d.clone(), b.clone(), c.clone()];
+        let theirs = [e.clone(), a.clone(), c.clone(), d.clone(), b, e.clone()];
+        let merged = merge_elements(
+            &Positions::new(&base),
+            &Positions::new(&ours),
+            &Positions::new(&theirs[1..4]),
+        );
+        let merged: Vec<&str> = merged.iter().filter_map(PlistRef::as_str).collect();
+        assert_eq!(merged, ["A", "D", "C"]);
+
+        let theirs = [a, e.clone(), c, PlistRef::String("F".into(), None), e];
+        let merged = merge_elements(
+            &Positions::new(&base),
+            &Positions::new(&ours),
+            &Positions::new(&theirs[..4]),
+        );
+        let merged: Vec<&str> = merged.iter().filter_map(PlistRef::as_str).collect();
+        assert_eq!(merged, ["A", "E", "D", "C", "F"]);
+    }
+
+    #[test]
+    fn elements_are_found_regardless_of_comments_and_key_order() {
+        let parse = |text: &'static str| PlistRef::parse(text).unwrap();
+        let elements = [
+            parse("{a = 1; b = (x, y); }"),
+            parse("x /* comment */"),
+            parse("<0aff>"),
+        ];
+        let positions = Positions::new(&elements);
+        assert!(positions.distinct);
+        assert_eq!(
+            positions.get(&parse("{b = (x /* x */, y); a = 1; }")),
+            Some(0)
+        );
+        assert_eq!(positions.get(&parse("x")), Some(1));
+        assert_eq!(positions.get(&parse("<0aff>")), Some(2));
+        assert_eq!(positions.get(&parse("{b = (y, x); a = 1; }")), None);
+        assert_eq!(positions.get(&parse("{a = 1; }")), None);
+        assert!(!Positions::new(&[parse("x"), parse("y"), parse("x /* again */")]).distinct);
+    }
+
+    #[test]
+    fn removal_against_no_change_removes_the_object() {
+        let theirs = BASE.replace("F1 = {isa = PBXFileReference; path = a.swift; };", "");
+        let (merged, conflicts) = merge(BASE, &theirs);
+        assert_eq!(conflicts, []);
+        assert_eq!(ids(&merged), ["G", "T"]);
+    }
+
+    #[test]
+    fn change_against_removal_is_a_conflict() {
+        let ours = BASE.replace("path = a.swift;", "path = renamed.swift;");
+        let theirs = BASE.replace("F1 = {isa = PBXFileReference; path = a.swift; };", "");
+        let (merged, conflicts) = merge(&ours, &theirs);
+        assert_eq!(
+            conflicts,
+            [MergeConflict {
+                path: "objects/F1".to_string(),
+                base: Some("{isa = PBXFileReference; path = a.swift; }".to_string()),
+                ours: Some("{isa = PBXFileReference; path = renamed.swift; }".to_string()),
+                theirs: None,
+            }]
+        );
+        assert_eq!(
+            get(&merged, "objects/F1/path").as_deref(),
+            Some("renamed.swift")
+        );
+
+        // the other way around, our removal is kept
+        let (merged, conflicts) = merge(&theirs, &ours);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].ours, None);
+        assert_eq!(ids(&merged), ["G", "T"]);
+    }
+
+    #[test]
+    fn different_changes_to_one_value_conflict() {
+        let ours = BASE.replace("name = App;", "name = Ours;");
+        let theirs = BASE.replace("name = App;", "name = Theirs;");
+        let (merged, conflicts) = merge(&ours, &theirs);
+        assert_eq!(
+            conflicts,
+            [MergeConflict {
+                path: "objects/T/name".to_string(),
+                base: Some("App".to_string()),
+                ours: Some("Ours".to_string()),
+                theirs: Some("Theirs".to_string()),
+            }]
+        );
+        assert_eq!(get(&merged, "objects/T/name").as_deref(), Some("Ours"));
+    }
+
+    #[test]
+    fn arrays_with_repeated_elements_conflict() {
+        let ours = BASE.replace("children = (F1);", "children = (F1, F1);");
+        let theirs = BASE.replace("children = (F1);", "children = (F1, F2);");
+        let (_, conflicts) = merge(&ours, &theirs);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, "objects/G/children");
+    }
+}
@@ -1,4 +1,19 @@
+mod annotations;
 pub(crate) mod helpers;
+mod json_convert;
+mod lint;
+mod merge;
+mod pbx_macro;
+mod plist_document;
+mod plist_path;
+mod plist_reader;
+mod project_diff;
+pub(crate) mod plist_serde;
 pub(crate) mod types;
 pub(crate) mod xcode_pbxproj_parser;
-pub(crate) use types::*;
+pub use lint::{LintDiagnostic, Severity};
+pub use merge::MergeConflict;
+pub use plist_document::{PlistDocument, PlistObjects};
+pub use project_diff::{ObjectMatching, ProjectChange};
+pub use xcode_pbxproj_parser::XcodePbxProject;
+pub use types::*;
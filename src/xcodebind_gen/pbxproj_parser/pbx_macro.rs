@@ -0,0 +1,330 @@
+/// Builds a dictionary (`PlistItem::Array`) written in the pbxproj (OpenStep plist) syntax.
+///
+/// ```
+/// use xcode_uniffi::pbx;
+///
+/// let name = "MyLib";
+/// let rule = pbx! {
+///     isa = PBXBuildRule;
+///     filePatterns = "*.udl";
+///     inputFiles = ();
+///     name = [format!("{} - UniFFI Build rule", name)];
+///     outputFiles = (
+///         "$(SRCROOT)/$(INPUT_FILE_BASE).swift",
+///         [format!("$(SRCROOT)/rust_libs/{}/$(INPUT_FILE_BASE)FFI.h", name)],
+///     );
+///     settings = { ATTRIBUTES = (Public); };
+///     target = ["C39C62FB2336DEC2004581CA"; name];
+/// };
+/// assert_eq!(rule.isa(), Some("PBXBuildRule"));
+/// ```
+///
+/// Keys and values are bare words (`PBXBuildRule`, `YES`), string literals, or `[expr]` for
+/// any expression implementing `AsRef<str>`; `[expr; comment]` adds a `/* comment */`.
+/// `{ ... }` nests a dictionary, `( ... )` an array, and `..expr;` (`..expr,` in arrays)
+/// splices the `PlistItem`s of an iterator, e.g. entries built in a loop or the sections of
+/// `objects`. Keys of arrays can't carry a comment.
+#[macro_export]
+macro_rules! pbx {
+    (@entries $entries:ident) => {};
+    (@entries $entries:ident .. $items:expr ; $($rest:tt)*) => {
+        $entries.extend($items);
+        $crate::pbx!(@entries $entries $($rest)*);
+    };
+    (@entries $entries:ident $key:tt = ( $($elements:tt)* ) ; $($rest:tt)*) => {
+        $entries.push($crate::plist::PlistItem::TupleItem($crate::plist::PlistTupleItem {
+            name: $crate::pbx!(@name $key),
+            items: $crate::pbx!(@array $($elements)*),
+        }));
+        $crate::pbx!(@entries $entries $($rest)*);
+    };
+    (@entries $entries:ident $key:tt = $value:tt ; $($rest:tt)*) => {
+        $entries.push($crate::plist::PlistItem::Item($crate::plist::PlistKeyValueItem {
+            key: ::std::boxed::Box::new($crate::plist::PlistKey {
+                name: $crate::pbx!(@string $key),
+                comment: $crate::pbx!(@comment $key),
+            }),
+            value: ::std::boxed::Box::new($crate::pbx!(@value $value)),
+        }));
+        $crate::pbx!(@entries $entries $($rest)*);
+    };
+
+    (@array $($elements:tt)*) => {{
+        #[allow(unused_mut, clippy::vec_init_then_push)]
+        let elements = {
+            let mut elements: ::std::vec::Vec<$crate::plist::PlistItem> = ::std::vec::Vec::new();
+            $crate::pbx!(@elements elements $($elements)*);
+            elements
+        };
+        elements
+    }};
+    (@elements $elements:ident) => {};
+    (@elements $elements:ident .. $items:expr $(, $($rest:tt)*)?) => {
+        $elements.extend($items);
+        $crate::pbx!(@elements $elements $($($rest)*)?);
+    };
+    (@elements $elements:ident ( $($array:tt)* ) $(, $($rest:tt)*)?) => {
+        $elements.push($crate::plist::PlistItem::Tuple($crate::pbx!(@array $($array)*)));
+        $crate::pbx!(@elements $elements $($($rest)*)?);
+    };
+    (@elements $elements:ident { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        $elements.push($crate::pbx!($($body)*));
+        $crate::pbx!(@elements $elements $($($rest)*)?);
+    };
+    (@elements $elements:ident $value:tt $(, $($rest:tt)*)?) => {
+        $elements.push($crate::plist::PlistItem::TupleValue($crate::plist::PlistTupleValue {
+            value: $crate::pbx!(@string $value),
+            comment: $crate::pbx!(@comment $value),
+        }));
+        $crate::pbx!(@elements $elements $($($rest)*)?);
+    };
+
+    (@value { $($body:tt)* }) => {
+        $crate::pbx!($($body)*)
+    };
+    (@value $value:tt) => {
+        $crate::plist::PlistItem::Value($crate::plist::PlistValue {
+            value: $crate::pbx!(@string $value),
+            comment: $crate::pbx!(@comment $value),
+        })
+    };
+
+    (@name [$name:expr]) => {
+        $crate::pbx!(@string [$name])
+    };
+    (@name $name:ident) => {
+        $crate::pbx!(@string $name)
+    };
+    (@name $name:literal) => {
+        $crate::pbx!(@string $name)
+    };
+    (@string [$value:expr $(; $comment:expr)?]) => {
+        $crate::plist::PlistString::from(::std::convert::AsRef::<str>::as_ref(&$value))
+    };
+    (@string $value:ident) => {
+        $crate::plist::PlistString::from(::std::stringify!($value))
+    };
+    (@string $value:literal) => {
+        $crate::plist::PlistString::from($value)
+    };
+    (@comment [$value:expr; $comment:expr]) => {
+        ::std::option::Option::Some(::std::string::String::from(
+            ::std::convert::AsRef::<str>::as_ref(&$comment),
+        ))
+    };
+    (@comment $value:tt) => {
+        ::std::option::Option::None
+    };
+
+    ($($body:tt)*) => {{
+        #[allow(unused_mut, clippy::vec_init_then_push)]
+        let entries = {
+            let mut entries: ::std::vec::Vec<$crate::plist::PlistItem> = ::std::vec::Vec::new();
+            $crate::pbx!(@entries entries $($body)*);
+            entries
+        };
+        $crate::plist::PlistItem::Array(entries)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    //! `pbx!` against the trees the section builders assembled by hand before it.
+
+    use crate::xcodebind_gen::types::*;
+
+    fn key(name: &str, comment: Option<&str>) -> Box<PlistKey> {
+        Box::new(PlistKey {
+            name: PlistString::from(name),
+            comment: comment.map(str::to_string),
+        })
+    }
+
+    /// `key /* key_comment */ = value /* value_comment */;`
+    fn value(
+        key_name: &str,
+        key_comment: Option<&str>,
+        value: &str,
+        value_comment: Option<&str>,
+    ) -> PlistItem {
+        PlistItem::Item(PlistKeyValueItem {
+            key: key(key_name, key_comment),
+            value: Box::new(PlistItem::Value(PlistValue {
+                value: PlistString::from(value),
+                comment: value_comment.map(str::to_string),
+            })),
+        })
+    }
+
+    /// `key /* key_comment */ = { entries };`
+    fn dictionary(key_name: &str, key_comment: Option<&str>, entries: Vec<PlistItem>) -> PlistItem {
+        PlistItem::Item(PlistKeyValueItem {
+            key: key(key_name, key_comment),
+            value: Box::new(PlistItem::Array(entries)),
+        })
+    }
+
+    fn array(name: &str, items: Vec<PlistItem>) -> PlistItem {
+        PlistItem::TupleItem(PlistTupleItem {
+            name: PlistString::from(name),
+            items,
+        })
+    }
+
+    fn element(value: &str, comment: Option<&str>) -> PlistItem {
+        PlistItem::TupleValue(PlistTupleValue {
+            value: PlistString::from(value),
+            comment: comment.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn bare_words_literals_and_expressions() {
+        let name = "MyLib";
+        let id = String::from("C39C62FB2336DEC2004581CA");
+        let built = pbx! {
+            isa = PBXFileReference;
+            includeInIndex = "0";
+            "path" = [format!("{}_ios.framework", name)];
+            sourceTree = "<group>";
+            [id.as_str()] = YES;
+            target = [id; name];
+        };
+        assert_eq!(
+            built,
+            PlistItem::Array(vec![
+                value("isa", None, "PBXFileReference", None),
+                value("includeInIndex", None, "0", None),
+                value("path", None, "MyLib_ios.framework", None),
+                value("sourceTree", None, "<group>", None),
+                value("C39C62FB2336DEC2004581CA", None, "YES", None),
+                value("target", None, "C39C62FB2336DEC2004581CA", Some("MyLib")),
+            ])
+        );
+    }
+
+    #[test]
+    fn keys_with_comments_and_nested_dictionaries() {
+        let id = "C39C62FB2336DEC2004581CA";
+        let built = pbx! {
+            [id; "MyLib_ios.h in Headers"] = {
+                isa = PBXBuildFile;
+                fileRef = ["C39C62FC2336DEC2004581CA"; "MyLib_ios.h"];
+                settings = { ATTRIBUTES = (Public, ); };
+            };
+        };
+        assert_eq!(
+            built,
+            PlistItem::Array(vec![dictionary(
+                id,
+                Some("MyLib_ios.h in Headers"),
+                vec![
+                    value("isa", None, "PBXBuildFile", None),
+                    value(
+                        "fileRef",
+                        None,
+                        "C39C62FC2336DEC2004581CA",
+                        Some("MyLib_ios.h")
+                    ),
+                    dictionary(
+                        "settings",
+                        None,
+                        vec![array("ATTRIBUTES", vec![element("Public", None)])]
+                    ),
+                ]
+            )])
+        );
+    }
+
+    #[test]
+    fn arrays_of_values_dictionaries_and_arrays() {
+        let name = "MyLib";
+        let built = pbx! {
+            inputFiles = ();
+            outputFiles = (
+                "$(SRCROOT)/$(INPUT_FILE_BASE).swift",
+                [format!("$(SRCROOT)/rust_libs/{}/$(INPUT_FILE_BASE)FFI.h", name)],
+                ["C39C62FB2336DEC2004581CA"; "MyLib_ios.h"]
+            );
+            packageProductDependencies = (
+                { isa = XCSwiftPackageProductDependency; productName = Lib; },
+                { productName = Other; },
+            );
+            nested = ((a, b), ());
+        };
+        assert_eq!(
+            built,
+            PlistItem::Array(vec![
+                array("inputFiles", vec![]),
+                array(
+                    "outputFiles",
+                    vec![
+                        element("$(SRCROOT)/$(INPUT_FILE_BASE).swift", None),
+                        element("$(SRCROOT)/rust_libs/MyLib/$(INPUT_FILE_BASE)FFI.h", None),
+                        element("C39C62FB2336DEC2004581CA", Some("MyLib_ios.h")),
+                    ]
+                ),
+                array(
+                    "packageProductDependencies",
+                    vec![
+                        PlistItem::Array(vec![
+                            value("isa", None, "XCSwiftPackageProductDependency", None),
+                            value("productName", None, "Lib", None),
+                        ]),
+                        PlistItem::Array(vec![value("productName", None, "Other", None)]),
+                    ]
+                ),
+                array(
+                    "nested",
+                    vec![
+                        PlistItem::Tuple(vec![element("a", None), element("b", None)]),
+                        PlistItem::Tuple(vec![]),
+                    ]
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn spliced_entries_and_elements() {
+        let files = ["a.udl", "b.udl"];
+        let shared = pbx! { CODE_SIGN_STYLE = Automatic; SWIFT_VERSION = "5.0"; };
+        let built = pbx! {
+            isa = PBXSourcesBuildPhase;
+            ..files.iter().map(|file| value(file, None, "udl", None));
+            files = (
+                first,
+                ..files.iter().map(|file| element(file, Some("in Sources"))),
+                last
+            );
+            buildSettings = { ..shared.into_entries(); PRODUCT_NAME = App; };
+        };
+        assert_eq!(
+            built,
+            PlistItem::Array(vec![
+                value("isa", None, "PBXSourcesBuildPhase", None),
+                value("a.udl", None, "udl", None),
+                value("b.udl", None, "udl", None),
+                array(
+                    "files",
+                    vec![
+                        element("first", None),
+                        element("a.udl", Some("in Sources")),
+                        element("b.udl", Some("in Sources")),
+                        element("last", None),
+                    ]
+                ),
+                dictionary(
+                    "buildSettings",
+                    None,
+                    vec![
+                        value("CODE_SIGN_STYLE", None, "Automatic", None),
+                        value("SWIFT_VERSION", None, "5.0", None),
+                        value("PRODUCT_NAME", None, "App", None),
+                    ]
+                ),
+            ])
+        );
+        assert_eq!(pbx! {}, PlistItem::Array(vec![]));
+    }
+}
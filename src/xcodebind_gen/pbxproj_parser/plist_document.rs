@@ -0,0 +1,216 @@
+use crate::xcodebind_gen::types::*;
+use crate::xcodebind_gen::{Result, XcodeUniffiError};
+
+use super::plist_reader::{next_entry, objects_start, plist_root, plist_value};
+use super::XcodePbxProject;
+
+/// `project.pbxproj`, or any other OpenStep plist dictionary, parsed without copying: keys,
+/// values and comments borrow from the text. The `// !$*UTF8*$!` header is optional.
+///
+/// Meant for read-only tools on large projects, `into_project` gives the owned, editable
+/// `XcodePbxProject`.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct PlistDocument<'a> {
+    entries: Vec<PlistEntryRef<'a>>,
+}
+
+impl<'a> PlistDocument<'a> {
+    pub fn parse(content: &'a str) -> Result<Self> {
+        let (_, entries) = plist_root(content.as_bytes()).map_err(|e| parse_error(content, e))?;
+        Ok(PlistDocument { entries })
+    }
+
+    /// Entries of the root dictionary (`archiveVersion`, `objects`, `rootObject`, ...).
+    pub fn entries(&self) -> &[PlistEntryRef<'a>] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<PlistEntryRef<'a>> {
+        self.entries
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PlistRef<'a>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.key == key)
+            .map(|entry| &entry.value)
+    }
+
+    /// Entries of the `objects` dictionary, keyed by object ID.
+    pub fn objects(&self) -> impl Iterator<Item = &PlistEntryRef<'a>> {
+        self.get("objects")
+            .and_then(PlistRef::as_dictionary)
+            .unwrap_or_default()
+            .iter()
+    }
+
+    pub fn into_project(self) -> XcodePbxProject {
+        XcodePbxProject::from_items(PlistEntryRef::into_items(self.entries))
+    }
+}
+
+impl<'a> PlistRef<'a> {
+    /// Parses a single value in plist text, e.g. `YES`, `"$(inherited)"` or `(a, b)`.
+    pub fn parse(content: &'a str) -> Result<Self> {
+        let (_, value) = plist_value(content.as_bytes()).map_err(|e| parse_error(content, e))?;
+        Ok(value)
+    }
+}
+
+/// Reads the entries of the `objects` dictionary one at a time, without building the
+/// rest of the document.
+///
+/// Iteration stops after the first error.
+pub struct PlistObjects<'a> {
+    content: &'a str,
+    input: &'a [u8],
+    section: Option<&'a str>,
+    is_done: bool,
+}
+
+impl<'a> PlistObjects<'a> {
+    pub fn new(content: &'a str) -> Result<Self> {
+        let (input, _) = objects_start(content.as_bytes()).map_err(|e| parse_error(content, e))?;
+        Ok(PlistObjects {
+            content,
+            input,
+            section: None,
+            is_done: false,
+        })
+    }
+}
+
+impl<'a> Iterator for PlistObjects<'a> {
+    type Item = Result<PlistEntryRef<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+        match next_entry(self.input, &mut self.section) {
+            Ok((rest, Some(entry))) => {
+                self.input = rest;
+                Some(Ok(entry))
+            }
+            Ok((_, None)) => {
+                self.is_done = true;
+                None
+            }
+            Err(e) => {
+                self.is_done = true;
+                Some(Err(parse_error(self.content, e)))
+            }
+        }
+    }
+}
+
+/// Error with the line of `content` where parsing stopped.
+fn parse_error(content: &str, e: nom::Err<nom::error::Error<&[u8]>>) -> XcodeUniffiError {
+    let rest = match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+        nom::Err::Incomplete(_) => &[],
+    };
+    let consumed = &content.as_bytes()[..content.len() - rest.len()];
+    let line = rest.split(|&b| b == b'\n').next().unwrap_or_default();
+    XcodeUniffiError::Parse(format!(
+        "Unexpected input at line {}: {:?}",
+        consumed.iter().filter(|&&b| b == b'\n').count() + 1,
+        String::from_utf8_lossy(line)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn value_forms() -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/value_forms.pbxproj");
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn header_is_only_required_for_projects() {
+        let content = "{\n\tname = App;\n\tobjects = {\n\t\tA = {isa = PBXGroup; };\n\t};\n}\n";
+        let document = PlistDocument::parse(content).unwrap();
+        assert_eq!(document.get("name").and_then(PlistRef::as_str), Some("App"));
+        assert_eq!(document.objects().count(), 1);
+        let objects = PlistObjects::new(content).unwrap();
+        assert_eq!(
+            objects
+                .map(|object| object.unwrap().key)
+                .collect::<Vec<_>>(),
+            ["A"]
+        );
+
+        let error = XcodePbxProject::parse_from_string(content).err().unwrap();
+        assert_eq!(error.kind(), "parse");
+        assert!(XcodePbxProject::parse_from_string(&format!("// !$*UTF8*$!\n{}", content)).is_ok());
+    }
+
+    #[test]
+    fn value_forms_round_trip() {
+        let content = value_forms();
+        let project = XcodePbxProject::parse_from_string(&content).unwrap();
+        assert_eq!(project.serialize_lossless(), content);
+        assert_eq!(project.serialize(), content);
+    }
+
+    #[test]
+    fn value_forms_are_read() {
+        let content = value_forms();
+        let document = PlistDocument::parse(&content).unwrap();
+        let target = document
+            .objects()
+            .find(|object| object.key == "0A0000000000000000000002")
+            .map(|object| &object.value)
+            .unwrap();
+        assert_eq!(
+            target.get("buildToolPath").and_then(PlistRef::as_str),
+            Some("")
+        );
+        assert_eq!(target.get("emptyData"), Some(&PlistRef::Data(vec![], None)));
+        assert_eq!(
+            target.get("fingerprint"),
+            Some(&PlistRef::Data(
+                vec![0x0f, 0xbd, 0x77, 0x7f, 0x12, 0x34, 0x56, 0x78, 0x9a],
+                None
+            ))
+        );
+
+        let matrix = target.get("matrix").and_then(PlistRef::as_array).unwrap();
+        let rows: Vec<Vec<&str>> = matrix
+            .iter()
+            .map(|row| {
+                row.as_array()
+                    .unwrap()
+                    .iter()
+                    .filter_map(PlistRef::as_str)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows, [vec!["a", "b c"], vec![]]);
+
+        let passes = target.get("passes").and_then(PlistRef::as_array).unwrap();
+        let inputs = passes[0]
+            .get("inputs")
+            .and_then(PlistRef::as_array)
+            .unwrap();
+        assert_eq!(
+            inputs,
+            [
+                PlistRef::String("".into(), None),
+                PlistRef::Data(vec![0xca, 0xfe], None)
+            ]
+        );
+        assert_eq!(passes[1], PlistRef::Dictionary(vec![]));
+
+        // the owned model reads the same values
+        let owned = XcodePbxProject::parse_from_string(&content).unwrap();
+        let fingerprint = owned
+            .get("objects/0A0000000000000000000002/fingerprint")
+            .unwrap()
+            .unwrap();
+        assert_eq!(fingerprint, *target.get("fingerprint").unwrap());
+    }
+}
@@ -0,0 +1,648 @@
+//! Paths into a plist tree, e.g. `objects/<id>/buildSettings/SDKROOT`.
+//!
+//! A path is a list of `/` separated steps. A step is a dictionary key, an array index or `*`
+//! for every entry or element, followed by any number of `[key=value]` filters keeping only
+//! the dictionaries whose `key` holds `value` (`[=value]` keeps the strings equal to `value`).
+//! Keys holding `/`, `[`, `]`, `"` or `*` are written in double quotes, with `\` escaping
+//! the next character. Entries of `/* Begin X section */` sections are found like any other
+//! entry of their dictionary.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::xcodebind_gen::types::*;
+use crate::xcodebind_gen::{Result, XcodeUniffiError};
+
+use super::XcodePbxProject;
+
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+enum Selector {
+    /// Dictionary key, or index of an array element.
+    Key(String),
+    Any,
+}
+
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+struct Filter {
+    /// Empty for `[=value]`.
+    key: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+struct Step {
+    selector: Selector,
+    filters: Vec<Filter>,
+}
+
+impl Step {
+    fn matches_key(&self, key: &str) -> bool {
+        match &self.selector {
+            Selector::Key(name) => name == key,
+            Selector::Any => true,
+        }
+    }
+
+    fn matches_index(&self, index: usize) -> bool {
+        match &self.selector {
+            Selector::Key(name) => name.parse() == Ok(index),
+            Selector::Any => true,
+        }
+    }
+
+    fn matches_value(&self, value: &PlistRef) -> bool {
+        self.filters.iter().all(|filter| {
+            let value = if filter.key.is_empty() {
+                Some(value)
+            } else {
+                value.get(&filter.key)
+            };
+            value.and_then(PlistRef::as_str) == Some(filter.value.as_str())
+        })
+    }
+
+    /// `matches_value` for an entry or element of an owned tree.
+    fn matches_item(&self, item: &PlistItem) -> bool {
+        self.filters.is_empty() || self.matches_value(&PlistRef::from(item))
+    }
+}
+
+fn path_error(path: &str, message: &str) -> XcodeUniffiError {
+    XcodeUniffiError::Query(format!("{} in {:?}", message, path))
+}
+
+fn parse_path(path: &str) -> Result<Vec<Step>> {
+    let mut chars = path.chars().peekable();
+    let mut steps = vec![];
+    loop {
+        steps.push(parse_step(&mut chars, path)?);
+        match chars.next() {
+            None => return Ok(steps),
+            Some('/') => {}
+            Some(c) => return Err(path_error(path, &format!("unexpected '{}'", c))),
+        }
+    }
+}
+
+fn parse_step(chars: &mut Peekable<Chars>, path: &str) -> Result<Step> {
+    let quoted = chars.peek() == Some(&'"');
+    let name = parse_token(chars, &['/', '['], path)?;
+    let selector = match name.as_str() {
+        "" if !quoted => return Err(path_error(path, "empty step")),
+        "*" if !quoted => Selector::Any,
+        _ => Selector::Key(name),
+    };
+    let mut filters = vec![];
+    while chars.next_if_eq(&'[').is_some() {
+        let key = parse_token(chars, &['=', ']'], path)?;
+        if chars.next() != Some('=') {
+            return Err(path_error(path, "expected '=' in filter"));
+        }
+        let value = parse_token(chars, &[']'], path)?;
+        if chars.next() != Some(']') {
+            return Err(path_error(path, "unclosed filter"));
+        }
+        filters.push(Filter { key, value });
+    }
+    Ok(Step { selector, filters })
+}
+
+/// Quoted string, or the text up to one of `stops`.
+fn parse_token(chars: &mut Peekable<Chars>, stops: &[char], path: &str) -> Result<String> {
+    let mut token = String::new();
+    if chars.next_if_eq(&'"').is_none() {
+        while let Some(c) = chars.next_if(|c| !stops.contains(c)) {
+            token.push(c);
+        }
+        return Ok(token);
+    }
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(token),
+            Some('\\') => token.extend(chars.next()),
+            Some(c) => token.push(c),
+            None => return Err(path_error(path, "unclosed quote")),
+        }
+    }
+}
+
+/// `path/key`, quoting `key` when it would not be read back as itself.
+pub(super) fn join(path: &str, key: &str) -> String {
+    let key = if key.is_empty() || key == "*" || key.contains(['/', '[', ']', '"', '\\']) {
+        format!("\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        key.to_string()
+    };
+    if path.is_empty() {
+        key
+    } else {
+        format!("{}/{}", path, key)
+    }
+}
+
+impl<'a> PlistRef<'a> {
+    /// Values at `path` below this value, each with its path, e.g.
+    /// `objects/*[isa=PBXNativeTarget]/name` gives the name of every native target.
+    pub fn query(&self, path: &str) -> Result<Vec<(String, PlistRef<'a>)>> {
+        let steps = parse_path(path)?;
+        let mut matches = vec![];
+        collect(self, &steps, String::new(), &mut matches);
+        Ok(matches)
+    }
+}
+
+fn collect<'a>(
+    value: &PlistRef<'a>,
+    steps: &[Step],
+    path: String,
+    matches: &mut Vec<(String, PlistRef<'a>)>,
+) {
+    let (step, rest) = match steps.split_first() {
+        Some(first) => first,
+        None => return matches.push((path, value.clone())),
+    };
+    let children: Vec<(String, &PlistRef<'a>)> = match value {
+        PlistRef::Dictionary(entries) => entries
+            .iter()
+            .filter(|entry| step.matches_key(&entry.key))
+            .map(|entry| (join(&path, &entry.key), &entry.value))
+            .collect(),
+        PlistRef::Array(elements) => elements
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| step.matches_index(*index))
+            .map(|(index, element)| (join(&path, &index.to_string()), element))
+            .collect(),
+        _ => return,
+    };
+    for (path, child) in children {
+        if step.matches_value(child) {
+            collect(child, rest, path, matches);
+        }
+    }
+}
+
+/// Dictionary or array of an owned tree.
+enum Container<'a> {
+    Dictionary(&'a mut Vec<PlistItem>),
+    Array(&'a mut Vec<PlistItem>),
+}
+
+impl<'a> Container<'a> {
+    /// Container holding the value of an entry or element, if it is not a scalar.
+    fn of(item: &'a mut PlistItem) -> Option<Self> {
+        match item {
+            PlistItem::Item(kv) => Container::of(kv.value.as_mut()),
+            PlistItem::TupleItem(tuple) => Some(Container::Array(&mut tuple.items)),
+            PlistItem::Array(items) => Some(Container::Dictionary(items)),
+            PlistItem::Tuple(items) => Some(Container::Array(items)),
+            _ => None,
+        }
+    }
+}
+
+fn entry_key(item: &PlistItem) -> Option<&str> {
+    match item {
+        PlistItem::Item(kv) => Some(kv.key.name.as_str()),
+        PlistItem::TupleItem(tuple) => Some(tuple.name.as_str()),
+        _ => None,
+    }
+}
+
+/// Entries of a dictionary, the ones of its sections included.
+fn entries_mut<'a>(items: &'a mut [PlistItem], entries: &mut Vec<&'a mut PlistItem>) {
+    for item in items {
+        match item {
+            PlistItem::SectionItem(section) => entries_mut(&mut section.items, entries),
+            item => entries.push(item),
+        }
+    }
+}
+
+/// Calls `apply` on every container `steps` leads to.
+fn walk_mut(container: Container, steps: &[Step], apply: &mut dyn FnMut(Container)) {
+    let (step, rest) = match steps.split_first() {
+        Some(first) => first,
+        None => return apply(container),
+    };
+    let children = match container {
+        Container::Dictionary(items) => {
+            let mut entries = vec![];
+            entries_mut(items, &mut entries);
+            entries
+                .into_iter()
+                .filter(|entry| entry_key(entry).is_some_and(|key| step.matches_key(key)))
+                .collect::<Vec<_>>()
+        }
+        Container::Array(items) => items
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| step.matches_index(*index))
+            .map(|(_, element)| element)
+            .collect(),
+    };
+    for child in children {
+        if step.matches_item(child) {
+            if let Some(container) = Container::of(child) {
+                walk_mut(container, rest, apply);
+            }
+        }
+    }
+}
+
+/// `value` as the value of a `key = value;` entry.
+fn into_value(item: PlistItem) -> PlistItem {
+    match item {
+        PlistItem::TupleValue(value) => PlistItem::Value(PlistValue {
+            value: value.value,
+            comment: value.comment,
+        }),
+        PlistItem::Item(kv) => into_value(*kv.value),
+        PlistItem::TupleItem(tuple) => PlistItem::Tuple(tuple.items),
+        PlistItem::SectionItem(section) => PlistItem::Array(section.items),
+        item => item,
+    }
+}
+
+/// `value` as an array element.
+fn into_element(item: PlistItem) -> PlistItem {
+    match into_value(item) {
+        PlistItem::Value(value) => PlistItem::TupleValue(PlistTupleValue {
+            value: value.value,
+            comment: value.comment,
+        }),
+        item => item,
+    }
+}
+
+/// `name = value;`, arrays are `TupleItem`s like in parsed projects.
+fn entry(name: PlistString, comment: Option<String>, value: PlistItem) -> PlistItem {
+    match value {
+        PlistItem::Tuple(items) => PlistItem::TupleItem(PlistTupleItem { name, items }),
+        value => PlistItem::Item(PlistKeyValueItem {
+            key: Box::new(PlistKey { name, comment }),
+            value: Box::new(value),
+        }),
+    }
+}
+
+fn set_in(container: Container, step: &Step, value: &PlistItem) -> usize {
+    let mut count = 0;
+    match container {
+        Container::Dictionary(items) => {
+            let mut entries = vec![];
+            entries_mut(items, &mut entries);
+            for item in entries {
+                let (name, comment) = match &*item {
+                    PlistItem::Item(kv) => (kv.key.name.clone(), kv.key.comment.clone()),
+                    PlistItem::TupleItem(tuple) => (tuple.name.clone(), None),
+                    _ => continue,
+                };
+                if step.matches_key(name.as_str()) && step.matches_item(item) {
+                    *item = entry(name, comment, value.clone());
+                    count += 1;
+                }
+            }
+            let missing = match &step.selector {
+                Selector::Key(key) if count == 0 && step.filters.is_empty() => Some(key),
+                _ => None,
+            };
+            if let Some(key) = missing {
+                let item = entry(PlistString::from(key.as_str()), None, value.clone());
+                // new objects go in the section of their `isa`, when there is one
+                let section = value.isa().and_then(|isa| {
+                    items.iter_mut().find_map(|item| match item {
+                        PlistItem::SectionItem(section) if section.name == isa => Some(section),
+                        _ => None,
+                    })
+                });
+                match section {
+                    Some(section) => section.items.push(item),
+                    None => items.push(item),
+                }
+                count = 1;
+            }
+        }
+        Container::Array(items) => {
+            for (index, element) in items.iter_mut().enumerate() {
+                if step.matches_index(index) && step.matches_item(element) {
+                    *element = into_element(value.clone());
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+fn remove_in(container: Container, step: &Step) -> usize {
+    match container {
+        Container::Dictionary(items) => remove_entries(items, step),
+        Container::Array(items) => {
+            let mut count = 0;
+            let mut index = 0;
+            items.retain(|element| {
+                let matches = step.matches_index(index) && step.matches_item(element);
+                index += 1;
+                count += matches as usize;
+                !matches
+            });
+            count
+        }
+    }
+}
+
+/// Removes the matching entries of a dictionary, and the sections this leaves empty.
+fn remove_entries(items: &mut Vec<PlistItem>, step: &Step) -> usize {
+    let mut count = 0;
+    items.retain_mut(|item| match item {
+        PlistItem::SectionItem(section) => {
+            let removed = remove_entries(&mut section.items, step);
+            count += removed;
+            removed == 0 || !section.items.is_empty()
+        }
+        item => {
+            let matches =
+                entry_key(item).is_some_and(|key| step.matches_key(key)) && step.matches_item(item);
+            count += matches as usize;
+            !matches
+        }
+    });
+    count
+}
+
+/// Path queries and edits, see the `plist_path` module for the path syntax.
+///
+/// ```
+/// use xcode_uniffi::plist::PlistRef;
+///
+/// let content = "// !$*UTF8*$!\n{\n\tobjects = {\n\
+///     \t\tC39C62FB2336DEC2004581CA /* Debug */ = {isa = XCBuildConfiguration; buildSettings = {SDKROOT = iphoneos; }; name = Debug; };\n\
+///     \t};\n}\n";
+/// let mut project = xcode_uniffi::XcodePbxProject::parse_from_string(content)?;
+/// let sdk = project.get("objects/*[isa=XCBuildConfiguration][name=Debug]/buildSettings/SDKROOT")?;
+/// assert_eq!(sdk.as_ref().and_then(|sdk| sdk.as_str()), Some("iphoneos"));
+///
+/// let version = PlistRef::parse("5.0")?.into_value();
+/// project.set("objects/*/buildSettings/SWIFT_VERSION", version)?;
+/// assert_eq!(project.query("objects/*/buildSettings/*")?.len(), 2);
+/// assert_eq!(project.remove("objects/*/buildSettings/SDKROOT")?, 1);
+/// # Ok::<(), xcode_uniffi::XcodeUniffiError>(())
+/// ```
+impl XcodePbxProject {
+    /// First value at `path`.
+    pub fn get(&self, path: &str) -> Result<Option<PlistRef<'_>>> {
+        Ok(self.query(path)?.into_iter().next().map(|(_, value)| value))
+    }
+
+    /// Every value at `path`, with its path, e.g. `objects/*[isa=PBXNativeTarget]/name`.
+    pub fn query(&self, path: &str) -> Result<Vec<(String, PlistRef<'_>)>> {
+        PlistRef::Dictionary(PlistEntryRef::from_items(self.items())).query(path)
+    }
+
+    /// Sets every value `path` matches to `value` and returns how many were set.
+    /// A key missing from a dictionary the rest of the path matches is added (when the last
+    /// step is a plain key), array elements are only replaced.
+    pub fn set(&mut self, path: &str, value: PlistItem) -> Result<usize> {
+        let steps = parse_path(path)?;
+        let (last, parents) = steps.split_last().expect("a path has at least one step");
+        let value = into_value(value);
+        let mut count = 0;
+        walk_mut(
+            Container::Dictionary(self.items_mut()),
+            parents,
+            &mut |container| count += set_in(container, last, &value),
+        );
+        Ok(count)
+    }
+
+    /// Removes every entry or element `path` matches and returns how many were removed.
+    pub fn remove(&mut self, path: &str) -> Result<usize> {
+        let steps = parse_path(path)?;
+        let (last, parents) = steps.split_last().expect("a path has at least one step");
+        let mut count = 0;
+        walk_mut(
+            Container::Dictionary(self.items_mut()),
+            parents,
+            &mut |container| count += remove_in(container, last),
+        );
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OBJECTS: &str = "
+/* Begin PBXFileReference section */
+\t\tF1 = {isa = PBXFileReference; path = a.swift; sourceTree = \"<group>\"; };
+\t\tF2 = {isa = PBXFileReference; path = b.swift; sourceTree = \"<group>\"; };
+/* End PBXFileReference section */
+
+/* Begin PBXGroup section */
+\t\tG = {isa = PBXGroup; children = (F1, F2, ); name = Sources; };
+/* End PBXGroup section */
+
+/* Begin XCBuildConfiguration section */
+\t\tC1 = {isa = XCBuildConfiguration; buildSettings = {\"OTHER_LDFLAGS[sdk=macosx*]\" = \"-lc++\"; \"*\" = star; SDKROOT = iphoneos; }; name = Debug; };
+\t\tC2 = {isa = XCBuildConfiguration; buildSettings = {SDKROOT = macosx; }; name = Release; };
+/* End XCBuildConfiguration section */
+";
+
+    fn project() -> XcodePbxProject {
+        let content = format!(
+            "// !$*UTF8*$!\n{{\n\tobjects = {{\n{}\n\t}};\n\trootObject = G;\n}}\n",
+            OBJECTS
+        );
+        XcodePbxProject::parse_from_string(&content).unwrap()
+    }
+
+    fn query(project: &XcodePbxProject, path: &str) -> Vec<(String, String)> {
+        project
+            .query(path)
+            .unwrap()
+            .into_iter()
+            .map(|(path, value)| {
+                let value = match value.as_str() {
+                    Some(value) => value.to_string(),
+                    None => value.into_value().serialize_single_line(),
+                };
+                (path, value)
+            })
+            .collect()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(path, value)| (path.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// Keys of the entries in the section of `isa`.
+    fn section(project: &XcodePbxProject, isa: &str) -> Option<Vec<String>> {
+        let objects = project.items().iter().find_map(|item| match item {
+            PlistItem::Item(kv) if kv.key.name == "objects" => match kv.value.as_ref() {
+                PlistItem::Array(objects) => Some(objects),
+                _ => None,
+            },
+            _ => None,
+        })?;
+        objects.iter().find_map(|item| match item {
+            PlistItem::SectionItem(section) if section.name == isa => Some(
+                section
+                    .items
+                    .iter()
+                    .filter_map(entry_key)
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn wildcards_and_filters_select_entries_and_elements() {
+        let project = project();
+        assert_eq!(
+            query(&project, "objects/*[isa=XCBuildConfiguration]/name"),
+            pairs(&[("objects/C1/name", "Debug"), ("objects/C2/name", "Release")])
+        );
+        assert_eq!(
+            query(
+                &project,
+                "objects/*[isa=XCBuildConfiguration][name=Release]/buildSettings/*"
+            ),
+            pairs(&[("objects/C2/buildSettings/SDKROOT", "macosx")])
+        );
+        assert_eq!(
+            query(&project, "objects/*/path"),
+            pairs(&[
+                ("objects/F1/path", "a.swift"),
+                ("objects/F2/path", "b.swift")
+            ])
+        );
+        assert_eq!(
+            query(&project, "objects/G/children/*[=F2]"),
+            pairs(&[("objects/G/children/1", "F2")])
+        );
+        assert_eq!(
+            query(&project, "objects/G/children/0"),
+            pairs(&[("objects/G/children/0", "F1")])
+        );
+        assert_eq!(query(&project, "objects/*[isa=PBXNativeTarget]/name"), []);
+        assert_eq!(query(&project, "objects/G/name/more"), []);
+    }
+
+    #[test]
+    fn quoted_keys_are_taken_literally() {
+        let project = project();
+        assert_eq!(
+            query(
+                &project,
+                "objects/C1/buildSettings/\"OTHER_LDFLAGS[sdk=macosx*]\""
+            ),
+            pairs(&[(
+                "objects/C1/buildSettings/\"OTHER_LDFLAGS[sdk=macosx*]\"",
+                "-lc++"
+            )])
+        );
+        assert_eq!(
+            query(&project, "objects/C1/buildSettings/\"*\""),
+            pairs(&[("objects/C1/buildSettings/\"*\"", "star")])
+        );
+        // paths of the results read back as themselves
+        for (path, value) in query(&project, "objects/C1/buildSettings/*") {
+            assert_eq!(query(&project, &path), [(path.clone(), value)]);
+        }
+        assert_eq!(join("a", "x\\\"y"), "a/\"x\\\\\\\"y\"");
+    }
+
+    #[test]
+    fn set_adds_new_objects_to_the_section_of_their_isa() {
+        let mut project = project();
+        let file = PlistRef::parse("{isa = PBXFileReference; path = c.swift; }")
+            .unwrap()
+            .into_value();
+        assert_eq!(project.set("objects/F3", file).unwrap(), 1);
+        assert_eq!(
+            section(&project, "PBXFileReference").unwrap(),
+            ["F1", "F2", "F3"]
+        );
+        assert_eq!(section(&project, "PBXGroup").unwrap(), ["G"]);
+
+        let target = PlistRef::parse("{isa = PBXNativeTarget; name = App; }")
+            .unwrap()
+            .into_value();
+        assert_eq!(project.set("objects/T", target).unwrap(), 1);
+        assert_eq!(section(&project, "PBXNativeTarget"), None);
+        assert_eq!(
+            query(&project, "objects/T/name"),
+            pairs(&[("objects/T/name", "App")])
+        );
+
+        let sdk = PlistRef::String("macosx".into(), None).into_value();
+        assert_eq!(
+            project.set("objects/*/buildSettings/SDKROOT", sdk).unwrap(),
+            2
+        );
+        let value = PlistRef::String("F9".into(), None).into_value();
+        assert_eq!(project.set("objects/G/children/*[=F2]", value).unwrap(), 1);
+        assert_eq!(
+            query(&project, "objects/G/children"),
+            pairs(&[("objects/G/children", "(F1, F9, )")])
+        );
+        // a key is added below filtered steps, but a filtered last step adds nothing
+        let value = PlistRef::String("x".into(), None).into_value();
+        assert_eq!(
+            project
+                .set("objects/*[isa=PBXGroup]/comments", value.clone())
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            query(&project, "objects/G/comments"),
+            pairs(&[("objects/G/comments", "x")])
+        );
+        assert_eq!(project.set("objects/*[isa=Missing]", value).unwrap(), 0);
+    }
+
+    #[test]
+    fn remove_drops_sections_left_empty() {
+        let mut project = project();
+        assert_eq!(project.remove("objects/F1").unwrap(), 1);
+        assert_eq!(section(&project, "PBXFileReference").unwrap(), ["F2"]);
+        assert_eq!(project.remove("objects/*[isa=PBXGroup]").unwrap(), 1);
+        assert_eq!(section(&project, "PBXGroup"), None);
+        assert!(!project.serialize_lossless().contains("PBXGroup section"));
+        assert_eq!(project.remove("objects/*[isa=PBXGroup]").unwrap(), 0);
+        assert_eq!(project.remove("objects/C1/buildSettings/\"*\"").unwrap(), 1);
+        assert_eq!(query(&project, "objects/C1/buildSettings/*").len(), 2);
+    }
+
+    #[test]
+    fn malformed_paths_are_errors() {
+        let project = project();
+        for path in [
+            "",
+            "objects//G",
+            "objects/",
+            "objects/*[isa]",
+            "objects/*[isa=X",
+            "objects/\"G",
+            "objects/*[isa=X]G",
+        ] {
+            let error = project.query(path).unwrap_err();
+            assert_eq!(error.kind(), "query", "{}", path);
+        }
+        assert_eq!(
+            project.query("objects/*[isa").unwrap_err().to_string(),
+            "Invalid path: expected '=' in filter in \"objects/*[isa\""
+        );
+        let mut project = project;
+        let value = PlistRef::String("x".into(), None).into_value();
+        assert!(project.set("objects/\"G", value).is_err());
+        assert!(project.remove("objects//G").is_err());
+    }
+}
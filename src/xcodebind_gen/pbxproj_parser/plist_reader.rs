@@ -0,0 +1,291 @@
+//! Recursive descent reader for OpenStep (ASCII) property lists, the format of `project.pbxproj`.
+//!
+//! Besides what Xcode writes, it accepts every OpenStep value form: quoted and bare strings,
+//! `<hex>` data, dictionaries and arrays nested in any combination, `//` comments and optional
+//! trailing commas in arrays.
+//!
+//! The grammar works on bytes and never looks back, so reading is linear in the file size.
+//! All delimiters are ASCII, which never occurs inside a UTF-8 multi-byte sequence, so every
+//! token is a valid UTF-8 slice of the original text.
+
+use nom::{
+    branch::alt,
+    bytes::complete::take_while,
+    character::complete::char,
+    combinator::{cut, map},
+    error::{make_error, ErrorKind},
+    sequence::delimited,
+    Err, IResult,
+};
+
+use std::borrow::Cow;
+
+use crate::xcodebind_gen::types::*;
+
+fn fail<T>(input: &[u8]) -> IResult<&[u8], T> {
+    Err(Err::Error(make_error(input, ErrorKind::Char)))
+}
+
+fn text(bytes: &[u8]) -> Result<&str, Err<nom::error::Error<&[u8]>>> {
+    std::str::from_utf8(bytes).map_err(|_| Err::Failure(make_error(bytes, ErrorKind::Char)))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Parses the root dictionary of a plist and everything around it.
+pub(crate) fn plist_root(input: &[u8]) -> IResult<&[u8], Vec<PlistEntryRef<'_>>> {
+    let (rest, items) = dictionary(skip_trivia(input))?;
+    let rest = skip_trivia(rest);
+    if !rest.is_empty() {
+        return fail(rest);
+    }
+    Ok((rest, items))
+}
+
+/// Parses a single value, e.g. the right hand side of an entry, and everything around it.
+pub(crate) fn plist_value(input: &[u8]) -> IResult<&[u8], PlistRef<'_>> {
+    let (rest, value) = commented_value(skip_trivia(input))?;
+    let rest = skip_trivia(rest);
+    if !rest.is_empty() {
+        return fail(rest);
+    }
+    Ok((rest, value))
+}
+
+fn is_section_marker(input: &[u8]) -> bool {
+    input.starts_with(b"/* Begin ") || input.starts_with(b"/* End ")
+}
+
+/// `/* Begin X section */` or `/* End X section */`: whether it ends a section, and `X`.
+fn section_marker(input: &[u8]) -> Option<(&[u8], bool, &str)> {
+    let is_end = input.starts_with(b"/* End ");
+    if !is_end && !input.starts_with(b"/* Begin ") {
+        return None;
+    }
+    let end = find(input, b"*/")?;
+    let name = std::str::from_utf8(&input[if is_end { 7 } else { 9 }..end])
+        .ok()?
+        .trim();
+    Some((
+        &input[end + 2..],
+        is_end,
+        name.strip_suffix(" section").unwrap_or(name),
+    ))
+}
+
+/// Skips whitespace and comments, stopping at `/* Begin X section */` and `/* End X section */`.
+fn skip_trivia(mut input: &[u8]) -> &[u8] {
+    loop {
+        input = input.trim_ascii_start();
+        if input.starts_with(b"//") {
+            input = input
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(&[][..], |end| &input[end + 1..]);
+        } else if input.starts_with(b"/*") && !is_section_marker(input) {
+            input = find(&input[2..], b"*/").map_or(&[][..], |end| &input[end + 4..]);
+        } else {
+            return input;
+        }
+    }
+}
+
+/// `/* comment */` following a key or value, trimmed.
+fn trailing_comment(input: &[u8]) -> (&[u8], Option<&str>) {
+    let rest = input.trim_ascii_start();
+    if rest.starts_with(b"/*") && !is_section_marker(rest) {
+        if let Some(end) = find(&rest[2..], b"*/") {
+            let comment = std::str::from_utf8(&rest[2..end + 2]).ok();
+            return (&rest[end + 4..], comment.map(str::trim));
+        }
+    }
+    (input, None)
+}
+
+fn string(input: &[u8]) -> IResult<&[u8], Cow<'_, str>> {
+    alt((quoted_string, bare_string))(input)
+}
+
+fn quoted_string(input: &[u8]) -> IResult<&[u8], Cow<'_, str>> {
+    if input.first() != Some(&b'"') {
+        return fail(input);
+    }
+    let mut is_escaped = false;
+    for (i, &b) in input.iter().enumerate().skip(1) {
+        if is_escaped {
+            is_escaped = false;
+        } else if b == b'\\' {
+            is_escaped = true;
+        } else if b == b'"' {
+            let token = text(&input[..i + 1])?;
+            return Ok((&input[i + 1..], PlistString::token_text(token)));
+        }
+    }
+    fail(input)
+}
+
+fn is_delimiter(b: u8) -> bool {
+    b.is_ascii_whitespace() || b";,=(){}<>\"".contains(&b)
+}
+
+/// Unquoted token, up to whitespace, a delimiter or a comment.
+fn bare_string(input: &[u8]) -> IResult<&[u8], Cow<'_, str>> {
+    let end = (0..input.len())
+        .find(|&i| {
+            is_delimiter(input[i])
+                || (input[i] == b'/' && matches!(input.get(i + 1), Some(b'*') | Some(b'/')))
+        })
+        .unwrap_or(input.len());
+    if end == 0 {
+        return fail(input);
+    }
+    Ok((&input[end..], Cow::Borrowed(text(&input[..end])?)))
+}
+
+fn data(input: &[u8]) -> IResult<&[u8], Vec<u8>> {
+    let (rest, digits) = delimited(
+        char('<'),
+        take_while(|b: u8| b.is_ascii_hexdigit() || b.is_ascii_whitespace()),
+        char('>'),
+    )(input)?;
+    let digits: Vec<u8> = digits
+        .iter()
+        .filter_map(|&b| (b as char).to_digit(16))
+        .map(|digit| digit as u8)
+        .collect();
+    if !digits.len().is_multiple_of(2) {
+        return fail(input);
+    }
+    Ok((
+        rest,
+        digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect(),
+    ))
+}
+
+fn value(input: &[u8]) -> IResult<&[u8], PlistRef<'_>> {
+    alt((
+        map(dictionary, PlistRef::Dictionary),
+        map(array, PlistRef::Array),
+        map(data, |value| PlistRef::Data(value, None)),
+        map(string, |value| PlistRef::String(value, None)),
+    ))(input)
+}
+
+/// `value /* comment */`
+fn commented_value(input: &[u8]) -> IResult<&[u8], PlistRef<'_>> {
+    let (input, value) = value(input)?;
+    let (input, comment) = trailing_comment(input);
+    let value = match value {
+        PlistRef::String(value, _) => PlistRef::String(value, comment),
+        PlistRef::Data(value, _) => PlistRef::Data(value, comment),
+        value => value,
+    };
+    Ok((input, value))
+}
+
+fn dictionary(input: &[u8]) -> IResult<&[u8], Vec<PlistEntryRef<'_>>> {
+    let (input, _) = char('{')(input)?;
+    // past the brace it can only be a dictionary, report errors where they are
+    let (input, entries) = cut(dictionary_entries)(input)?;
+    let (input, _) = cut(char('}'))(skip_trivia(input))?;
+    Ok((input, entries))
+}
+
+fn dictionary_entries(mut input: &[u8]) -> IResult<&[u8], Vec<PlistEntryRef<'_>>> {
+    let mut entries = vec![];
+    let mut section = None;
+    loop {
+        let (rest, entry) = next_entry(input, &mut section)?;
+        input = rest;
+        match entry {
+            Some(entry) => entries.push(entry),
+            None => return Ok((input, entries)),
+        }
+    }
+}
+
+/// Next entry of a dictionary, `None` at its closing `}`. `section` keeps track of the
+/// enclosing `/* Begin X section */` between calls.
+pub(crate) fn next_entry<'a>(
+    mut input: &'a [u8],
+    section: &mut Option<&'a str>,
+) -> IResult<&'a [u8], Option<PlistEntryRef<'a>>> {
+    loop {
+        input = skip_trivia(input);
+        if let Some((rest, is_end, name)) = section_marker(input) {
+            *section = if is_end { None } else { Some(name) };
+            input = rest;
+        } else if input.is_empty() || input[0] == b'}' {
+            return Ok((input, None));
+        } else {
+            let (rest, (key, key_comment)) = entry_key(input)?;
+            let (rest, value) = entry_value(rest)?;
+            let entry = PlistEntryRef {
+                key,
+                key_comment,
+                value,
+                section: *section,
+            };
+            return Ok((rest, Some(entry)));
+        }
+    }
+}
+
+/// `key /* comment */ =`
+fn entry_key(input: &[u8]) -> IResult<&[u8], (Cow<'_, str>, Option<&str>)> {
+    let (input, key) = string(input)?;
+    let (input, key_comment) = trailing_comment(input);
+    let (input, _) = char('=')(skip_trivia(input))?;
+    Ok((input, (key, key_comment)))
+}
+
+/// `value /* comment */;` after the `=` of an entry.
+fn entry_value(input: &[u8]) -> IResult<&[u8], PlistRef<'_>> {
+    let (input, value) = commented_value(skip_trivia(input))?;
+    let (input, _) = char(';')(skip_trivia(input))?;
+    Ok((input, value))
+}
+
+/// Moves to the first entry of the root `objects` dictionary, skipping the entries before it.
+pub(crate) fn objects_start(input: &[u8]) -> IResult<&[u8], ()> {
+    let (mut input, _) = char('{')(skip_trivia(input))?;
+    loop {
+        let (rest, (key, _)) = entry_key(skip_trivia(input))?;
+        let rest = skip_trivia(rest);
+        if key == "objects" && rest.first() == Some(&b'{') {
+            return Ok((&rest[1..], ()));
+        }
+        (input, _) = entry_value(rest)?;
+    }
+}
+
+/// `( element, element, )`, the trailing comma is optional.
+fn array(input: &[u8]) -> IResult<&[u8], Vec<PlistRef<'_>>> {
+    let (input, _) = char('(')(input)?;
+    cut(array_elements)(input)
+}
+
+fn array_elements(mut input: &[u8]) -> IResult<&[u8], Vec<PlistRef<'_>>> {
+    let mut elements = vec![];
+    loop {
+        input = skip_trivia(input);
+        if let Some(rest) = input.strip_prefix(b")") {
+            return Ok((rest, elements));
+        }
+        let (rest, element) = commented_value(input)?;
+        elements.push(element);
+        let rest = skip_trivia(rest);
+        input = match rest.first() {
+            Some(b',') => &rest[1..],
+            Some(b')') => rest,
+            _ => return fail(rest),
+        };
+    }
+}
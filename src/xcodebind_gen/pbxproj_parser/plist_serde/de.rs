@@ -0,0 +1,264 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use serde::de::{self, DeserializeSeed, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::xcodebind_gen::types::*;
+use crate::xcodebind_gen::{Result, XcodeUniffiError};
+
+impl de::Error for XcodeUniffiError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        XcodeUniffiError::Convert(msg.to_string())
+    }
+}
+
+/// Deserializes Rust values from a plist value.
+///
+/// Plist scalars are all strings, so numbers and booleans (`YES`/`NO`, `1`/`0`,
+/// `true`/`false`) are parsed from them. Strings borrowed from the parsed text can be
+/// deserialized as `&str`. A single string is accepted where a sequence is expected,
+/// as Xcode writes one element build settings without parentheses. `deserialize_any` cannot
+/// tell a number from a string and always yields strings, see `from_str`.
+pub struct Deserializer<'de> {
+    value: PlistRef<'de>,
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(value: PlistRef<'de>) -> Self {
+        Deserializer { value }
+    }
+
+    fn scalar(&self) -> Result<&str> {
+        match &self.value {
+            PlistRef::String(value, _) => Ok(value),
+            _ => Err(de::Error::custom("expected a string")),
+        }
+    }
+
+    fn parse<T: FromStr>(&self) -> Result<T> {
+        let text = self.scalar()?;
+        text.parse()
+            .map_err(|_| de::Error::custom(format!("invalid number: {:?}", text)))
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = XcodeUniffiError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            PlistRef::String(Cow::Borrowed(value), _) => visitor.visit_borrowed_str(value),
+            PlistRef::String(Cow::Owned(value), _) => visitor.visit_string(value),
+            PlistRef::Data(value, _) => visitor.visit_byte_buf(value),
+            PlistRef::Dictionary(entries) => visitor.visit_map(MapAccess {
+                entries: entries.into_iter(),
+                value: None,
+            }),
+            PlistRef::Array(elements) => visitor.visit_seq(SeqAccess {
+                elements: elements.into_iter(),
+            }),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.scalar()? {
+            "YES" | "yes" | "1" | "true" => visitor.visit_bool(true),
+            "NO" | "no" | "0" | "false" => visitor.visit_bool(false),
+            other => Err(de::Error::custom(format!("invalid boolean: {:?}", other))),
+        }
+    }
+
+    deserialize_number! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            value @ PlistRef::String(..) => visitor.visit_seq(SeqAccess {
+                elements: vec![value].into_iter(),
+            }),
+            value => Deserializer::new(value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.value {
+            PlistRef::String(variant, _) => visitor.visit_enum(EnumAccess {
+                variant,
+                value: PlistRef::String(Cow::Borrowed(""), None),
+            }),
+            PlistRef::Dictionary(entries) if entries.len() == 1 => {
+                let entry = entries.into_iter().next().expect("one entry");
+                visitor.visit_enum(EnumAccess {
+                    variant: entry.key,
+                    value: entry.value,
+                })
+            }
+            _ => Err(de::Error::custom(
+                "expected a variant name or a dictionary with a single key",
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf map struct identifier
+    }
+}
+
+struct SeqAccess<'de> {
+    elements: std::vec::IntoIter<PlistRef<'de>>,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess<'de> {
+    type Error = XcodeUniffiError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.elements
+            .next()
+            .map(|element| seed.deserialize(Deserializer::new(element)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+struct MapAccess<'de> {
+    entries: std::vec::IntoIter<PlistEntryRef<'de>>,
+    value: Option<PlistRef<'de>>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess<'de> {
+    type Error = XcodeUniffiError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.entries.next() {
+            Some(entry) => {
+                self.value = Some(entry.value);
+                seed.deserialize(Deserializer::new(PlistRef::String(entry.key, None)))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(Deserializer::new(value)),
+            None => Err(de::Error::custom(
+                "next_value_seed called before next_key_seed",
+            )),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct EnumAccess<'de> {
+    variant: Cow<'de, str>,
+    value: PlistRef<'de>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess<'de> {
+    type Error = XcodeUniffiError;
+    type Variant = Deserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(Deserializer::new(PlistRef::String(self.variant, None)))?;
+        Ok((variant, Deserializer::new(self.value)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer<'de> {
+    type Error = XcodeUniffiError;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
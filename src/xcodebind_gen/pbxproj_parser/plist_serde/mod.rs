@@ -0,0 +1,335 @@
+//! serde data format for pbxproj (OpenStep plist) text.
+//!
+//! Values go through the borrowed `PlistRef` model, so they can be read from and written to
+//! whole files (`from_str`/`to_string`) as well as single items of a parsed
+//! `XcodePbxProject` (`from_item`/`to_item`).
+
+use serde::{Deserialize, Serialize};
+
+use crate::xcodebind_gen::types::*;
+use crate::xcodebind_gen::{Result, XcodeUniffiError};
+
+use super::{PlistDocument, XcodePbxProject};
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+
+mod de;
+mod ser;
+
+/// Serializes `value` to a plist value.
+pub fn to_plist<T: ?Sized + Serialize>(value: &T) -> Result<PlistRef<'static>> {
+    value
+        .serialize(Serializer)?
+        .ok_or_else(|| XcodeUniffiError::Convert("None and () have no plist form".to_string()))
+}
+
+/// Serializes `value` to an owned plist value, e.g. the value of an `objects` entry.
+pub fn to_item<T: ?Sized + Serialize>(value: &T) -> Result<PlistItem> {
+    Ok(to_plist(value)?.into_value())
+}
+
+/// Serializes `value`, which must serialize to a dictionary, to a whole pbxproj file.
+/// Objects are grouped into sections by `isa` and everything is written in Xcode order.
+pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String> {
+    match to_plist(value)? {
+        PlistRef::Dictionary(entries) => {
+            let mut project = XcodePbxProject::from_items(PlistEntryRef::into_items(entries));
+            project.group_objects_by_isa();
+            Ok(project.serialize())
+        }
+        _ => Err(XcodeUniffiError::Convert(
+            "a pbxproj file must hold a dictionary".to_string(),
+        )),
+    }
+}
+
+/// Deserializes a `T` from a plist value.
+pub fn from_plist<'de, T: Deserialize<'de>>(value: PlistRef<'de>) -> Result<T> {
+    T::deserialize(Deserializer::new(value))
+}
+
+/// Deserializes a `T` from an item of a parsed project, borrowing its strings.
+pub fn from_item<'de, T: Deserialize<'de>>(item: &'de PlistItem) -> Result<T> {
+    from_plist(PlistRef::from(item))
+}
+
+/// Deserializes a `T` from the root dictionary of pbxproj text.
+///
+/// Plist scalars carry no type, so numbers and booleans are parsed from strings when the
+/// target type asks for them. Internally tagged and untagged enums, such as `objects` modelled
+/// as a `#[serde(tag = "isa")]` enum, are buffered by serde through `deserialize_any` and only
+/// ever see strings: their fields must be `String`s (or use `deserialize_with`), otherwise
+/// `includeInIndex = 0` fails with `invalid type: string "0", expected u8`. To read typed
+/// fields, match on `isa` and call `from_item` with the struct of that object instead.
+///
+/// ```
+/// use serde_derive::Deserialize;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Deserialize)]
+/// struct BuildFile {
+///     #[serde(rename = "fileRef")]
+///     file_ref: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Project {
+///     objects: BTreeMap<String, BuildFile>,
+/// }
+///
+/// let content = "// !$*UTF8*$!\n{\n\tobjects = {\n\
+///     \t\tC39C63112336DEC2004581CA /* main.swift in Sources */ = {isa = PBXBuildFile; fileRef = C39C63102336DEC2004581CA /* main.swift */; };\n\
+///     \t};\n}\n";
+/// let project: Project = xcode_uniffi::plist::from_str(content)?;
+/// assert_eq!(project.objects["C39C63112336DEC2004581CA"].file_ref, "C39C63102336DEC2004581CA");
+/// # Ok::<(), xcode_uniffi::XcodeUniffiError>(())
+/// ```
+pub fn from_str<'de, T: Deserialize<'de>>(content: &'de str) -> Result<T> {
+    from_plist(PlistRef::Dictionary(
+        PlistDocument::parse(content)?.into_entries(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Settings {
+        #[serde(rename = "ATTRIBUTES")]
+        attributes: Vec<String>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[allow(non_snake_case)]
+    struct Reference {
+        isa: String,
+        includeInIndex: u8,
+        explicitFileType: Option<String>,
+        lastKnownFileType: Option<String>,
+        fileEncoding: Option<i32>,
+        wrapsLines: bool,
+        settings: BTreeMap<String, Settings>,
+        data: Option<serde_bytes_like::Bytes>,
+    }
+
+    /// `Vec<u8>` serialized with `serialize_bytes`, as `serde_bytes` would.
+    mod serde_bytes_like {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        #[derive(Debug, PartialEq)]
+        pub struct Bytes(pub Vec<u8>);
+
+        impl Serialize for Bytes {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Bytes {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct Visitor;
+                impl<'de> serde::de::Visitor<'de> for Visitor {
+                    type Value = Bytes;
+                    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        f.write_str("data")
+                    }
+                    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Bytes, E> {
+                        Ok(Bytes(v))
+                    }
+                }
+                deserializer.deserialize_byte_buf(Visitor)
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Project<T> {
+        archive_version: u32,
+        objects: BTreeMap<String, T>,
+        root_object: String,
+    }
+
+    fn reference() -> Reference {
+        Reference {
+            isa: "PBXFileReference".to_string(),
+            includeInIndex: 0,
+            explicitFileType: Some("wrapper.framework".to_string()),
+            lastKnownFileType: None,
+            fileEncoding: Some(-4),
+            wrapsLines: true,
+            settings: BTreeMap::from([(
+                "Public".to_string(),
+                Settings {
+                    attributes: vec!["Public".to_string(), "Weak Linked".to_string()],
+                },
+            )]),
+            data: Some(serde_bytes_like::Bytes(vec![0xde, 0xad, 0x00])),
+        }
+    }
+
+    fn project<T>(object: T) -> Project<T> {
+        Project {
+            archive_version: 1,
+            objects: BTreeMap::from([("C39C63102336DEC2004581CA".to_string(), object)]),
+            root_object: "C39C63102336DEC2004581CA".to_string(),
+        }
+    }
+
+    #[test]
+    fn scalars_become_strings() {
+        let item = to_plist(&reference()).unwrap();
+        let entries = match &item {
+            PlistRef::Dictionary(entries) => entries,
+            _ => panic!("expected a dictionary"),
+        };
+        let value = |key: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.key == key)
+                .map(|entry| entry.value.clone())
+        };
+        assert_eq!(value("includeInIndex").unwrap().as_str(), Some("0"));
+        assert_eq!(value("fileEncoding").unwrap().as_str(), Some("-4"));
+        assert_eq!(value("wrapsLines").unwrap().as_str(), Some("YES"));
+        assert!(value("lastKnownFileType").is_none());
+        assert!(
+            matches!(value("data"), Some(PlistRef::Data(data, None)) if data == [0xde, 0xad, 0x00])
+        );
+    }
+
+    #[test]
+    fn structs_round_trip_through_pbxproj_text() {
+        let text = to_string(&project(reference())).unwrap();
+        assert!(text.contains("includeInIndex = 0;"));
+        assert!(text.contains("wrapsLines = YES;"));
+        assert!(text.contains("fileEncoding = \"-4\";"));
+        assert!(!text.contains("lastKnownFileType"));
+        let parsed: Project<Reference> = from_str(&text).unwrap();
+        assert_eq!(parsed, project(reference()));
+    }
+
+    #[test]
+    fn items_round_trip() {
+        let item = to_item(&reference()).unwrap();
+        assert_eq!(from_item::<Reference>(&item).unwrap(), reference());
+    }
+
+    #[test]
+    fn booleans_accept_every_spelling() {
+        for (text, expected) in [
+            ("YES", true),
+            ("1", true),
+            ("true", true),
+            ("NO", false),
+            ("0", false),
+        ] {
+            let value = PlistRef::String(text.into(), None);
+            assert_eq!(from_plist::<bool>(value).unwrap(), expected);
+        }
+        let error = from_plist::<bool>(PlistRef::String("maybe".into(), None)).unwrap_err();
+        assert!(
+            error.to_string().contains("invalid boolean: \"maybe\""),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn a_single_string_is_a_one_element_sequence() {
+        let value = PlistRef::String("$(inherited)".into(), None);
+        assert_eq!(from_plist::<Vec<String>>(value).unwrap(), ["$(inherited)"]);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Externally {
+        Unit,
+        Newtype(u32),
+        Struct { name: String, count: u16 },
+    }
+
+    #[test]
+    fn externally_tagged_enums_round_trip() {
+        for value in [
+            Externally::Unit,
+            Externally::Newtype(7),
+            Externally::Struct {
+                name: "App".to_string(),
+                count: 2,
+            },
+        ] {
+            let item = to_item(&value).unwrap();
+            assert_eq!(from_item::<Externally>(&item).unwrap(), value);
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "isa")]
+    enum Object {
+        PBXNativeTarget {
+            name: String,
+            #[serde(rename = "productName")]
+            product_name: Option<String>,
+            #[serde(rename = "buildSettings")]
+            build_settings: BTreeMap<String, Vec<String>>,
+        },
+        PBXFileReference {
+            path: String,
+            #[serde(rename = "includeInIndex")]
+            include_in_index: Option<String>,
+        },
+    }
+
+    #[test]
+    fn internally_tagged_enums_round_trip_with_string_fields() {
+        for object in [
+            Object::PBXNativeTarget {
+                name: "App".to_string(),
+                product_name: None,
+                build_settings: BTreeMap::from([(
+                    "OTHER_LDFLAGS".to_string(),
+                    vec!["-ObjC".to_string(), "-lz".to_string()],
+                )]),
+            },
+            Object::PBXFileReference {
+                path: "App.framework".to_string(),
+                include_in_index: Some("0".to_string()),
+            },
+        ] {
+            let text = to_string(&project(object.clone())).unwrap();
+            assert!(text.contains("isa = PBX"));
+            let parsed: Project<Object> = from_str(&text).unwrap();
+            assert_eq!(parsed, project(object));
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "isa")]
+    enum TypedObject {
+        PBXFileReference {
+            #[serde(rename = "includeInIndex")]
+            include_in_index: u8,
+        },
+    }
+
+    #[test]
+    fn internally_tagged_enums_only_see_strings() {
+        let object = TypedObject::PBXFileReference {
+            include_in_index: 0,
+        };
+        let text = to_string(&project(object)).unwrap();
+        assert!(text.contains("includeInIndex = 0;"));
+        let error = from_str::<Project<TypedObject>>(&text).unwrap_err();
+        assert!(error.to_string().contains("expected u8"), "{}", error);
+    }
+
+    #[test]
+    fn to_string_rejects_anything_but_a_dictionary() {
+        assert!(to_string(&vec!["a"]).is_err());
+        assert!(to_plist(&None::<u8>).is_err());
+    }
+}
@@ -0,0 +1,365 @@
+use std::borrow::Cow;
+
+use serde::ser::{self, Serialize};
+
+use crate::xcodebind_gen::types::*;
+use crate::xcodebind_gen::{Result, XcodeUniffiError};
+
+impl ser::Error for XcodeUniffiError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        XcodeUniffiError::Convert(msg.to_string())
+    }
+}
+
+/// Serializes Rust values into plist values.
+///
+/// Scalars become strings (booleans `YES`/`NO`), byte buffers `<data>`, sequences arrays, and
+/// maps and structs dictionaries. `None` and `()` serialize to `Ok(None)`: dictionaries leave
+/// such entries out, arrays reject them. Enum variants other than unit variants are written as
+/// a dictionary with the variant name as only key.
+pub struct Serializer;
+
+type Serialized = Option<PlistRef<'static>>;
+
+fn string(value: impl ToString) -> Result<Serialized> {
+    Ok(Some(PlistRef::String(Cow::Owned(value.to_string()), None)))
+}
+
+fn variant(name: &'static str, value: PlistRef<'static>) -> PlistRef<'static> {
+    PlistRef::Dictionary(vec![PlistEntryRef {
+        key: Cow::Borrowed(name),
+        key_comment: None,
+        value,
+        section: None,
+    }])
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Serialized;
+    type Error = XcodeUniffiError;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeDictionary;
+    type SerializeStruct = SerializeDictionary;
+    type SerializeStructVariant = SerializeDictionary;
+
+    fn serialize_bool(self, v: bool) -> Result<Serialized> {
+        string(if v { "YES" } else { "NO" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Serialized> {
+        string(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Serialized> {
+        Ok(Some(PlistRef::Data(v.to_vec(), None)))
+    }
+
+    fn serialize_none(self) -> Result<Serialized> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Serialized> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Serialized> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Serialized> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Serialized> {
+        string(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Serialized> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Serialized> {
+        Ok(value.serialize(self)?.map(|value| variant(name, value)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray> {
+        Ok(SerializeArray {
+            variant: None,
+            elements: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray> {
+        Ok(SerializeArray {
+            variant: Some(variant),
+            elements: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeDictionary> {
+        Ok(SerializeDictionary {
+            variant: None,
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeDictionary> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeDictionary> {
+        Ok(SerializeDictionary {
+            variant: Some(variant),
+            entries: Vec::with_capacity(len),
+            next_key: None,
+        })
+    }
+}
+
+pub struct SerializeArray {
+    variant: Option<&'static str>,
+    elements: Vec<PlistRef<'static>>,
+}
+
+impl SerializeArray {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        match value.serialize(Serializer)? {
+            Some(element) => self.elements.push(element),
+            None => return Err(ser::Error::custom("arrays can't hold None or ()")),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Serialized> {
+        let array = PlistRef::Array(self.elements);
+        Ok(Some(match self.variant {
+            Some(name) => variant(name, array),
+            None => array,
+        }))
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Serialized;
+    type Error = XcodeUniffiError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Serialized> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Serialized;
+    type Error = XcodeUniffiError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Serialized> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Serialized;
+    type Error = XcodeUniffiError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Serialized> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Serialized;
+    type Error = XcodeUniffiError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Serialized> {
+        self.finish()
+    }
+}
+
+pub struct SerializeDictionary {
+    variant: Option<&'static str>,
+    entries: Vec<PlistEntryRef<'static>>,
+    next_key: Option<Cow<'static, str>>,
+}
+
+impl SerializeDictionary {
+    fn insert<T: ?Sized + Serialize>(&mut self, key: Cow<'static, str>, value: &T) -> Result<()> {
+        if let Some(value) = value.serialize(Serializer)? {
+            self.entries.push(PlistEntryRef {
+                key,
+                key_comment: None,
+                value,
+                section: None,
+            });
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Serialized> {
+        let dictionary = PlistRef::Dictionary(self.entries);
+        Ok(Some(match self.variant {
+            Some(name) => variant(name, dictionary),
+            None => dictionary,
+        }))
+    }
+}
+
+impl ser::SerializeMap for SerializeDictionary {
+    type Ok = Serialized;
+    type Error = XcodeUniffiError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        match key.serialize(Serializer)? {
+            Some(PlistRef::String(key, _)) => {
+                self.next_key = Some(key);
+                Ok(())
+            }
+            _ => Err(ser::Error::custom("dictionary keys must be strings")),
+        }
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        match self.next_key.take() {
+            Some(key) => self.insert(key, value),
+            None => Err(ser::Error::custom(
+                "serialize_value called before serialize_key",
+            )),
+        }
+    }
+
+    fn end(self) -> Result<Serialized> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeDictionary {
+    type Ok = Serialized;
+    type Error = XcodeUniffiError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.insert(Cow::Borrowed(key), value)
+    }
+
+    fn end(self) -> Result<Serialized> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeDictionary {
+    type Ok = Serialized;
+    type Error = XcodeUniffiError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.insert(Cow::Borrowed(key), value)
+    }
+
+    fn end(self) -> Result<Serialized> {
+        self.finish()
+    }
+}
@@ -0,0 +1,877 @@
+//! Differences between two projects in terms of targets, build phases, build settings and files,
+//! rather than lines of text.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::xcodebind_gen::types::*;
+
+use super::annotations::object_names;
+use super::XcodePbxProject;
+
+/// Objects `diff` describes through targets, phases, configurations and groups.
+const DESCRIBED_ISAS: [&str; 8] = [
+    "PBXBuildFile",
+    "PBXFileReference",
+    "PBXGroup",
+    "PBXProject",
+    "PBXVariantGroup",
+    "XCBuildConfiguration",
+    "XCConfigurationList",
+    "XCVersionGroup",
+];
+
+/// Group path of the files directly under the main group.
+const MAIN_GROUP: &str = "<main group>";
+
+/// How `XcodePbxProject::diff` finds the object of one project in the other.
+#[derive(Debug, Clone, Copy, std::cmp::Eq, std::cmp::PartialEq)]
+pub enum ObjectMatching {
+    /// By object ID, for two versions of the same project.
+    Id,
+    /// By name (targets, phases, configurations) and path (files), for projects whose IDs
+    /// differ, e.g. a regenerated project and a hand-edited one.
+    Name,
+}
+
+/// Difference found by `XcodePbxProject::diff`. `old` and `new` are `None` on the side that
+/// has nothing, e.g. `new` of a removed target.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct ProjectChange {
+    /// Stable name of the change, e.g. `target-added` or `build-setting-changed`.
+    pub kind: &'static str,
+    /// What changed, e.g. `App`, `App/Sources/Foo.swift` or `App/Debug/SWIFT_VERSION`.
+    pub subject: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl ProjectChange {
+    fn new(kind: &'static str, subject: String, old: Option<String>, new: Option<String>) -> Self {
+        ProjectChange {
+            kind,
+            subject,
+            old,
+            new,
+        }
+    }
+}
+
+fn string<'a>(object: &'a PlistRef, key: &str) -> Option<&'a str> {
+    object.get(key).and_then(PlistRef::as_str)
+}
+
+fn ids<'a>(object: &'a PlistRef, key: &str) -> impl Iterator<Item = &'a str> {
+    object
+        .get(key)
+        .and_then(PlistRef::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(PlistRef::as_str)
+}
+
+/// Single line text of `value`, without comments.
+fn text(value: &PlistRef) -> String {
+    fn uncommented<'a>(value: &PlistRef<'a>) -> PlistRef<'a> {
+        match value {
+            PlistRef::String(value, _) => PlistRef::String(value.clone(), None),
+            PlistRef::Data(value, _) => PlistRef::Data(value.clone(), None),
+            PlistRef::Dictionary(entries) => PlistRef::Dictionary(
+                entries
+                    .iter()
+                    .map(|entry| PlistEntryRef {
+                        key: entry.key.clone(),
+                        key_comment: None,
+                        value: uncommented(&entry.value),
+                        section: None,
+                    })
+                    .collect(),
+            ),
+            PlistRef::Array(elements) => {
+                PlistRef::Array(elements.iter().map(uncommented).collect())
+            }
+        }
+    }
+    match value.as_str() {
+        Some(value) => value.to_string(),
+        None => uncommented(value).into_value().serialize_single_line(),
+    }
+}
+
+struct Phase {
+    key: String,
+    name: String,
+    /// Key and name of each built file.
+    files: Vec<(String, String)>,
+}
+
+/// Build settings by configuration name.
+type Configurations = BTreeMap<String, BTreeMap<String, String>>;
+
+struct Target {
+    key: String,
+    name: String,
+    phases: Vec<Phase>,
+    configurations: Configurations,
+}
+
+/// What `diff` compares, objects being identified by a key that depends on `ObjectMatching`.
+struct Model {
+    targets: Vec<Target>,
+    /// Configurations of the project itself.
+    configurations: Configurations,
+    /// Name and group path of each file reference.
+    files: BTreeMap<String, (String, String)>,
+    /// Name and content of the other objects, by ID.
+    others: BTreeMap<String, (String, String)>,
+}
+
+impl Model {
+    fn new(project: &XcodePbxProject, matching: ObjectMatching) -> Model {
+        let root = PlistRef::Dictionary(PlistEntryRef::from_items(project.items()));
+        let objects: HashMap<&str, &PlistRef> = root
+            .get("objects")
+            .and_then(PlistRef::as_dictionary)
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| (entry.key.as_ref(), &entry.value))
+            .collect();
+        let names = object_names(&root);
+        let name = |id: &str| names.get(id).cloned().unwrap_or_else(|| id.to_string());
+        let project_object = root
+            .get("rootObject")
+            .and_then(PlistRef::as_str)
+            .and_then(|id| objects.get(id));
+
+        // group path of every file, from the main group
+        let mut groups: HashMap<&str, String> = HashMap::new();
+        let mut pending: Vec<(&str, String)> = project_object
+            .and_then(|project| string(project, "mainGroup"))
+            .map(|id| (id, MAIN_GROUP.to_string()))
+            .into_iter()
+            .collect();
+        let mut visited: HashSet<&str> = HashSet::new();
+        while let Some((group, path)) = pending.pop() {
+            if !visited.insert(group) {
+                continue;
+            }
+            for child in objects
+                .get(group)
+                .map(|group| ids(group, "children"))
+                .into_iter()
+                .flatten()
+            {
+                groups.insert(child, path.clone());
+                if objects
+                    .get(child)
+                    .is_some_and(|child| child.get("children").is_some())
+                {
+                    let child_path = match path.as_str() {
+                        MAIN_GROUP => name(child),
+                        path => format!("{}/{}", path, name(child)),
+                    };
+                    pending.push((child, child_path));
+                }
+            }
+        }
+
+        let mut files = BTreeMap::new();
+        let mut file_keys: HashMap<&str, String> = HashMap::new();
+        let file_refs: Vec<&str> = objects
+            .iter()
+            .filter(|(_, object)| string(object, "isa") == Some("PBXFileReference"))
+            .map(|(&id, _)| id)
+            .collect();
+        let mut name_counts: HashMap<String, usize> = HashMap::new();
+        for &id in &file_refs {
+            *name_counts.entry(name(id)).or_default() += 1;
+        }
+        for &id in &file_refs {
+            let group = groups.get(id).cloned().unwrap_or_default();
+            let key = match matching {
+                ObjectMatching::Id => id.to_string(),
+                // files sharing a name are told apart by their group
+                ObjectMatching::Name if name_counts[&name(id)] > 1 => {
+                    format!("{}/{}", group, name(id))
+                }
+                ObjectMatching::Name => name(id),
+            };
+            file_keys.insert(id, key.clone());
+            files.insert(key, (name(id), group));
+        }
+        let key = |id: &str| match matching {
+            ObjectMatching::Id => id.to_string(),
+            ObjectMatching::Name => name(id),
+        };
+
+        let configurations_of = |owner: &PlistRef| -> Configurations {
+            let list = string(owner, "buildConfigurationList").and_then(|id| objects.get(id));
+            list.map(|list| ids(list, "buildConfigurations"))
+                .into_iter()
+                .flatten()
+                .filter_map(|id| objects.get(id))
+                .map(|configuration| {
+                    let settings = configuration
+                        .get("buildSettings")
+                        .and_then(PlistRef::as_dictionary)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|entry| (entry.key.to_string(), text(&entry.value)))
+                        .collect();
+                    let name = string(configuration, "name").unwrap_or_default();
+                    (name.to_string(), settings)
+                })
+                .collect()
+        };
+
+        let mut targets = vec![];
+        let mut configurations = Configurations::new();
+        if let Some(project) = project_object {
+            configurations = configurations_of(project);
+            for id in ids(project, "targets") {
+                let target = match objects.get(id) {
+                    Some(target) => target,
+                    None => continue,
+                };
+                let phases = ids(target, "buildPhases")
+                    .map(|phase| Phase {
+                        key: key(phase),
+                        name: name(phase),
+                        files: objects
+                            .get(phase)
+                            .map(|phase| ids(phase, "files"))
+                            .into_iter()
+                            .flatten()
+                            .map(|build_file| {
+                                let file = objects.get(build_file).and_then(|build_file| {
+                                    string(build_file, "fileRef")
+                                        .or_else(|| string(build_file, "productRef"))
+                                });
+                                match file {
+                                    Some(file) => (
+                                        file_keys.get(file).cloned().unwrap_or_else(|| key(file)),
+                                        name(file),
+                                    ),
+                                    None => (key(build_file), name(build_file)),
+                                }
+                            })
+                            .collect(),
+                    })
+                    .collect();
+                targets.push(Target {
+                    key: key(id),
+                    name: name(id),
+                    phases,
+                    configurations: configurations_of(target),
+                });
+            }
+        }
+
+        let mut others = BTreeMap::new();
+        if matching == ObjectMatching::Id {
+            for (&id, object) in &objects {
+                let isa = string(object, "isa").unwrap_or_default();
+                if !DESCRIBED_ISAS.contains(&isa)
+                    && !isa.ends_with("Target")
+                    && !isa.ends_with("BuildPhase")
+                {
+                    others.insert(id.to_string(), (name(id), text(object)));
+                }
+            }
+        }
+
+        Model {
+            targets,
+            configurations,
+            files,
+            others,
+        }
+    }
+}
+
+/// Elements of `new` whose key is not in `old` (added) and of `old` whose key is not in `new`
+/// (removed), in order.
+fn added_removed<'b, T>(
+    old: &'b [T],
+    new: &'b [T],
+    key: impl Fn(&T) -> &str,
+) -> (Vec<&'b T>, Vec<&'b T>) {
+    let old_keys: HashSet<&str> = old.iter().map(&key).collect();
+    let new_keys: HashSet<&str> = new.iter().map(&key).collect();
+    let removed = old
+        .iter()
+        .filter(|item| !new_keys.contains(key(item)))
+        .collect();
+    let added = new
+        .iter()
+        .filter(|item| !old_keys.contains(key(item)))
+        .collect();
+    (added, removed)
+}
+
+fn list(names: &[&str]) -> String {
+    names.join(", ")
+}
+
+fn diff_phases(old: &Target, new: &Target, changes: &mut Vec<ProjectChange>) {
+    let (added, removed) = added_removed(&old.phases, &new.phases, |phase| &phase.key);
+    for phase in removed {
+        let subject = format!("{}/{}", new.name, phase.name);
+        changes.push(ProjectChange::new(
+            "phase-removed",
+            subject,
+            Some(phase.name.clone()),
+            None,
+        ));
+    }
+    for phase in added {
+        let subject = format!("{}/{}", new.name, phase.name);
+        changes.push(ProjectChange::new(
+            "phase-added",
+            subject,
+            None,
+            Some(phase.name.clone()),
+        ));
+    }
+
+    let common = |phases: &[Phase], other: &[Phase]| -> Vec<(String, String)> {
+        phases
+            .iter()
+            .filter(|phase| other.iter().any(|other| other.key == phase.key))
+            .map(|phase| (phase.key.clone(), phase.name.clone()))
+            .collect()
+    };
+    let (old_order, new_order) = (
+        common(&old.phases, &new.phases),
+        common(&new.phases, &old.phases),
+    );
+    if old_order
+        .iter()
+        .map(|phase| &phase.0)
+        .ne(new_order.iter().map(|phase| &phase.0))
+    {
+        let names = |order: &[(String, String)]| {
+            list(
+                &order
+                    .iter()
+                    .map(|phase| phase.1.as_str())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        changes.push(ProjectChange::new(
+            "phases-reordered",
+            new.name.clone(),
+            Some(names(&old_order)),
+            Some(names(&new_order)),
+        ));
+    }
+
+    for new_phase in &new.phases {
+        let old_phase = match old.phases.iter().find(|phase| phase.key == new_phase.key) {
+            Some(phase) => phase,
+            None => continue,
+        };
+        let (added, removed) = added_removed(&old_phase.files, &new_phase.files, |file| &file.0);
+        for (_, file) in removed {
+            let subject = format!("{}/{}/{}", new.name, new_phase.name, file);
+            changes.push(ProjectChange::new(
+                "file-removed-from-phase",
+                subject,
+                Some(file.clone()),
+                None,
+            ));
+        }
+        for (_, file) in added {
+            let subject = format!("{}/{}/{}", new.name, new_phase.name, file);
+            changes.push(ProjectChange::new(
+                "file-added-to-phase",
+                subject,
+                None,
+                Some(file.clone()),
+            ));
+        }
+    }
+}
+
+fn diff_configurations(
+    owner: &str,
+    old: &Configurations,
+    new: &Configurations,
+    changes: &mut Vec<ProjectChange>,
+) {
+    let empty = BTreeMap::new();
+    let names: Vec<&String> = old
+        .keys()
+        .chain(new.keys().filter(|name| !old.contains_key(*name)))
+        .collect();
+    for name in names {
+        let subject = format!("{}/{}", owner, name);
+        let (old_settings, new_settings) = match (old.get(name), new.get(name)) {
+            (Some(old), Some(new)) => (old, new),
+            (Some(old), None) => {
+                changes.push(ProjectChange::new(
+                    "configuration-removed",
+                    subject,
+                    Some(name.clone()),
+                    None,
+                ));
+                (old, &empty)
+            }
+            (None, Some(new)) => {
+                changes.push(ProjectChange::new(
+                    "configuration-added",
+                    subject,
+                    None,
+                    Some(name.clone()),
+                ));
+                (&empty, new)
+            }
+            (None, None) => continue,
+        };
+        let keys: Vec<&String> = old_settings
+            .keys()
+            .chain(
+                new_settings
+                    .keys()
+                    .filter(|key| !old_settings.contains_key(*key)),
+            )
+            .collect();
+        for key in keys {
+            let (old_value, new_value) = (old_settings.get(key), new_settings.get(key));
+            if old_value != new_value {
+                changes.push(ProjectChange::new(
+                    "build-setting-changed",
+                    format!("{}/{}/{}", owner, name, key),
+                    old_value.cloned(),
+                    new_value.cloned(),
+                ));
+            }
+        }
+    }
+}
+
+impl XcodePbxProject {
+    /// Changes from this project to `other`: targets added, removed or renamed, build phases
+    /// added, removed or reordered, files added to or removed from phases, build settings changed
+    /// in each configuration, files added, removed or moved between groups and, when matching
+    /// by ID, any other object added, removed or changed.
+    ///
+    /// Comments, the order of dictionary entries and the formatting of the text are ignored.
+    pub fn diff(&self, other: &XcodePbxProject, matching: ObjectMatching) -> Vec<ProjectChange> {
+        let (old, new) = (Model::new(self, matching), Model::new(other, matching));
+        let mut changes = vec![];
+
+        let (added, removed) = added_removed(&old.targets, &new.targets, |target| &target.key);
+        for target in removed {
+            changes.push(ProjectChange::new(
+                "target-removed",
+                target.name.clone(),
+                Some(target.name.clone()),
+                None,
+            ));
+        }
+        for target in added {
+            changes.push(ProjectChange::new(
+                "target-added",
+                target.name.clone(),
+                None,
+                Some(target.name.clone()),
+            ));
+        }
+        for new_target in &new.targets {
+            let old_target = match old
+                .targets
+                .iter()
+                .find(|target| target.key == new_target.key)
+            {
+                Some(target) => target,
+                None => continue,
+            };
+            if old_target.name != new_target.name {
+                changes.push(ProjectChange::new(
+                    "target-renamed",
+                    new_target.name.clone(),
+                    Some(old_target.name.clone()),
+                    Some(new_target.name.clone()),
+                ));
+            }
+            diff_phases(old_target, new_target, &mut changes);
+            diff_configurations(
+                &new_target.name,
+                &old_target.configurations,
+                &new_target.configurations,
+                &mut changes,
+            );
+        }
+        diff_configurations(
+            "project",
+            &old.configurations,
+            &new.configurations,
+            &mut changes,
+        );
+
+        for (key, (name, old_group)) in &old.files {
+            match new.files.get(key) {
+                None => changes.push(ProjectChange::new(
+                    "file-removed",
+                    name.clone(),
+                    Some(old_group.clone()),
+                    None,
+                )),
+                Some((_, new_group)) if new_group != old_group => changes.push(ProjectChange::new(
+                    "file-moved",
+                    name.clone(),
+                    Some(old_group.clone()),
+                    Some(new_group.clone()),
+                )),
+                Some(_) => {}
+            }
+        }
+        for (key, (name, new_group)) in &new.files {
+            if !old.files.contains_key(key) {
+                changes.push(ProjectChange::new(
+                    "file-added",
+                    name.clone(),
+                    None,
+                    Some(new_group.clone()),
+                ));
+            }
+        }
+
+        for (id, (name, old_object)) in &old.others {
+            let subject = format!("{} ({})", id, name);
+            match new.others.get(id) {
+                None => changes.push(ProjectChange::new(
+                    "object-removed",
+                    subject,
+                    Some(old_object.clone()),
+                    None,
+                )),
+                Some((_, new_object)) if new_object != old_object => {
+                    changes.push(ProjectChange::new(
+                        "object-changed",
+                        subject,
+                        Some(old_object.clone()),
+                        Some(new_object.clone()),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        for (id, (name, new_object)) in &new.others {
+            if !old.others.contains_key(id) {
+                let subject = format!("{} ({})", id, name);
+                changes.push(ProjectChange::new(
+                    "object-added",
+                    subject,
+                    None,
+                    Some(new_object.clone()),
+                ));
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Project with an `App` target building `main.swift`, with `util.swift` in a `Sources`
+    /// group.
+    const OBJECTS: &str = "\
+        ID_B = {isa = PBXBuildFile; fileRef = ID_F; };
+        ID_F = {isa = PBXFileReference; path = main.swift; };
+        ID_F2 = {isa = PBXFileReference; path = util.swift; };
+        ID_G = {isa = PBXGroup; children = (ID_F, ID_SG); };
+        ID_H = {isa = PBXHeadersBuildPhase; files = (); };
+        ID_P = {isa = PBXProject; buildConfigurationList = ID_PL; mainGroup = ID_G; targets = (ID_T); };
+        ID_PD = {isa = XCBuildConfiguration; buildSettings = {SDKROOT = iphoneos; }; name = Debug; };
+        ID_PL = {isa = XCConfigurationList; buildConfigurations = (ID_PD); };
+        ID_S = {isa = PBXSourcesBuildPhase; files = (ID_B); };
+        ID_SG = {isa = PBXGroup; children = (ID_F2); name = Sources; };
+        ID_T = {isa = PBXNativeTarget; buildConfigurationList = ID_TL; buildPhases = (ID_S, ID_H); name = App; };
+        ID_TD = {isa = XCBuildConfiguration; buildSettings = {SWIFT_VERSION = 5.0; }; name = Debug; };
+        ID_TL = {isa = XCConfigurationList; buildConfigurations = (ID_TD); };
+    ";
+
+    /// Second target, `Tests`, with a sources phase building `util.swift`.
+    const TESTS_TARGET: &str = "\
+        ID_B2 = {isa = PBXBuildFile; fileRef = ID_F2; };
+        ID_S2 = {isa = PBXSourcesBuildPhase; files = (ID_B2); };
+        ID_T2 = {isa = PBXNativeTarget; buildConfigurationList = ID_TL2; buildPhases = (ID_S2); name = Tests; };
+        ID_TD2 = {isa = XCBuildConfiguration; name = Debug; };
+        ID_TL2 = {isa = XCConfigurationList; buildConfigurations = (ID_TD2); };
+    ";
+
+    fn project(objects: &str) -> XcodePbxProject {
+        let content = format!(
+            "// !$*UTF8*$!\n{{\n\tobjects = {{\n{}\n\t}};\n\trootObject = ID_P;\n}}\n",
+            objects
+        );
+        XcodePbxProject::parse_from_string(&content).unwrap()
+    }
+
+    /// `project` with every object ID changed, as in a regenerated project.
+    fn regenerated(objects: &str) -> XcodePbxProject {
+        let content = project(objects).serialize().replace("ID_", "NEW_");
+        XcodePbxProject::parse_from_string(&content).unwrap()
+    }
+
+    fn diff(old: &str, new: &str, matching: ObjectMatching) -> Vec<ProjectChange> {
+        project(old).diff(&project(new), matching)
+    }
+
+    fn change(
+        kind: &'static str,
+        subject: &str,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) -> ProjectChange {
+        ProjectChange::new(
+            kind,
+            subject.to_string(),
+            old.map(str::to_string),
+            new.map(str::to_string),
+        )
+    }
+
+    fn with_tests_target() -> String {
+        format!("{}{}", OBJECTS, TESTS_TARGET)
+            .replace("targets = (ID_T);", "targets = (ID_T, ID_T2);")
+    }
+
+    #[test]
+    fn identical_projects_have_no_changes() {
+        for matching in [ObjectMatching::Id, ObjectMatching::Name] {
+            assert_eq!(diff(OBJECTS, OBJECTS, matching), []);
+        }
+    }
+
+    #[test]
+    fn target_added_and_removed() {
+        for matching in [ObjectMatching::Id, ObjectMatching::Name] {
+            assert_eq!(
+                diff(OBJECTS, &with_tests_target(), matching),
+                [change("target-added", "Tests", None, Some("Tests"))]
+            );
+            assert_eq!(
+                diff(&with_tests_target(), OBJECTS, matching),
+                [change("target-removed", "Tests", Some("Tests"), None)]
+            );
+        }
+    }
+
+    #[test]
+    fn target_renamed() {
+        let renamed = OBJECTS.replace("name = App;", "name = Renamed;");
+        assert_eq!(
+            diff(OBJECTS, &renamed, ObjectMatching::Id),
+            [change(
+                "target-renamed",
+                "Renamed",
+                Some("App"),
+                Some("Renamed")
+            )]
+        );
+        // by name, a renamed target is another target
+        assert_eq!(
+            diff(OBJECTS, &renamed, ObjectMatching::Name),
+            [
+                change("target-removed", "App", Some("App"), None),
+                change("target-added", "Renamed", None, Some("Renamed")),
+            ]
+        );
+    }
+
+    #[test]
+    fn phases_reordered() {
+        let reordered =
+            OBJECTS.replace("buildPhases = (ID_S, ID_H);", "buildPhases = (ID_H, ID_S);");
+        for matching in [ObjectMatching::Id, ObjectMatching::Name] {
+            assert_eq!(
+                diff(OBJECTS, &reordered, matching),
+                [change(
+                    "phases-reordered",
+                    "App",
+                    Some("Sources, Headers"),
+                    Some("Headers, Sources")
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn files_added_to_and_removed_from_a_phase() {
+        let added = OBJECTS.replace("files = ();", "files = (ID_B2);")
+            + "ID_B2 = {isa = PBXBuildFile; fileRef = ID_F2; };\n";
+        assert_eq!(
+            diff(OBJECTS, &added, ObjectMatching::Id),
+            [change(
+                "file-added-to-phase",
+                "App/Headers/util.swift",
+                None,
+                Some("util.swift")
+            )]
+        );
+        assert_eq!(
+            diff(&added, OBJECTS, ObjectMatching::Name),
+            [change(
+                "file-removed-from-phase",
+                "App/Headers/util.swift",
+                Some("util.swift"),
+                None
+            )]
+        );
+    }
+
+    #[test]
+    fn file_moved() {
+        let moved = OBJECTS
+            .replace("children = (ID_F, ID_SG);", "children = (ID_SG);")
+            .replace("children = (ID_F2);", "children = (ID_F2, ID_F);");
+        for matching in [ObjectMatching::Id, ObjectMatching::Name] {
+            assert_eq!(
+                diff(OBJECTS, &moved, matching),
+                [change(
+                    "file-moved",
+                    "main.swift",
+                    Some("<main group>"),
+                    Some("Sources")
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn files_in_the_main_group_name_it() {
+        let added = OBJECTS.replace(
+            "children = (ID_F, ID_SG);",
+            "children = (ID_F, ID_SG, ID_Z);",
+        ) + "ID_Z = {isa = PBXFileReference; path = src/z.udl; };\n";
+        assert_eq!(
+            diff(OBJECTS, &added, ObjectMatching::Id),
+            [change("file-added", "z.udl", None, Some("<main group>"))]
+        );
+    }
+
+    #[test]
+    fn build_setting_changed() {
+        let changed = OBJECTS
+            .replace(
+                "SWIFT_VERSION = 5.0;",
+                "SWIFT_VERSION = 5.9; OTHER_LDFLAGS = (\"-ObjC\", \"-lz\");",
+            )
+            .replace("SDKROOT = iphoneos;", "");
+        for matching in [ObjectMatching::Id, ObjectMatching::Name] {
+            assert_eq!(
+                diff(OBJECTS, &changed, matching),
+                [
+                    change(
+                        "build-setting-changed",
+                        "App/Debug/SWIFT_VERSION",
+                        Some("5.0"),
+                        Some("5.9")
+                    ),
+                    change(
+                        "build-setting-changed",
+                        "App/Debug/OTHER_LDFLAGS",
+                        None,
+                        Some("(\"-ObjC\", \"-lz\", )")
+                    ),
+                    change(
+                        "build-setting-changed",
+                        "project/Debug/SDKROOT",
+                        Some("iphoneos"),
+                        None
+                    ),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn configurations_added_and_removed() {
+        let added = OBJECTS.replace("buildConfigurations = (ID_TD);", "buildConfigurations = (ID_TD, ID_TR);")
+            + "ID_TR = {isa = XCBuildConfiguration; buildSettings = {SWIFT_VERSION = 5.0; }; name = Release; };\n";
+        assert_eq!(
+            diff(OBJECTS, &added, ObjectMatching::Id),
+            [
+                change("configuration-added", "App/Release", None, Some("Release")),
+                change(
+                    "build-setting-changed",
+                    "App/Release/SWIFT_VERSION",
+                    None,
+                    Some("5.0")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn name_matching_ignores_ids() {
+        let old = project(&with_tests_target());
+        let new = regenerated(&with_tests_target());
+        assert_eq!(old.diff(&new, ObjectMatching::Name), []);
+
+        let by_id = old.diff(&new, ObjectMatching::Id);
+        let kinds: Vec<&str> = by_id.iter().map(|change| change.kind).collect();
+        assert!(kinds.contains(&"target-removed") && kinds.contains(&"target-added"));
+        assert!(kinds.contains(&"file-removed") && kinds.contains(&"file-added"));
+
+        let renamed = with_tests_target().replace("name = Tests;", "name = UnitTests;");
+        assert_eq!(
+            old.diff(&regenerated(&renamed), ObjectMatching::Name),
+            [
+                change("target-removed", "Tests", Some("Tests"), None),
+                change("target-added", "UnitTests", None, Some("UnitTests")),
+            ]
+        );
+    }
+
+    #[test]
+    fn files_sharing_a_name_are_matched_by_group() {
+        let objects = OBJECTS.replace("children = (ID_F2);", "children = (ID_F2, ID_F3);")
+            + "ID_F3 = {isa = PBXFileReference; path = main.swift; };\n";
+        let moved = objects
+            .replace("children = (ID_F2, ID_F3);", "children = (ID_F2);")
+            .replace(
+                "children = (ID_F, ID_SG);",
+                "children = (ID_F, ID_SG, ID_OG);",
+            )
+            + "ID_OG = {isa = PBXGroup; children = (ID_F3); name = Other; };\n";
+        let old = project(&objects);
+        assert_eq!(old.diff(&regenerated(&objects), ObjectMatching::Name), []);
+        // the key of a file sharing its name includes its group, so a move is a new file
+        assert_eq!(
+            old.diff(&regenerated(&moved), ObjectMatching::Name),
+            [
+                change("file-removed", "main.swift", Some("Sources"), None),
+                change("file-added", "main.swift", None, Some("Other")),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_objects_are_compared_by_id() {
+        let proxy =
+            "ID_X = {isa = PBXContainerItemProxy; containerPortal = ID_P; proxyType = 1; };\n";
+        let old = format!("{}{}", OBJECTS, proxy);
+        let new = format!(
+            "{}{}",
+            OBJECTS,
+            proxy.replace("proxyType = 1;", "proxyType = 2;")
+        );
+        let changes = diff(&old, &new, ObjectMatching::Id);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, "object-changed");
+        assert_eq!(
+            diff(OBJECTS, &old, ObjectMatching::Id)[0].kind,
+            "object-added"
+        );
+        assert_eq!(
+            diff(&old, OBJECTS, ObjectMatching::Id)[0].kind,
+            "object-removed"
+        );
+        assert_eq!(diff(&old, &new, ObjectMatching::Name), []);
+    }
+}
@@ -1,18 +1,22 @@
-pub use end_reason::*;
+pub use plist_data::*;
 pub use plist_item::*;
 pub use plist_key::*;
 pub use plist_key_value_item::*;
+pub use plist_ref::*;
 pub use plist_section_item::*;
+pub use plist_string::*;
 pub use plist_tuple_item::*;
 pub use plist_tuple_value::*;
 pub use plist_value::*;
 pub use types_traits::*;
 
-mod end_reason;
+mod plist_data;
 mod plist_item;
 mod plist_key;
 mod plist_key_value_item;
+mod plist_ref;
 mod plist_section_item;
+mod plist_string;
 mod plist_tuple_item;
 mod plist_tuple_value;
 mod plist_value;
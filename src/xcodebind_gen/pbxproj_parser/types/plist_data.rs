@@ -0,0 +1,36 @@
+use crate::xcodebind_gen::helpers::*;
+
+use super::*;
+
+/// `<0fbd777f 1234>` data literal.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct PlistData {
+    pub value: Vec<u8>,
+    pub comment: Option<String>,
+}
+
+impl PlistData {
+    /// Hex digits between angle brackets, in groups of four bytes.
+    pub fn to_literal(&self) -> String {
+        let mut out = String::with_capacity(self.value.len() * 2 + self.value.len() / 4 + 2);
+        out.push('<');
+        for (i, byte) in self.value.iter().enumerate() {
+            if i > 0 && i.is_multiple_of(4) {
+                out.push(' ');
+            }
+            out.push_str(format!("{:02x}", byte).as_str());
+        }
+        out.push('>');
+        out
+    }
+}
+
+impl InternalXcodePlistSerializer for PlistData {
+    fn serialize(&self, _number_of_tabs: &mut i32) -> String {
+        format!(
+            "{}{};\n",
+            self.to_literal(),
+            self.comment.as_spaced_comment()
+        )
+    }
+}
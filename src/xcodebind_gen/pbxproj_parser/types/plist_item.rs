@@ -3,14 +3,19 @@ use crate::xcodebind_gen::helpers::*;
 use super::*;
 
 #[allow(dead_code)]
-#[derive(Debug, std::cmp::Eq, std::cmp::PartialEq)]
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
 pub enum PlistItem {
     Value(PlistValue),
     TupleValue(PlistTupleValue),
     Item(PlistKeyValueItem),
     SectionItem(PlistSectionItem),
     TupleItem(PlistTupleItem),
+    /// Dictionary body.
     Array(Vec<PlistItem>),
+    /// `<hex>` data.
+    Data(PlistData),
+    /// Array without a key, i.e. an array nested in another array.
+    Tuple(Vec<PlistItem>),
 }
 
 impl InternalXcodePlistSerializer for PlistItem {
@@ -32,36 +37,156 @@ impl InternalXcodePlistSerializer for PlistItem {
                 string_val.push_str(value.serialize(number_of_tabs).as_str());
             }
             PlistItem::Array(value) => {
-                string_val.push('{');
-                if !value.is_empty() {
-                    string_val.push('\n');
-                    *number_of_tabs += 1;
-                    for litem in value {
-                        string_val.push_str(litem.serialize(number_of_tabs).as_str());
-                    }
-                    *number_of_tabs -= 1;
-                    string_val
-                        .push_str(format!("{}}};\n", String::get_tabs(*number_of_tabs)).as_str());
-                } else {
-                    string_val
-                        .push_str(format!("{}}};\n", String::get_tabs(*number_of_tabs)).as_str());
+                string_val.push_str("{\n");
+                *number_of_tabs += 1;
+                for litem in value {
+                    string_val.push_str(litem.serialize(number_of_tabs).as_str());
                 }
+                *number_of_tabs -= 1;
+                string_val.push_str(format!("{}}};\n", String::get_tabs(*number_of_tabs)).as_str());
             }
+            PlistItem::Data(value) => string_val.push_str(value.serialize(number_of_tabs).as_str()),
+            PlistItem::Tuple(value) => {
+                string_val.push_str("(\n");
+                string_val.push_str(PlistItem::serialize_elements(value, number_of_tabs).as_str());
+                string_val.push_str(format!("{});\n", String::get_tabs(*number_of_tabs)).as_str());
+            }
+        }
+
+        string_val
+    }
+}
+
+/// Objects Xcode writes on a single line, e.g. `{isa = PBXBuildFile; fileRef = ...; };`
+pub const SINGLE_LINE_ISAS: [&str; 2] = ["PBXBuildFile", "PBXFileReference"];
+
+impl PlistItem {
+    /// `isa` of a dictionary, or of the dictionary value of a `key = {...};` item.
+    pub fn isa(&self) -> Option<&str> {
+        match self {
+            PlistItem::Array(items) => items.iter().find_map(|item| match item {
+                PlistItem::Item(kv) if kv.key.name == "isa" => match kv.value.as_ref() {
+                    PlistItem::Value(isa) => Some(isa.value.as_str()),
+                    _ => None,
+                },
+                _ => None,
+            }),
+            PlistItem::Item(kv) => kv.value.isa(),
+            _ => None,
+        }
+    }
+
+    /// Entries of a dictionary, e.g. one built with `pbx!`. Any other item is its own only entry.
+    pub fn into_entries(self) -> Vec<PlistItem> {
+        match self {
+            PlistItem::Array(items) => items,
+            item => vec![item],
+        }
+    }
+
+    /// Dictionary whose `isa` is one of `SINGLE_LINE_ISAS`.
+    pub fn is_single_line_object(&self) -> bool {
+        matches!(self, PlistItem::Array(_))
+            && self.isa().is_some_and(|isa| SINGLE_LINE_ISAS.contains(&isa))
+    }
+
+    /// Serializes the item without line breaks, the way Xcode writes single-line objects.
+    pub fn serialize_single_line(&self) -> String {
+        match self {
+            PlistItem::Value(item) => format!("{}{}", item.value, item.comment.as_spaced_comment()),
+            PlistItem::TupleValue(item) => {
+                format!("{}{}, ", item.value, item.comment.as_spaced_comment())
+            }
+            PlistItem::Item(item) => format!(
+                "{} = {}; ",
+                item.key.serialize(&mut 0),
+                item.value.serialize_single_line()
+            ),
+            PlistItem::TupleItem(item) => format!(
+                "{} = ({}); ",
+                item.name,
+                item.items
+                    .iter()
+                    .map(|i| i.serialize_single_line_element())
+                    .collect::<String>()
+            ),
+            PlistItem::SectionItem(section) => section
+                .items
+                .iter()
+                .map(|i| i.serialize_single_line())
+                .collect(),
+            PlistItem::Array(items) => format!(
+                "{{{}}}",
+                items.iter().map(|i| i.serialize_single_line()).collect::<String>()
+            ),
+            PlistItem::Data(data) => format!("{}{}", data.to_literal(), data.comment.as_spaced_comment()),
+            PlistItem::Tuple(items) => format!(
+                "({})",
+                items
+                    .iter()
+                    .map(|i| i.serialize_single_line_element())
+                    .collect::<String>()
+            ),
         }
+    }
 
+    fn serialize_single_line_element(&self) -> String {
+        match self {
+            PlistItem::TupleValue(_) => self.serialize_single_line(),
+            item => format!("{}, ", item.serialize_single_line()),
+        }
+    }
+
+    /// Array elements, one per line at `number_of_tabs + 1`, each followed by a comma.
+    pub(crate) fn serialize_elements(items: &[PlistItem], number_of_tabs: &mut i32) -> String {
+        let mut string_val = String::new();
+        *number_of_tabs += 1;
+        for item in items {
+            string_val.push_str(
+                format!(
+                    "{}{}\n",
+                    String::get_tabs(*number_of_tabs),
+                    item.serialize_element(number_of_tabs)
+                )
+                .as_str(),
+            );
+        }
+        *number_of_tabs -= 1;
         string_val
     }
+
+    /// The item as an array element: like `serialize`, but ended by `,` instead of `;`.
+    fn serialize_element(&self, number_of_tabs: &mut i32) -> String {
+        match self {
+            PlistItem::TupleValue(value) => value.serialize(number_of_tabs),
+            PlistItem::Value(value) => {
+                format!("{}{},", value.value.to_quoted(), value.comment.as_spaced_comment())
+            }
+            PlistItem::Data(data) => {
+                format!("{}{},", data.to_literal(), data.comment.as_spaced_comment())
+            }
+            PlistItem::Tuple(items) => format!(
+                "(\n{}{}),",
+                PlistItem::serialize_elements(items, number_of_tabs),
+                String::get_tabs(*number_of_tabs)
+            ),
+            item => {
+                let serialized = item.serialize(number_of_tabs);
+                format!("{},", serialized.trim_end().trim_end_matches(';'))
+            }
+        }
+    }
 }
 
 impl XcodePlistSerializer for Vec<PlistItem> {
     fn serialize(&self) -> String {
-        let mut number_of_tabs = 2;
+        let mut number_of_tabs = 1;
         let mut string_val = String::new();
         string_val.push_str("// !$*UTF8*$!\n{\n");
         for item in self {
             string_val.push_str(item.serialize(&mut number_of_tabs).as_str());
         }
-        string_val.push_str("\n}\n");
+        string_val.push_str("}\n");
         string_val
     }
 }
@@ -1,6 +1,7 @@
 use crate::xcodebind_gen::helpers::*;
-use std::collections::HashMap;
-use std::fmt::format;
+use crate::pbx;
+use crate::xcodebind_gen::IdAllocator;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use super::*;
@@ -59,7 +60,7 @@ pub(crate) struct PBXBuildFileIds {
     pub(crate) macos_h: String,
     pub(crate) macos_docc: String,
     pub(crate) macos_tests_swift: String,
-    pub(crate) udl_files_hashmap: HashMap<String, (String, String)>,
+    pub(crate) udl_files_hashmap: BTreeMap<String, String>,
 }
 
 pub(crate) struct PBXFileReferenceIds {
@@ -73,7 +74,7 @@ pub(crate) struct PBXFileReferenceIds {
     pub(crate) macos_docc: String,
     pub(crate) macos_tests_xc: String,
     pub(crate) macos_tests_swift: String,
-    pub(crate) udl_files_hashmap: HashMap<String, String>,
+    pub(crate) udl_files_hashmap: BTreeMap<String, String>,
 }
 
 pub(crate) struct XCBuildConfigurationIds {
@@ -122,7 +123,7 @@ pub(crate) struct PBXContainerItemProxyIds {
     pub(crate) macos: String,
 }
 
-#[derive(Debug, std::cmp::Eq, std::cmp::PartialEq)]
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
 pub struct PlistKeyValueItem {
     pub key: Box<PlistKey>,
     pub value: Box<PlistItem>,
@@ -130,378 +131,141 @@ pub struct PlistKeyValueItem {
 
 impl InternalXcodePlistSerializer for PlistKeyValueItem {
     fn serialize(&self, number_of_tabs: &mut i32) -> String {
+        let value = if self.value.is_single_line_object() {
+            format!("{};\n", self.value.serialize_single_line())
+        } else {
+            self.value.serialize(number_of_tabs)
+        };
         format!(
             "{}{} = {}",
             String::get_tabs(*number_of_tabs),
             self.key.serialize(number_of_tabs),
-            self.value.serialize(number_of_tabs)
+            value
         )
     }
 }
 
 impl PlistKeyValueItem {
-    pub(crate) fn create_value_type_by_array(key_val_items: Vec<(&str, &str)>) -> Vec<PlistItem> {
-        let mut items: Vec<PlistItem> = vec![];
-        for (key, val) in key_val_items {
-            items.push(PlistItem::Item(PlistKeyValueItem {
-                key: Box::from(PlistKey {
-                    name: key.to_string(),
-                    comment: None,
-                }),
-                value: Box::from(PlistItem::Value(PlistValue {
-                    value: val.to_string(),
-                    comment: None,
-                })),
-            }))
-        }
-        items
-    }
-
-    pub(crate) fn create_value_type(
-        key: &str,
-        key_comment: Option<&str>,
-        value: &str,
-        value_comment: Option<&str>,
-    ) -> PlistItem {
-        PlistItem::Item(PlistKeyValueItem {
-            key: Box::from(PlistKey {
-                name: key.to_string(),
-                comment: key_comment.map(|f| f.to_string()),
-            }),
-            value: Box::from(PlistItem::Value(PlistValue {
-                value: value.to_string(),
-                comment: value_comment.map(|f| f.to_string()),
-            })),
-        })
-    }
-
-    pub(crate) fn create_array_type(
-        key: &str,
-        key_comment: Option<&str>,
-        value: Vec<PlistItem>,
-    ) -> PlistItem {
-        PlistItem::Item(PlistKeyValueItem {
-            key: Box::from(PlistKey {
-                name: key.to_string(),
-                comment: key_comment.map(|f| f.to_string()),
-            }),
-            value: Box::from(PlistItem::Array(value)),
-        })
-    }
-
     pub(crate) fn create_pbx_file_reference_section(
         xc_fw_name: &str,
         udl_files_path: &Vec<(PathBuf, String)>,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXFileReferenceIds, PlistItem) {
         let kind = "PBXFileReference";
-        let ios_framework = id_maker(
-            id_base,
-            kind,
+        let ios_framework = ids.allocate(kind,
             format!("{}_ios.framework", xc_fw_name).as_str(),
         );
-        let ios_h = id_maker(id_base, kind, format!("{}_ios.h", xc_fw_name).as_str());
-        let ios_docc = id_maker(id_base, kind, format!("{}_ios.docc", xc_fw_name).as_str());
-        let ios_tests_xc = id_maker(
-            id_base,
-            kind,
+        let ios_h = ids.allocate(kind, format!("{}_ios.h", xc_fw_name).as_str());
+        let ios_docc = ids.allocate(kind, format!("{}_ios.docc", xc_fw_name).as_str());
+        let ios_tests_xc = ids.allocate(kind,
             format!("{}_iosTests.xctest", xc_fw_name).as_str(),
         );
-        let ios_tests_swift = id_maker(
-            id_base,
-            kind,
+        let ios_tests_swift = ids.allocate(kind,
             format!("{}_iosTests.swift", xc_fw_name).as_str(),
         );
 
-        let macos_framework = id_maker(
-            id_base,
-            kind,
+        let macos_framework = ids.allocate(kind,
             format!("{}_macos.framework", xc_fw_name).as_str(),
         );
-        let macos_h = id_maker(id_base, kind, format!("{}_macos.h", xc_fw_name).as_str());
-        let macos_docc = id_maker(id_base, kind, format!("{}_macos.docc", xc_fw_name).as_str());
-        let macos_tests_xc = id_maker(
-            id_base,
-            kind,
+        let macos_h = ids.allocate(kind, format!("{}_macos.h", xc_fw_name).as_str());
+        let macos_docc = ids.allocate(kind, format!("{}_macos.docc", xc_fw_name).as_str());
+        let macos_tests_xc = ids.allocate(kind,
             format!("{}_macosTests.xctest", xc_fw_name).as_str(),
         );
-        let macos_tests_swift = id_maker(
-            id_base,
-            kind,
+        let macos_tests_swift = ids.allocate(kind,
             format!("{}_macosTests.swift", xc_fw_name).as_str(),
         );
 
-        println!("name: {} - id_base: {}", xc_fw_name, id_base);
-        let ios_framework_item = PlistKeyValueItem::create_array_type(
-            ios_framework.as_str(),
-            Some(format!("{}_ios.framework", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "explicitFileType",
-                    None,
-                    "wrapper.framework",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("includeInIndex", None, "0", None),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_ios.framework", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "sourceTree",
-                    None,
-                    "BUILT_PRODUCTS_DIR",
-                    None,
-                ),
-            ],
-        );
-
-        let ios_h_item = PlistKeyValueItem::create_array_type(
-            ios_h.as_str(),
-            Some(format!("{}_ios.h", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "lastKnownFileType",
-                    None,
-                    "sourcecode.c.h",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_ios.h", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let ios_docc_item = PlistKeyValueItem::create_array_type(
-            ios_docc.as_str(),
-            Some(format!("{}_ios.docc", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "lastKnownFileType",
-                    None,
-                    "folder.documentationcatalog",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_ios.docc", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let ios_tests_xc_item = PlistKeyValueItem::create_array_type(
-            ios_tests_xc.as_str(),
-            Some(format!("{}_iosTests.xctest", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "explicitFileType",
-                    None,
-                    "wrapper.cfbundle",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("includeInIndex", None, "0", None),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_iosTests.xctest", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "sourceTree",
-                    None,
-                    "BUILT_PRODUCTS_DIR",
-                    None,
-                ),
-            ],
-        );
-        let ios_swift_item = PlistKeyValueItem::create_array_type(
-            ios_tests_swift.as_str(),
-            Some(format!("{}_iosTests.swift", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "lastKnownFileType",
-                    None,
-                    "sourcecode.swift",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_iosTests.swift", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        // --------------- MacOS
-        let macos_framework_item = PlistKeyValueItem::create_array_type(
-            macos_framework.as_str(),
-            Some(format!("{}_macos.framework", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "explicitFileType",
-                    None,
-                    "wrapper.framework",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("includeInIndex", None, "0", None),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_macos.framework", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "sourceTree",
-                    None,
-                    "BUILT_PRODUCTS_DIR",
-                    None,
-                ),
-            ],
-        );
-
-        let macos_h_item = PlistKeyValueItem::create_array_type(
-            macos_h.as_str(),
-            Some(format!("{}_macos.h", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "lastKnownFileType",
-                    None,
-                    "sourcecode.c.h",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_macos.h", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let macos_docc_item = PlistKeyValueItem::create_array_type(
-            macos_docc.as_str(),
-            Some(format!("{}_macos.docc", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "lastKnownFileType",
-                    None,
-                    "folder.documentationcatalog",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_macos.docc", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let macos_tests_xc_item = PlistKeyValueItem::create_array_type(
-            macos_tests_xc.as_str(),
-            Some(format!("{}_macosTests.xctest", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "explicitFileType",
-                    None,
-                    "wrapper.cfbundle",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("includeInIndex", None, "0", None),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_macosTests.xctest", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "sourceTree",
-                    None,
-                    "BUILT_PRODUCTS_DIR",
-                    None,
-                ),
-            ],
-        );
-        let macos_swift_item = PlistKeyValueItem::create_array_type(
-            macos_tests_swift.as_str(),
-            Some(format!("{}_macosTests.swift", xc_fw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "lastKnownFileType",
-                    None,
-                    "sourcecode.swift",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("{}_macosTests.swift", xc_fw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let mut udl_files_hashmap: HashMap<String, String> = HashMap::new();
-        let mut items = vec![
-            ios_framework_item,
-            ios_h_item,
-            ios_docc_item,
-            ios_tests_xc_item,
-            ios_swift_item,
-            macos_framework_item,
-            macos_h_item,
-            macos_docc_item,
-            macos_tests_xc_item,
-            macos_swift_item,
-        ];
+        let mut udl_files_hashmap: BTreeMap<String, String> = BTreeMap::new();
+        let mut udl_file_items = vec![];
         for (path, udl_file_name) in udl_files_path {
-            let udl_file_id = id_maker(id_base, kind, udl_file_name.as_str());
-            let udl_file_item = PlistKeyValueItem::create_array_type(
-                udl_file_id.as_str(),
-                Some(udl_file_name),
-                vec![
-                    PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                    PlistKeyValueItem::create_value_type("lastKnownFileType", None, "text", None),
-                    PlistKeyValueItem::create_value_type("name", None, udl_file_name, None),
-                    PlistKeyValueItem::create_value_type(
-                        "path",
-                        None,
-                        path.to_str()
-                            .expect("Unable to convert from UDL file Path to Str"),
-                        None,
-                    ),
-                    PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-                ],
+            let udl_file_id = ids.allocate(kind, udl_file_name.as_str());
+            let udl_file_path = path
+                .to_str()
+                .expect("Unable to convert from UDL file Path to Str");
+            udl_file_items.extend(
+                pbx! {
+                    [udl_file_id] = {
+                        isa = PBXFileReference;
+                        lastKnownFileType = text;
+                        name = [udl_file_name];
+                        path = [udl_file_path];
+                        sourceTree = "<group>";
+                    };
+                }
+                .into_entries(),
             );
             udl_files_hashmap.insert(udl_file_name.to_string(), udl_file_id);
-            items.push(udl_file_item);
         }
 
+        let items = pbx! {
+            [ios_framework] = {
+                isa = PBXFileReference;
+                explicitFileType = "wrapper.framework";
+                includeInIndex = "0";
+                path = [format!("{}_ios.framework", xc_fw_name)];
+                sourceTree = BUILT_PRODUCTS_DIR;
+            };
+            [ios_h] = {
+                isa = PBXFileReference;
+                lastKnownFileType = "sourcecode.c.h";
+                path = [format!("{}_ios.h", xc_fw_name)];
+                sourceTree = "<group>";
+            };
+            [ios_docc] = {
+                isa = PBXFileReference;
+                lastKnownFileType = "folder.documentationcatalog";
+                path = [format!("{}_ios.docc", xc_fw_name)];
+                sourceTree = "<group>";
+            };
+            [ios_tests_xc] = {
+                isa = PBXFileReference;
+                explicitFileType = "wrapper.cfbundle";
+                includeInIndex = "0";
+                path = [format!("{}_iosTests.xctest", xc_fw_name)];
+                sourceTree = BUILT_PRODUCTS_DIR;
+            };
+            [ios_tests_swift] = {
+                isa = PBXFileReference;
+                lastKnownFileType = "sourcecode.swift";
+                path = [format!("{}_iosTests.swift", xc_fw_name)];
+                sourceTree = "<group>";
+            };
+            [macos_framework] = {
+                isa = PBXFileReference;
+                explicitFileType = "wrapper.framework";
+                includeInIndex = "0";
+                path = [format!("{}_macos.framework", xc_fw_name)];
+                sourceTree = BUILT_PRODUCTS_DIR;
+            };
+            [macos_h] = {
+                isa = PBXFileReference;
+                lastKnownFileType = "sourcecode.c.h";
+                path = [format!("{}_macos.h", xc_fw_name)];
+                sourceTree = "<group>";
+            };
+            [macos_docc] = {
+                isa = PBXFileReference;
+                lastKnownFileType = "folder.documentationcatalog";
+                path = [format!("{}_macos.docc", xc_fw_name)];
+                sourceTree = "<group>";
+            };
+            [macos_tests_xc] = {
+                isa = PBXFileReference;
+                explicitFileType = "wrapper.cfbundle";
+                includeInIndex = "0";
+                path = [format!("{}_macosTests.xctest", xc_fw_name)];
+                sourceTree = BUILT_PRODUCTS_DIR;
+            };
+            [macos_tests_swift] = {
+                isa = PBXFileReference;
+                lastKnownFileType = "sourcecode.swift";
+                path = [format!("{}_macosTests.swift", xc_fw_name)];
+                sourceTree = "<group>";
+            };
+            ..udl_file_items;
+        };
+
         let file_ref_ids = PBXFileReferenceIds {
             ios_framework,
             ios_h,
@@ -516,199 +280,88 @@ impl PlistKeyValueItem {
             udl_files_hashmap,
         };
 
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items,
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_build_file_section(
         pbx_file_ref_ids: &PBXFileReferenceIds,
         name: &str,
         udl_files_path: &Vec<(PathBuf, String)>,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXBuildFileIds, PlistItem) {
         let kind = "PBXBuildFile";
-        let ios_framework = id_maker(id_base, kind, format!("{}_ios.framework", name).as_str());
-        let ios_h = id_maker(id_base, kind, format!("{}_ios.h", name).as_str());
-        let ios_docc = id_maker(id_base, kind, format!("{}_ios.docc", name).as_str());
-        let ios_tests_swift = id_maker(id_base, kind, format!("{}_iosTests.swift", name).as_str());
-
-        let macos_framework = id_maker(id_base, kind, format!("{}_macos.framework", name).as_str());
-        let macos_h = id_maker(id_base, kind, format!("{}_macos.h", name).as_str());
-        let macos_docc = id_maker(id_base, kind, format!("{}_macos.docc", name).as_str());
+        let ios_framework = ids.allocate(kind, format!("{}_ios.framework", name).as_str());
+        let ios_h = ids.allocate(kind, format!("{}_ios.h", name).as_str());
+        let ios_docc = ids.allocate(kind, format!("{}_ios.docc", name).as_str());
+        let ios_tests_swift = ids.allocate(kind, format!("{}_iosTests.swift", name).as_str());
+
+        let macos_framework = ids.allocate(kind, format!("{}_macos.framework", name).as_str());
+        let macos_h = ids.allocate(kind, format!("{}_macos.h", name).as_str());
+        let macos_docc = ids.allocate(kind, format!("{}_macos.docc", name).as_str());
         let macos_tests_swift =
-            id_maker(id_base, kind, format!("{}_macosTests.swift", name).as_str());
-
-        let ios_framework_item = PlistKeyValueItem::create_array_type(
-            ios_framework.as_str(),
-            Some(format!("{}_ios.framework in Frameworks", name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "fileRef",
-                    None,
-                    pbx_file_ref_ids.ios_framework.as_str(),
-                    Some(format!("{}_ios.framework", name).as_str()),
-                ),
-            ],
-        );
-        let ios_h_item = PlistKeyValueItem::create_array_type(
-            ios_h.as_str(),
-            Some(format!("{}_ios.h in Headers", name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "fileRef",
-                    None,
-                    pbx_file_ref_ids.ios_h.as_str(),
-                    Some(format!("{}_ios.h", name).as_str()),
-                ),
-                PlistKeyValueItem::create_array_type(
-                    "settings",
-                    None,
-                    vec![PlistTupleItem::create("ATTRIBUTES", vec![("Public", None)])],
-                ),
-            ],
-        );
-        let ios_docc_item = PlistKeyValueItem::create_array_type(
-            ios_docc.as_str(),
-            Some(format!("{}_ios.docc in Sources", name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "fileRef",
-                    None,
-                    pbx_file_ref_ids.ios_docc.as_str(),
-                    Some(format!("{}_ios.docc", name).as_str()),
-                ),
-            ],
-        );
-        let ios_tests_swift_item = PlistKeyValueItem::create_array_type(
-            ios_tests_swift.as_str(),
-            Some(format!("{}_iosTests.swift in Sources", name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "fileRef",
-                    None,
-                    pbx_file_ref_ids.ios_tests_swift.as_str(),
-                    Some(format!("{}_iosTests.swift", name).as_str()),
-                ),
-            ],
-        );
+            ids.allocate(kind, format!("{}_macosTests.swift", name).as_str());
 
-        // --------------- MacOS
-        let macos_framework_item = PlistKeyValueItem::create_array_type(
-            macos_framework.as_str(),
-            Some(format!("{}_macos.framework in Frameworks", name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "fileRef",
-                    None,
-                    pbx_file_ref_ids.macos_framework.as_str(),
-                    Some(format!("{}_macos.framework", name).as_str()),
-                ),
-            ],
-        );
-        let macos_h_item = PlistKeyValueItem::create_array_type(
-            macos_h.as_str(),
-            Some(format!("{}_macos.h in Headers", name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "fileRef",
-                    None,
-                    pbx_file_ref_ids.macos_h.as_str(),
-                    Some(format!("{}_macos.h", name).as_str()),
-                ),
-                PlistKeyValueItem::create_array_type(
-                    "settings",
-                    None,
-                    vec![PlistTupleItem::create("ATTRIBUTES", vec![("Public", None)])],
-                ),
-            ],
-        );
-        let macos_docc_item = PlistKeyValueItem::create_array_type(
-            macos_docc.as_str(),
-            Some(format!("{}_macos.docc in Sources", name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "fileRef",
-                    None,
-                    pbx_file_ref_ids.macos_docc.as_str(),
-                    Some(format!("{}_macos.docc", name).as_str()),
-                ),
-            ],
-        );
-        let macos_tests_swift_item = PlistKeyValueItem::create_array_type(
-            macos_tests_swift.as_str(),
-            Some(format!("{}_macosTests.swift in Sources", name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "fileRef",
-                    None,
-                    pbx_file_ref_ids.macos_tests_swift.as_str(),
-                    Some(format!("{}_macosTests.swift", name).as_str()),
-                ),
-            ],
-        );
-
-        let mut udl_files_hashmap: HashMap<String, (String, String)> = HashMap::new();
-        let mut items = vec![
-            ios_framework_item,
-            ios_h_item,
-            ios_docc_item,
-            ios_tests_swift_item,
-            macos_framework_item,
-            macos_h_item,
-            macos_docc_item,
-            macos_tests_swift_item,
-        ];
+        let mut udl_files_hashmap: BTreeMap<String, String> = BTreeMap::new();
+        let mut udl_file_items = vec![];
         for (_, udl_file_name) in udl_files_path {
             let udl_file_ref_id = pbx_file_ref_ids
                 .udl_files_hashmap
                 .get(udl_file_name)
                 .unwrap();
-            let udl_file_id = id_maker(id_base, kind, format!("{}_ios", udl_file_name).as_str());
-            let udl_file_item = PlistKeyValueItem::create_array_type(
-                udl_file_id.as_str(),
-                Some(format!("{} in Sources", udl_file_name).as_str()),
-                vec![
-                    PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                    PlistKeyValueItem::create_value_type("fileRef", None, udl_file_ref_id, None),
-                ],
-            );
-            //udl_files_hashmap.insert(udl_file_id, udl_file_name.to_string());
-            items.push(udl_file_item);
-            udl_files_hashmap.insert(
-                format!("{}_ios", udl_file_name),
-                (udl_file_id, udl_file_name.to_string()),
-            );
-
-            let udl_file_id = id_maker(id_base, kind, format!("{}_macos", udl_file_name).as_str());
-            let udl_file_item = PlistKeyValueItem::create_array_type(
-                udl_file_id.as_str(),
-                Some(format!("{} in Sources", udl_file_name).as_str()),
-                vec![
-                    PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                    PlistKeyValueItem::create_value_type("fileRef", None, udl_file_ref_id, None),
-                ],
-            );
-            udl_files_hashmap.insert(
-                format!("{}_macos", udl_file_name),
-                (udl_file_id, udl_file_name.to_string()),
-            );
-            items.push(udl_file_item);
+            for platform in ["ios", "macos"] {
+                let key = format!("{}_{}", udl_file_name, platform);
+                let udl_file_id = ids.allocate(kind, key.as_str());
+                udl_file_items.extend(
+                    pbx! {
+                        [udl_file_id] = {
+                            isa = PBXBuildFile;
+                            fileRef = [udl_file_ref_id];
+                        };
+                    }
+                    .into_entries(),
+                );
+                udl_files_hashmap.insert(key, udl_file_id);
+            }
         }
 
+        let items = pbx! {
+            [ios_framework] = {
+                isa = PBXBuildFile;
+                fileRef = [pbx_file_ref_ids.ios_framework];
+            };
+            [ios_h] = {
+                isa = PBXBuildFile;
+                fileRef = [pbx_file_ref_ids.ios_h];
+                settings = { ATTRIBUTES = (Public); };
+            };
+            [ios_docc] = {
+                isa = PBXBuildFile;
+                fileRef = [pbx_file_ref_ids.ios_docc];
+            };
+            [ios_tests_swift] = {
+                isa = PBXBuildFile;
+                fileRef = [pbx_file_ref_ids.ios_tests_swift];
+            };
+            [macos_framework] = {
+                isa = PBXBuildFile;
+                fileRef = [pbx_file_ref_ids.macos_framework];
+            };
+            [macos_h] = {
+                isa = PBXBuildFile;
+                fileRef = [pbx_file_ref_ids.macos_h];
+                settings = { ATTRIBUTES = (Public); };
+            };
+            [macos_docc] = {
+                isa = PBXBuildFile;
+                fileRef = [pbx_file_ref_ids.macos_docc];
+            };
+            [macos_tests_swift] = {
+                isa = PBXBuildFile;
+                fileRef = [pbx_file_ref_ids.macos_tests_swift];
+            };
+            ..udl_file_items;
+        };
+
         let file_ref_ids = PBXBuildFileIds {
             ios_framework,
             ios_h,
@@ -720,456 +373,231 @@ impl PlistKeyValueItem {
             macos_tests_swift,
             udl_files_hashmap,
         };
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items,
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_headers_build_section(
         pbx_file_ref_ids: &PBXBuildFileIds,
         name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXHeadersBuildPhaseIds, PlistItem) {
         let kind = "PBXHeadersBuildPhase";
-        let headers_ios = id_maker(id_base, kind, format!("{}_ios.headers", name).as_str());
-        let headers_macos = id_maker(id_base, kind, format!("{}_macos.headers", name).as_str());
-
-        let ios_headers = PlistKeyValueItem::create_array_type(
-            headers_ios.as_str(),
-            Some("Headers"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create(
-                    "files",
-                    vec![(
-                        pbx_file_ref_ids.ios_h.as_str(),
-                        Some(format!("{}_ios.h in Headers", name)),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-        let macos_headers = PlistKeyValueItem::create_array_type(
-            headers_macos.as_str(),
-            Some("Headers"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create(
-                    "files",
-                    vec![(
-                        pbx_file_ref_ids.macos_h.as_str(),
-                        Some(format!("{}_macos.h in Headers", name)),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
+        let headers_ios = ids.allocate(kind, format!("{}_ios.headers", name).as_str());
+        let headers_macos = ids.allocate(kind, format!("{}_macos.headers", name).as_str());
+
+        let items = pbx! {
+            [headers_ios] = {
+                isa = PBXHeadersBuildPhase;
+                buildActionMask = "2147483647";
+                files = (
+                    [pbx_file_ref_ids.ios_h],
+                );
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+            [headers_macos] = {
+                isa = PBXHeadersBuildPhase;
+                buildActionMask = "2147483647";
+                files = (
+                    [pbx_file_ref_ids.macos_h],
+                );
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+        };
         let file_ref_ids = PBXHeadersBuildPhaseIds {
             headers_ios,
             headers_macos,
         };
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![ios_headers, macos_headers],
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_sources_build_section(
         pbx_file_ref_ids: &PBXBuildFileIds,
         name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXSourcesBuildPhaseIds, PlistItem) {
         let kind = "PBXSourcesBuildPhase";
-        let ios_docc = id_maker(id_base, kind, format!("{}_ios.docc", name).as_str());
-        let ios_tests_swift = id_maker(id_base, kind, format!("{}_iosTests.swift", name).as_str());
-        let macos_docc = id_maker(id_base, kind, format!("{}_macos.docc", name).as_str());
+        let ios_docc = ids.allocate(kind, format!("{}_ios.docc", name).as_str());
+        let ios_tests_swift = ids.allocate(kind, format!("{}_iosTests.swift", name).as_str());
+        let macos_docc = ids.allocate(kind, format!("{}_macos.docc", name).as_str());
         let macos_tests_swift =
-            id_maker(id_base, kind, format!("{}_macosTests.swift", name).as_str());
+            ids.allocate(kind, format!("{}_macosTests.swift", name).as_str());
 
-        let mut macos_udls_ios = vec![];
-        pbx_file_ref_ids
-            .udl_files_hashmap
-            .iter()
-            .for_each(|(key, (id, name))| {
-                if key.ends_with("ios") {
-                    macos_udls_ios.push((id.as_str(), Some(format!("{} in Sources", name))));
-                }
-            });
-        macos_udls_ios.push((
-            pbx_file_ref_ids.ios_docc.as_str(),
-            Some(format!("{}_ios.docc in Sources", name)),
-        ));
-        let ios_docc_item = PlistKeyValueItem::create_array_type(
-            ios_docc.as_str(),
-            Some("Sources"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create("files", macos_udls_ios),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-        let ios_swift_item = PlistKeyValueItem::create_array_type(
-            ios_tests_swift.as_str(),
-            Some("Sources"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create(
-                    "files",
-                    vec![(
-                        pbx_file_ref_ids.ios_tests_swift.as_str(),
-                        Some(format!("{}_iosTests.swift in Sources", name)),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
+        let udl_sources = |platform: &str| -> Vec<PlistItem> {
+            pbx_file_ref_ids
+                .udl_files_hashmap
+                .iter()
+                .filter(|(key, _)| key.ends_with(platform))
+                .map(|(_, id)| PlistTupleValue::create(id, None))
+                .collect()
+        };
 
-        // --------- MacOS
-        let mut macos_udls_macos = vec![];
-        pbx_file_ref_ids
-            .udl_files_hashmap
-            .iter()
-            .for_each(|(key, (id, name))| {
-                if key.ends_with("macos") {
-                    macos_udls_macos.push((id.as_str(), Some(format!("{} in Sources", name))));
-                }
-            });
-        macos_udls_macos.push((
-            pbx_file_ref_ids.macos_docc.as_str(),
-            Some(format!("{}_macos.docc in Sources", name)),
-        ));
-        let macos_docc_item = PlistKeyValueItem::create_array_type(
-            macos_docc.as_str(),
-            Some("Sources"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create("files", macos_udls_macos),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-        let macos_swift_item = PlistKeyValueItem::create_array_type(
-            macos_tests_swift.as_str(),
-            Some("Sources"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create(
-                    "files",
-                    vec![(
-                        pbx_file_ref_ids.macos_tests_swift.as_str(),
-                        Some(format!("{}_macosTests.swift in Sources", name)),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
+        let items = pbx! {
+            [ios_docc] = {
+                isa = PBXSourcesBuildPhase;
+                buildActionMask = "2147483647";
+                files = (
+                    ..udl_sources("ios"),
+                    [pbx_file_ref_ids.ios_docc],
+                );
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+            [ios_tests_swift] = {
+                isa = PBXSourcesBuildPhase;
+                buildActionMask = "2147483647";
+                files = (
+                    [pbx_file_ref_ids.ios_tests_swift],
+                );
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+            [macos_docc] = {
+                isa = PBXSourcesBuildPhase;
+                buildActionMask = "2147483647";
+                files = (
+                    ..udl_sources("macos"),
+                    [pbx_file_ref_ids.macos_docc],
+                );
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+            [macos_tests_swift] = {
+                isa = PBXSourcesBuildPhase;
+                buildActionMask = "2147483647";
+                files = (
+                    [pbx_file_ref_ids.macos_tests_swift],
+                );
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+        };
         let file_ref_ids = PBXSourcesBuildPhaseIds {
             ios_docc,
             ios_tests_swift,
             macos_docc,
             macos_tests_swift,
         };
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![
-                    ios_docc_item,
-                    ios_swift_item,
-                    macos_docc_item,
-                    macos_swift_item,
-                ],
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_frameworks_build_section(
         pbx_file_ref_ids: &PBXBuildFileIds,
         name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXFrameworksBuildPhaseIds, PlistItem) {
         let kind = "PBXFrameworksBuildPhase";
-        let ios_framework = id_maker(id_base, kind, format!("{}_ios.framework", name).as_str());
+        let ios_framework = ids.allocate(kind, format!("{}_ios.framework", name).as_str());
         let ios_tests_xctest =
-            id_maker(id_base, kind, format!("{}_iosTests.xctest", name).as_str());
-        let macos_framework = id_maker(id_base, kind, format!("{}_macos.framework", name).as_str());
-        let macos_tests_xctest = id_maker(
-            id_base,
-            kind,
+            ids.allocate(kind, format!("{}_iosTests.xctest", name).as_str());
+        let macos_framework = ids.allocate(kind, format!("{}_macos.framework", name).as_str());
+        let macos_tests_xctest = ids.allocate(kind,
             format!("{}_macosTests.xctest", name).as_str(),
         );
 
-        let ios_framework_item = PlistKeyValueItem::create_array_type(
-            ios_framework.as_str(),
-            Some("Frameworks"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create(
-                    "files",
-                    vec![],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-        let ios_tests_xctest_item = PlistKeyValueItem::create_array_type(
-            ios_tests_xctest.as_str(),
-            Some("Frameworks"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create("files", vec![(
-                    pbx_file_ref_ids.ios_framework.as_str(),
-                    Some(format!("{}_ios.framework in Sources", name)),
-                )]),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-
-        // --------- MacOS
-        let macos_framework_item = PlistKeyValueItem::create_array_type(
-            macos_framework.as_str(),
-            Some("Frameworks"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create(
-                    "files",
-                    vec![],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-        let macos_tests_xctest_item = PlistKeyValueItem::create_array_type(
-            macos_tests_xctest.as_str(),
-            Some("Frameworks"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create("files", vec![(
-                    pbx_file_ref_ids.macos_framework.as_str(),
-                    Some(format!("{}_macos.framework in Frameworks", name)),
-                )]),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
+        let items = pbx! {
+            [ios_framework] = {
+                isa = PBXFrameworksBuildPhase;
+                buildActionMask = "2147483647";
+                files = ();
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+            [ios_tests_xctest] = {
+                isa = PBXFrameworksBuildPhase;
+                buildActionMask = "2147483647";
+                files = (
+                    [pbx_file_ref_ids.ios_framework],
+                );
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+            [macos_framework] = {
+                isa = PBXFrameworksBuildPhase;
+                buildActionMask = "2147483647";
+                files = ();
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+            [macos_tests_xctest] = {
+                isa = PBXFrameworksBuildPhase;
+                buildActionMask = "2147483647";
+                files = (
+                    [pbx_file_ref_ids.macos_framework],
+                );
+                runOnlyForDeploymentPostprocessing = "0";
+            };
+        };
         let file_ref_ids = PBXFrameworksBuildPhaseIds {
             ios_framework,
             ios_tests_xctest,
             macos_framework,
             macos_tests_xctest,
         };
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![
-                    ios_framework_item,
-                    ios_tests_xctest_item,
-                    macos_framework_item,
-                    macos_tests_xctest_item,
-                ],
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_build_rule_section(
         name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXBuildRuleIds, PlistItem) {
         let kind = "PBXBuildRule";
-        let ios_build_rule_id = id_maker(id_base, kind, format!("{}_ios_uniffi", name).as_str());
+        let ios_build_rule_id = ids.allocate(kind, format!("{}_ios_uniffi", name).as_str());
         let macos_build_rule_id =
-            id_maker(id_base, kind, format!("{}_macos_uniffi", name).as_str());
-
-        let ios_build_rule_item = PlistKeyValueItem::create_array_type(ios_build_rule_id.as_str(), Some(kind),
-                                                                 vec![
-                                                                     PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                                                                     PlistKeyValueItem::create_value_type("compilerSpec", None, "com.apple.compilers.proxy.script", None),
-                                                                     PlistKeyValueItem::create_value_type("filePatterns", None, "\"*.udl\"", None),
-                                                                     PlistKeyValueItem::create_value_type("fileType", None, "pattern.proxy", None),
-                                                                     PlistTupleItem::create("inputFiles", vec![]),
-                                                                     PlistKeyValueItem::create_value_type("isEditable", None, "1", None),
-                                                                     PlistKeyValueItem::create_value_type("name", None, format!("\"{} - UniFFI Build rule for '*.udl' files - ios\"", name).as_str(), None),
-                                                                     PlistTupleItem::create("outputFiles", vec![
-                                                                         (format!("\"$(SRCROOT)/rust_libs/{}/$(INPUT_FILE_BASE).swift\"", name).as_str(), None),
-                                                                         (format!("\"$(SRCROOT)/rust_libs/{}/$(INPUT_FILE_BASE)FFI.h\"", name).as_str(), None),
-                                                                     ]),
-                                                                     PlistKeyValueItem::create_value_type("runOncePerArchitecture", None, "0", None),
-                                                                     PlistKeyValueItem::create_value_type("script", None,
-                                                                                                          format!(r#"" # Generate swift bindings for the todolist rust library.\nset -e\necho \"Generating files for $INPUT_FILE_PATH\"\nexport PATH=\"$PATH:/opt/homebrew/bin/\"\n$HOME/.cargo/bin/uniffi-bindgen generate \"$INPUT_FILE_PATH\" --language swift --out-dir \"$SRCROOT/rust_libs/{0}\"\nmv \"$SRCROOT/rust_libs/{0}/\"$INPUT_FILE_BASE\"FFI.modulemap\" \"$SRCROOT/rust_libs/{0}/module.modulemap\"\necho \"Generated files for $INPUT_FILE_BASE in $SRCROOT/rust_libs/{0}\"\n""#,
-                                                                                                                  name).as_str(), None),
-                                                                 ]);
-        let macos_build_rule_item = PlistKeyValueItem::create_array_type(macos_build_rule_id.as_str(), Some(kind),
-                                                                       vec![
-                                                                           PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                                                                           PlistKeyValueItem::create_value_type("compilerSpec", None, "com.apple.compilers.proxy.script", None),
-                                                                           PlistKeyValueItem::create_value_type("filePatterns", None, "\"*.udl\"", None),
-                                                                           PlistKeyValueItem::create_value_type("fileType", None, "pattern.proxy", None),
-                                                                           PlistTupleItem::create("inputFiles", vec![]),
-                                                                           PlistKeyValueItem::create_value_type("isEditable", None, "1", None),
-                                                                           PlistKeyValueItem::create_value_type("name", None, format!("\"{} - UniFFI Build rule for '*.udl' files - macos\"", name).as_str(), None),
-                                                                           PlistTupleItem::create("outputFiles", vec![
-                                                                               (format!("\"$(SRCROOT)/rust_libs/{}/$(INPUT_FILE_BASE).swift\"", name).as_str(), None),
-                                                                               (format!("\"$(SRCROOT)/rust_libs/{}/$(INPUT_FILE_BASE)FFI.h\"", name).as_str(), None),
-                                                                           ]),
-                                                                           PlistKeyValueItem::create_value_type("runOncePerArchitecture", None, "0", None),
-                                                                           PlistKeyValueItem::create_value_type("script", None,
-                                                                                                                format!(r#"" # Generate swift bindings for the todolist rust library.\nset -e\necho \"Generating files for $INPUT_FILE_PATH\"\nexport PATH=\"$PATH:/opt/homebrew/bin/\"\n$HOME/.cargo/bin/uniffi-bindgen generate \"$INPUT_FILE_PATH\" --language swift --out-dir \"$SRCROOT/rust_libs/{0}\"\nmv \"$SRCROOT/rust_libs/{0}/\"$INPUT_FILE_BASE\"FFI.modulemap\" \"$SRCROOT/rust_libs/{0}/module.modulemap\"\necho \"Generated files for $INPUT_FILE_BASE in $SRCROOT/rust_libs/{0}\"\n""#,
-                                                                                                                        name).as_str(), None),
-                                                                       ]);
+            ids.allocate(kind, format!("{}_macos_uniffi", name).as_str());
+
+        let build_rule = |platform: &str| {
+            pbx! {
+                isa = PBXBuildRule;
+                compilerSpec = "com.apple.compilers.proxy.script";
+                filePatterns = "*.udl";
+                fileType = "pattern.proxy";
+                inputFiles = ();
+                isEditable = "1";
+                name = [format!("{} - UniFFI Build rule for '*.udl' files - {}", name, platform)];
+                outputFiles = (
+                    [format!("$(SRCROOT)/rust_libs/{}/$(INPUT_FILE_BASE).swift", name)],
+                    [format!("$(SRCROOT)/rust_libs/{}/$(INPUT_FILE_BASE)FFI.h", name)],
+                );
+                runOncePerArchitecture = "0";
+                script = [format!(" # Generate swift bindings for the todolist rust library.\nset -e\necho \"Generating files for $INPUT_FILE_PATH\"\nexport PATH=\"$PATH:/opt/homebrew/bin/\"\n$HOME/.cargo/bin/uniffi-bindgen generate \"$INPUT_FILE_PATH\" --language swift --out-dir \"$SRCROOT/rust_libs/{0}\"\nmv \"$SRCROOT/rust_libs/{0}/\"$INPUT_FILE_BASE\"FFI.modulemap\" \"$SRCROOT/rust_libs/{0}/module.modulemap\"\necho \"Generated files for $INPUT_FILE_BASE in $SRCROOT/rust_libs/{0}\"\n", name)];
+            }
+        };
+
+        let items = pbx! {
+            [ios_build_rule_id] = { ..build_rule("ios").into_entries(); };
+            [macos_build_rule_id] = { ..build_rule("macos").into_entries(); };
+        };
         let file_ref_ids = PBXBuildRuleIds {
             ios: ios_build_rule_id,
             macos: macos_build_rule_id,
         };
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![ios_build_rule_item, macos_build_rule_item],
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_resource_build_phase_section(
         name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXResourcesBuildPhaseIds, PlistItem) {
         let kind = "PBXResourcesBuildPhase";
-        let ios_resource_build_id = id_maker(id_base, kind, format!("{}_ios", name).as_str());
+        let ios_resource_build_id = ids.allocate(kind, format!("{}_ios", name).as_str());
         let ios_resource_build_tests_id =
-            id_maker(id_base, kind, format!("{}_iosTests", name).as_str());
-        let macos_resource_build_id = id_maker(id_base, kind, format!("{}_macos", name).as_str());
+            ids.allocate(kind, format!("{}_iosTests", name).as_str());
+        let macos_resource_build_id = ids.allocate(kind, format!("{}_macos", name).as_str());
         let macos_resource_build_tests_id =
-            id_maker(id_base, kind, format!("{}_macosTests", name).as_str());
-
-        let ios_resource_build_item = PlistKeyValueItem::create_array_type(
-            ios_resource_build_id.as_str(),
-            Some("Resources"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create("files", vec![]),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-        let ios_resource_build_tests_item = PlistKeyValueItem::create_array_type(
-            ios_resource_build_tests_id.as_str(),
-            Some("Resources"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create("files", vec![]),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-        let macos_resource_build_item = PlistKeyValueItem::create_array_type(
-            macos_resource_build_id.as_str(),
-            Some("Resources"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create("files", vec![]),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
-        let macos_resource_build_tests_item = PlistKeyValueItem::create_array_type(
-            macos_resource_build_tests_id.as_str(),
-            Some("Resources"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                PlistTupleItem::create("files", vec![]),
-                PlistKeyValueItem::create_value_type(
-                    "runOnlyForDeploymentPostprocessing",
-                    None,
-                    "0",
-                    None,
-                ),
-            ],
-        );
+            ids.allocate(kind, format!("{}_macosTests", name).as_str());
+
+        let mut items = vec![];
+        for id in [
+            &ios_resource_build_id,
+            &ios_resource_build_tests_id,
+            &macos_resource_build_id,
+            &macos_resource_build_tests_id,
+        ] {
+            items.extend(
+                pbx! {
+                    [id] = {
+                        isa = PBXResourcesBuildPhase;
+                        buildActionMask = "2147483647";
+                        files = ();
+                        runOnlyForDeploymentPostprocessing = "0";
+                    };
+                }
+                .into_entries(),
+            );
+        }
 
         let file_ref_ids = PBXResourcesBuildPhaseIds {
             ios: ios_resource_build_id,
@@ -1177,208 +605,96 @@ impl PlistKeyValueItem {
             macos: macos_resource_build_id,
             macos_tests: macos_resource_build_tests_id,
         };
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![
-                    ios_resource_build_item,
-                    ios_resource_build_tests_item,
-                    macos_resource_build_item,
-                    macos_resource_build_tests_item,
-                ],
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, PlistItem::Array(items)))
     }
 
     pub(crate) fn create_pbx_group_section(
         pbx_file_ref: &PBXFileReferenceIds,
         xfw_name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXGroupIds, PlistItem) {
         let kind = "PBXGroup";
-        let products_id = id_maker(id_base, kind, format!("{}_products", xfw_name).as_str());
-        let ios_id = id_maker(
-            id_base,
-            kind,
+        let products_id = ids.allocate(kind, format!("{}_products", xfw_name).as_str());
+        let ios_id = ids.allocate(kind,
             format!("{}_ios.framework", xfw_name).as_str(),
         );
-        let ios_tests_id = id_maker(id_base, kind, format!("{}_iosTests", xfw_name).as_str());
-        let macos_id = id_maker(id_base, kind, format!("{}_macos", xfw_name).as_str());
-        let macos_tests_id = id_maker(id_base, kind, format!("{}_macosTests", xfw_name).as_str());
-        let frameworks_id = id_maker(id_base, kind, format!("{}_Frameworks", xfw_name).as_str());
-
-        let products_item = PlistKeyValueItem::create_array_type(
-            products_id.as_str(),
-            Some("Products"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "children",
-                    vec![
-                        (
-                            pbx_file_ref.ios_framework.as_str(),
-                            Some(format!("{}_ios.framework", xfw_name)),
-                        ),
-                        (
-                            pbx_file_ref.ios_tests_xc.as_str(),
-                            Some(format!("{}_iosTests.xctest", xfw_name)),
-                        ),
-                        (
-                            pbx_file_ref.macos_framework.as_str(),
-                            Some(format!("{}_macos.framework", xfw_name)),
-                        ),
-                        (
-                            pbx_file_ref.macos_tests_xc.as_str(),
-                            Some(format!("{}_macosTests.xctest", xfw_name)),
-                        ),
-                    ],
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Products", None),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
+        let ios_tests_id = ids.allocate(kind, format!("{}_iosTests", xfw_name).as_str());
+        let macos_id = ids.allocate(kind, format!("{}_macos", xfw_name).as_str());
+        let macos_tests_id = ids.allocate(kind, format!("{}_macosTests", xfw_name).as_str());
+        let frameworks_id = ids.allocate(kind, format!("{}_Frameworks", xfw_name).as_str());
+        let groups_id = ids.allocate(kind, format!("{}_Groups", xfw_name).as_str());
 
-        let ios_item = PlistKeyValueItem::create_array_type(
-            ios_id.as_str(),
-            Some(format!("{}_ios", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "children",
-                    vec![
-                        (
-                            pbx_file_ref.ios_h.as_str(),
-                            Some(format!("{}_ios.h", xfw_name)),
-                        ),
-                        (
-                            pbx_file_ref.ios_docc.as_str(),
-                            Some(format!("{}_ios.docc", xfw_name)),
-                        ),
-                    ],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("\"{}_ios\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let ios_tests_item = PlistKeyValueItem::create_array_type(
-            ios_tests_id.as_str(),
-            Some(format!("{}_iosTests", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "children",
-                    vec![(
-                        pbx_file_ref.ios_tests_swift.as_str(),
-                        Some(format!("{}_iosTests.swift", xfw_name)),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("\"{}_iosTests\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let macos_item = PlistKeyValueItem::create_array_type(
-            macos_id.as_str(),
-            Some(format!("{}_macos", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "children",
-                    vec![
-                        (
-                            pbx_file_ref.macos_h.as_str(),
-                            Some(format!("{}_macos.h", xfw_name)),
-                        ),
-                        (
-                            pbx_file_ref.macos_docc.as_str(),
-                            Some(format!("{}_macos.docc", xfw_name)),
-                        ),
-                    ],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("\"{}_macos\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-        let macos_tests_item = PlistKeyValueItem::create_array_type(
-            macos_tests_id.as_str(),
-            Some(format!("{}_macosTests", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "children",
-                    vec![(
-                        pbx_file_ref.macos_tests_swift.as_str(),
-                        Some(format!("{}_macosTests.swift", xfw_name)),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "path",
-                    None,
-                    format!("\"{}_macosTests\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let frameworks_item = PlistKeyValueItem::create_array_type(
-            frameworks_id.as_str(),
-            Some("Frameworks"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create("children", vec![]),
-                PlistKeyValueItem::create_value_type("name", None, "Frameworks", None),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
-
-        let groups_id = id_maker(id_base, kind, format!("{}_Groups", xfw_name).as_str());
-        let mut items = vec![
-            (ios_id.as_str(), Some(format!("{}_ios", xfw_name))),
-            (
-                ios_tests_id.as_str(),
-                Some(format!("{}_iosTests", xfw_name)),
-            ),
-            (macos_id.as_str(), Some(format!("{}_macos", xfw_name))),
-            (
-                macos_tests_id.as_str(),
-                Some(format!("{}_macosTests", xfw_name)),
-            ),
-            (products_id.as_str(), Some("Products".to_string())),
-            (frameworks_id.as_str(), Some("Frameworks".to_string())),
-        ];
-        pbx_file_ref
+        let udl_files = pbx_file_ref
             .udl_files_hashmap
-            .iter()
-            .for_each(|(key, val)| items.push((val.as_str(), Some(key.to_string()))));
-
-        let groups_item = PlistKeyValueItem::create_array_type(
-            groups_id.as_str(),
-            None,
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create("children", items),
-                PlistKeyValueItem::create_value_type("sourceTree", None, "\"<group>\"", None),
-            ],
-        );
+            .values()
+            .map(|id| PlistTupleValue::create(id, None));
+
+        let items = pbx! {
+            [groups_id] = {
+                isa = PBXGroup;
+                children = (
+                    [ios_id],
+                    [ios_tests_id],
+                    [macos_id],
+                    [macos_tests_id],
+                    [products_id],
+                    [frameworks_id],
+                    ..udl_files,
+                );
+                sourceTree = "<group>";
+            };
+            [products_id] = {
+                isa = PBXGroup;
+                children = (
+                    [pbx_file_ref.ios_framework],
+                    [pbx_file_ref.ios_tests_xc],
+                    [pbx_file_ref.macos_framework],
+                    [pbx_file_ref.macos_tests_xc],
+                );
+                name = Products;
+                sourceTree = "<group>";
+            };
+            [ios_id] = {
+                isa = PBXGroup;
+                children = (
+                    [pbx_file_ref.ios_h],
+                    [pbx_file_ref.ios_docc],
+                );
+                path = [format!("{}_ios", xfw_name)];
+                sourceTree = "<group>";
+            };
+            [ios_tests_id] = {
+                isa = PBXGroup;
+                children = (
+                    [pbx_file_ref.ios_tests_swift],
+                );
+                path = [format!("{}_iosTests", xfw_name)];
+                sourceTree = "<group>";
+            };
+            [macos_id] = {
+                isa = PBXGroup;
+                children = (
+                    [pbx_file_ref.macos_h],
+                    [pbx_file_ref.macos_docc],
+                );
+                path = [format!("{}_macos", xfw_name)];
+                sourceTree = "<group>";
+            };
+            [macos_tests_id] = {
+                isa = PBXGroup;
+                children = (
+                    [pbx_file_ref.macos_tests_swift],
+                );
+                path = [format!("{}_macosTests", xfw_name)];
+                sourceTree = "<group>";
+            };
+            [frameworks_id] = {
+                isa = PBXGroup;
+                children = ();
+                name = Frameworks;
+                sourceTree = "<group>";
+            };
+        };
 
         let file_ref_ids = PBXGroupIds {
             groups: groups_id,
@@ -1389,21 +705,7 @@ impl PlistKeyValueItem {
             macos_tests: macos_tests_id,
             frameworks: frameworks_id,
         };
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![
-                    groups_item,
-                    products_item,
-                    ios_item,
-                    ios_tests_item,
-                    macos_item,
-                    macos_tests_item,
-                    frameworks_item,
-                ],
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_shell_script_build_phase_section(
@@ -1411,55 +713,42 @@ impl PlistKeyValueItem {
         cargo_package_name: &str,
         target_name: &str,
         xfw_name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXShellScriptBuildPhaseIds, PlistItem) {
         let kind = "PBXShellScriptBuildPhase";
-        let ios_id = id_maker(id_base, kind, format!("{}_ios", xfw_name).as_str());
-        let macos_id = id_maker(id_base, kind, format!("{}_macos", xfw_name).as_str());
-
-        let ios_item = PlistKeyValueItem::create_array_type(ios_id.as_str(), Some("ShellScript"),
-                                                                 vec![
-                                                                     PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                                                                     PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                                                                     PlistTupleItem::create("files", vec![]),
-                                                                     PlistTupleItem::create("inputFileListPaths", vec![]),
-                                                                     PlistTupleItem::create("inputPaths", vec![]),
-                                                                     PlistTupleItem::create("outputFileListPaths", vec![]),
-                                                                     PlistTupleItem::create("outputPaths", vec![]),
-                                                                     PlistKeyValueItem::create_value_type("runOnlyForDeploymentPostprocessing", None, "0", None),
-                                                                     PlistKeyValueItem::create_value_type("shellPath", None, "/bin/sh", None),
-                                                                     PlistKeyValueItem::create_value_type("shellScript", None,
-                                                                                                          format!(r#"" bash $SRCROOT/{0}/rust-xc-universal-binary.sh lib{1}.a {2} \"$SRCROOT/{0}\" ""#,
-                                                                                                                  cargo_relative_path_to_xcode_project, target_name, cargo_package_name).as_str(), None),
-                                                                 ]);
-        let macos_item = PlistKeyValueItem::create_array_type(macos_id.as_str(), Some("ShellScript"),
-                                                            vec![
-                                                                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                                                                PlistKeyValueItem::create_value_type("buildActionMask", None, "2147483647", None),
-                                                                PlistTupleItem::create("files", vec![]),
-                                                                PlistTupleItem::create("inputFileListPaths", vec![]),
-                                                                PlistTupleItem::create("inputPaths", vec![]),
-                                                                PlistTupleItem::create("outputFileListPaths", vec![]),
-                                                                PlistTupleItem::create("outputPaths", vec![]),
-                                                                PlistKeyValueItem::create_value_type("runOnlyForDeploymentPostprocessing", None, "0", None),
-                                                                PlistKeyValueItem::create_value_type("shellPath", None, "/bin/sh", None),
-                                                                PlistKeyValueItem::create_value_type("shellScript", None,
-                                                                                                     format!(r#"" bash $SRCROOT/{0}/rust-xc-universal-binary.sh lib{1}.a {2} \"$SRCROOT/{0}\" ""#,
-                                                                                                             cargo_relative_path_to_xcode_project, target_name, cargo_package_name).as_str(), None),
-                                                            ]);
+        let ios_id = ids.allocate(kind, format!("{}_ios", xfw_name).as_str());
+        let macos_id = ids.allocate(kind, format!("{}_macos", xfw_name).as_str());
+
+        let shell_script = format!(
+            " bash $SRCROOT/{0}/rust-xc-universal-binary.sh lib{1}.a {2} \"$SRCROOT/{0}\" ",
+            cargo_relative_path_to_xcode_project, target_name, cargo_package_name
+        );
+        let mut items = vec![];
+        for id in [&ios_id, &macos_id] {
+            items.extend(
+                pbx! {
+                    [id] = {
+                        isa = PBXShellScriptBuildPhase;
+                        buildActionMask = "2147483647";
+                        files = ();
+                        inputFileListPaths = ();
+                        inputPaths = ();
+                        outputFileListPaths = ();
+                        outputPaths = ();
+                        runOnlyForDeploymentPostprocessing = "0";
+                        shellPath = "/bin/sh";
+                        shellScript = [shell_script];
+                    };
+                }
+                .into_entries(),
+            );
+        }
 
         let file_ref_ids = PBXShellScriptBuildPhaseIds {
             ios: ios_id,
             macos: macos_id,
         };
-        (
-            file_ref_ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![ios_item, macos_item],
-            }),
-        )
+        (file_ref_ids, PlistSectionItem::create(kind, PlistItem::Array(items)))
     }
 
     pub(crate) fn create_pbx_xc_build_configuration_section(
@@ -1467,464 +756,318 @@ impl PlistKeyValueItem {
         cargo_relative_path_to_xcode_project: &str,
         cargo_lib_name: &str,
         xfw_name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (XCBuildConfigurationIds, PlistItem) {
         let kind = "XCBuildConfiguration";
 
-        let mut shared_debug_build_settings = PlistKeyValueItem::create_value_type_by_array(vec![
-            ("ALWAYS_SEARCH_USER_PATHS", "NO"),
-            ("CLANG_ANALYZER_NONNULL", "YES"),
-            ("CLANG_ANALYZER_NUMBER_OBJECT_CONVERSION", "YES_AGGRESSIVE"),
-            ("CLANG_CXX_LANGUAGE_STANDARD", "\"gnu++17\""),
-            ("CLANG_CXX_LIBRARY", "\"libc++\""),
-            ("CLANG_ENABLE_MODULES", "YES"),
-            ("CLANG_ENABLE_OBJC_ARC", "YES"),
-            ("CLANG_ENABLE_OBJC_WEAK", "YES"),
-            ("CLANG_WARN_BLOCK_CAPTURE_AUTORELEASING", "YES"),
-            ("CLANG_WARN_BOOL_CONVERSION", "YES"),
-            ("CLANG_WARN_COMMA", "YES"),
-            ("CLANG_WARN_CONSTANT_CONVERSION", "YES"),
-            ("CLANG_WARN_DEPRECATED_OBJC_IMPLEMENTATIONS", "YES"),
-            ("CLANG_WARN_DIRECT_OBJC_ISA_USAGE", "YES_ERROR"),
-            ("CLANG_WARN_DOCUMENTATION_COMMENTS", "YES"),
-            ("CLANG_WARN_EMPTY_BODY", "YES"),
-            ("CLANG_WARN_ENUM_CONVERSION", "YES"),
-            ("CLANG_WARN_INFINITE_RECURSION", "YES"),
-            ("CLANG_WARN_INT_CONVERSION", "YES"),
-            ("CLANG_WARN_NON_LITERAL_NULL_CONVERSION", "YES"),
-            ("CLANG_WARN_OBJC_IMPLICIT_RETAIN_SELF", "YES"),
-            ("CLANG_WARN_OBJC_LITERAL_CONVERSION", "YES"),
-            ("CLANG_WARN_OBJC_ROOT_CLASS", "YES_ERROR"),
-            ("CLANG_WARN_QUOTED_INCLUDE_IN_FRAMEWORK_HEADER", "YES"),
-            ("CLANG_WARN_RANGE_LOOP_ANALYSIS", "YES"),
-            ("CLANG_WARN_STRICT_PROTOTYPES", "YES"),
-            ("CLANG_WARN_SUSPICIOUS_MOVE", "YES"),
-            ("CLANG_WARN_UNGUARDED_AVAILABILITY", "YES_AGGRESSIVE"),
-            ("CLANG_WARN_UNREACHABLE_CODE", "YES"),
-            ("CLANG_WARN__DUPLICATE_METHOD_MATCH", "YES"),
-            ("COPY_PHASE_STRIP", "NO"),
-            ("CURRENT_PROJECT_VERSION", "1"),
-            ("DEBUG_INFORMATION_FORMAT", "dwarf"),
-            ("ENABLE_BITCODE", "NO"),
-            ("ENABLE_STRICT_OBJC_MSGSEND", "YES"),
-            ("ENABLE_TESTABILITY", "YES"),
-            ("GCC_C_LANGUAGE_STANDARD", "gnu11"),
-            ("GCC_DYNAMIC_NO_PIC", "NO"),
-            ("GCC_NO_COMMON_BLOCKS", "YES"),
-            ("GCC_OPTIMIZATION_LEVEL", "0"),
-            ("GCC_WARN_64_TO_32_BIT_CONVERSION", "YES"),
-            ("GCC_WARN_ABOUT_RETURN_TYPE", "YES_ERROR"),
-            ("GCC_WARN_UNDECLARED_SELECTOR", "YES"),
-            ("GCC_WARN_UNINITIALIZED_AUTOS", "YES_AGGRESSIVE"),
-            ("GCC_WARN_UNUSED_FUNCTION", "YES"),
-            ("GCC_WARN_UNUSED_VARIABLE", "YES"),
-            ("OTHER_LDFLAGS", format!("\"-l{}\"", cargo_lib_name).as_str()),
-            (
-                "HEADER_SEARCH_PATHS",
-                format!("\"$(SRCROOT)/rust_libs/{}\"", xfw_name).as_str(),
-            ),
-            ("IPHONEOS_DEPLOYMENT_TARGET", "15.2"),
-            (
-                "\"LIBRARY_SEARCH_PATHS[sdk=iphoneos*]\"",
-                format!(
-                    "\"$(SRCROOT)/{}/target/universal/debug/ios\"",
-                    cargo_relative_path_to_xcode_project
-                )
-                .as_str(),
-            ),
-            (
-                "\"LIBRARY_SEARCH_PATHS[sdk=iphonesimulator*]\"",
-                format!(
-                    "\"$(SRCROOT)/{}/target/universal/debug/ios_sim\"",
-                    cargo_relative_path_to_xcode_project
-                )
-                .as_str(),
-            ),
-            (
-                "\"LIBRARY_SEARCH_PATHS[sdk=macosx*]\"",
-                format!(
-                    "\"$(SRCROOT)/{}/target/universal/debug/osx\"",
-                    cargo_relative_path_to_xcode_project
-                )
-                .as_str(),
-            ),
-            ("MTL_ENABLE_DEBUG_INFO", "INCLUDE_SOURCE"),
-            ("MTL_FAST_MATH", "YES"),
-            ("ONLY_ACTIVE_ARCH", "YES"),
-            ("SDKROOT", "iphoneos"),
-            ("SWIFT_ACTIVE_COMPILATION_CONDITIONS", "DEBUG"),
-            (
-                "SWIFT_INCLUDE_PATHS",
-                format!("\"$(SRCROOT)/rust_libs/{}\"", xfw_name).as_str(),
-            ),
-            ("SWIFT_OPTIMIZATION_LEVEL", "\"-Onone\""),
-            ("VERSIONING_SYSTEM", "\"apple-generic\""),
-            ("VERSION_INFO_PREFIX", "\"\""),
-        ]);
-        shared_debug_build_settings.push(PlistTupleItem::create(
-            "GCC_PREPROCESSOR_DEFINITIONS",
-            vec![("\"DEBUG=1\"", None), ("\"$(inherited)\"", None)],
-        ));
+        let shared_debug_build_settings = pbx! {
+            ALWAYS_SEARCH_USER_PATHS = NO;
+            CLANG_ANALYZER_NONNULL = YES;
+            CLANG_ANALYZER_NUMBER_OBJECT_CONVERSION = YES_AGGRESSIVE;
+            CLANG_CXX_LANGUAGE_STANDARD = "gnu++17";
+            CLANG_CXX_LIBRARY = "libc++";
+            CLANG_ENABLE_MODULES = YES;
+            CLANG_ENABLE_OBJC_ARC = YES;
+            CLANG_ENABLE_OBJC_WEAK = YES;
+            CLANG_WARN_BLOCK_CAPTURE_AUTORELEASING = YES;
+            CLANG_WARN_BOOL_CONVERSION = YES;
+            CLANG_WARN_COMMA = YES;
+            CLANG_WARN_CONSTANT_CONVERSION = YES;
+            CLANG_WARN_DEPRECATED_OBJC_IMPLEMENTATIONS = YES;
+            CLANG_WARN_DIRECT_OBJC_ISA_USAGE = YES_ERROR;
+            CLANG_WARN_DOCUMENTATION_COMMENTS = YES;
+            CLANG_WARN_EMPTY_BODY = YES;
+            CLANG_WARN_ENUM_CONVERSION = YES;
+            CLANG_WARN_INFINITE_RECURSION = YES;
+            CLANG_WARN_INT_CONVERSION = YES;
+            CLANG_WARN_NON_LITERAL_NULL_CONVERSION = YES;
+            CLANG_WARN_OBJC_IMPLICIT_RETAIN_SELF = YES;
+            CLANG_WARN_OBJC_LITERAL_CONVERSION = YES;
+            CLANG_WARN_OBJC_ROOT_CLASS = YES_ERROR;
+            CLANG_WARN_QUOTED_INCLUDE_IN_FRAMEWORK_HEADER = YES;
+            CLANG_WARN_RANGE_LOOP_ANALYSIS = YES;
+            CLANG_WARN_STRICT_PROTOTYPES = YES;
+            CLANG_WARN_SUSPICIOUS_MOVE = YES;
+            CLANG_WARN_UNGUARDED_AVAILABILITY = YES_AGGRESSIVE;
+            CLANG_WARN_UNREACHABLE_CODE = YES;
+            CLANG_WARN__DUPLICATE_METHOD_MATCH = YES;
+            COPY_PHASE_STRIP = NO;
+            CURRENT_PROJECT_VERSION = "1";
+            DEBUG_INFORMATION_FORMAT = dwarf;
+            ENABLE_BITCODE = NO;
+            ENABLE_STRICT_OBJC_MSGSEND = YES;
+            ENABLE_TESTABILITY = YES;
+            GCC_C_LANGUAGE_STANDARD = gnu11;
+            GCC_DYNAMIC_NO_PIC = NO;
+            GCC_NO_COMMON_BLOCKS = YES;
+            GCC_OPTIMIZATION_LEVEL = "0";
+            GCC_WARN_64_TO_32_BIT_CONVERSION = YES;
+            GCC_WARN_ABOUT_RETURN_TYPE = YES_ERROR;
+            GCC_WARN_UNDECLARED_SELECTOR = YES;
+            GCC_WARN_UNINITIALIZED_AUTOS = YES_AGGRESSIVE;
+            GCC_WARN_UNUSED_FUNCTION = YES;
+            GCC_WARN_UNUSED_VARIABLE = YES;
+            OTHER_LDFLAGS = [format!("-l{}", cargo_lib_name)];
+            HEADER_SEARCH_PATHS = [format!("$(SRCROOT)/rust_libs/{}", xfw_name)];
+            IPHONEOS_DEPLOYMENT_TARGET = "15.2";
+            "LIBRARY_SEARCH_PATHS[sdk=iphoneos*]" = [format!(
+                "$(SRCROOT)/{}/target/universal/debug/ios",
+                cargo_relative_path_to_xcode_project
+            )];
+            "LIBRARY_SEARCH_PATHS[sdk=iphonesimulator*]" = [format!(
+                "$(SRCROOT)/{}/target/universal/debug/ios_sim",
+                cargo_relative_path_to_xcode_project
+            )];
+            "LIBRARY_SEARCH_PATHS[sdk=macosx*]" = [format!(
+                "$(SRCROOT)/{}/target/universal/debug/osx",
+                cargo_relative_path_to_xcode_project
+            )];
+            MTL_ENABLE_DEBUG_INFO = INCLUDE_SOURCE;
+            MTL_FAST_MATH = YES;
+            ONLY_ACTIVE_ARCH = YES;
+            SDKROOT = iphoneos;
+            SWIFT_ACTIVE_COMPILATION_CONDITIONS = DEBUG;
+            SWIFT_INCLUDE_PATHS = [format!("$(SRCROOT)/rust_libs/{}", xfw_name)];
+            SWIFT_OPTIMIZATION_LEVEL = "-Onone";
+            VERSIONING_SYSTEM = "apple-generic";
+            VERSION_INFO_PREFIX = "";
+            GCC_PREPROCESSOR_DEFINITIONS = ("DEBUG=1", "$(inherited)");
+        };
         let shared_debug_id =
-            id_maker(id_base, kind, format!("{}-shared-Debug", xfw_name).as_str());
-        let shared_debug_item = PlistKeyValueItem::create_array_type(
-            shared_debug_id.as_str(),
-            Some("Debug"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type(
-                    "buildSettings",
-                    None,
-                    shared_debug_build_settings,
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Debug", None),
-            ],
-        );
-
-        let shared_release_build_settings = PlistKeyValueItem::create_value_type_by_array(vec![
-            ("ALWAYS_SEARCH_USER_PATHS", "NO"),
-            ("CLANG_ANALYZER_NONNULL", "YES"),
-            ("CLANG_ANALYZER_NUMBER_OBJECT_CONVERSION", "YES_AGGRESSIVE"),
-            ("CLANG_CXX_LANGUAGE_STANDARD", "\"gnu++17\""),
-            ("CLANG_CXX_LIBRARY", "\"libc++\""),
-            ("CLANG_ENABLE_MODULES", "YES"),
-            ("CLANG_ENABLE_OBJC_ARC", "YES"),
-            ("CLANG_ENABLE_OBJC_WEAK", "YES"),
-            ("CLANG_WARN_BLOCK_CAPTURE_AUTORELEASING", "YES"),
-            ("CLANG_WARN_BOOL_CONVERSION", "YES"),
-            ("CLANG_WARN_COMMA", "YES"),
-            ("CLANG_WARN_CONSTANT_CONVERSION", "YES"),
-            ("CLANG_WARN_DEPRECATED_OBJC_IMPLEMENTATIONS", "YES"),
-            ("CLANG_WARN_DIRECT_OBJC_ISA_USAGE", "YES_ERROR"),
-            ("CLANG_WARN_DOCUMENTATION_COMMENTS", "YES"),
-            ("CLANG_WARN_EMPTY_BODY", "YES"),
-            ("CLANG_WARN_ENUM_CONVERSION", "YES"),
-            ("CLANG_WARN_INFINITE_RECURSION", "YES"),
-            ("CLANG_WARN_INT_CONVERSION", "YES"),
-            ("CLANG_WARN_NON_LITERAL_NULL_CONVERSION", "YES"),
-            ("CLANG_WARN_OBJC_IMPLICIT_RETAIN_SELF", "YES"),
-            ("CLANG_WARN_OBJC_LITERAL_CONVERSION", "YES"),
-            ("CLANG_WARN_OBJC_ROOT_CLASS", "YES_ERROR"),
-            ("CLANG_WARN_QUOTED_INCLUDE_IN_FRAMEWORK_HEADER", "YES"),
-            ("CLANG_WARN_RANGE_LOOP_ANALYSIS", "YES"),
-            ("CLANG_WARN_STRICT_PROTOTYPES", "YES"),
-            ("CLANG_WARN_SUSPICIOUS_MOVE", "YES"),
-            ("CLANG_WARN_UNGUARDED_AVAILABILITY", "YES_AGGRESSIVE"),
-            ("CLANG_WARN_UNREACHABLE_CODE", "YES"),
-            ("CLANG_WARN__DUPLICATE_METHOD_MATCH", "YES"),
-            ("COPY_PHASE_STRIP", "NO"),
-            ("CURRENT_PROJECT_VERSION", "1"),
-            ("DEBUG_INFORMATION_FORMAT", "\"dwarf-with-dsym\""),
-            ("ENABLE_BITCODE", "NO"),
-            ("ENABLE_NS_ASSERTIONS", "NO"),
-            ("ENABLE_STRICT_OBJC_MSGSEND", "YES"),
-            ("GCC_C_LANGUAGE_STANDARD", "gnu11"),
-            ("GCC_NO_COMMON_BLOCKS", "YES"),
-            ("GCC_WARN_64_TO_32_BIT_CONVERSION", "YES"),
-            ("GCC_WARN_ABOUT_RETURN_TYPE", "YES_ERROR"),
-            ("GCC_WARN_UNDECLARED_SELECTOR", "YES"),
-            ("GCC_WARN_UNINITIALIZED_AUTOS", "YES_AGGRESSIVE"),
-            ("GCC_WARN_UNUSED_FUNCTION", "YES"),
-            ("GCC_WARN_UNUSED_VARIABLE", "YES"),
-            ("OTHER_LDFLAGS", format!("\"-l{}\"", cargo_lib_name).as_str()),
-            (
-                "HEADER_SEARCH_PATHS",
-                format!("\"$(SRCROOT)/rust_libs/{}\"", xfw_name).as_str(),
-            ),
-            ("IPHONEOS_DEPLOYMENT_TARGET", "15.2"),
-            (
-                "\"LIBRARY_SEARCH_PATHS[sdk=iphoneos*]\"",
-                format!(
-                    "\"$(SRCROOT)/{}/target/universal/release/ios\"",
-                    cargo_relative_path_to_xcode_project
-                )
-                .as_str(),
-            ),
-            (
-                "\"LIBRARY_SEARCH_PATHS[sdk=iphonesimulator*]\"",
-                format!(
-                    "\"$(SRCROOT)/{}/target/universal/release/ios_sim\"",
-                    cargo_relative_path_to_xcode_project
-                )
-                .as_str(),
-            ),
-            (
-                "\"LIBRARY_SEARCH_PATHS[sdk=macosx*]\"",
-                format!(
-                    "\"$(SRCROOT)/{}/target/universal/release/osx\"",
-                    cargo_relative_path_to_xcode_project
-                )
-                .as_str(),
-            ),
-            ("MTL_ENABLE_DEBUG_INFO", "NO"),
-            ("MTL_FAST_MATH", "YES"),
-            ("SDKROOT", "iphoneos"),
-            ("SWIFT_COMPILATION_MODE", "wholemodule"),
-            (
-                "SWIFT_INCLUDE_PATHS",
-                format!("\"$(SRCROOT)/rust_libs/{}\"", xfw_name).as_str(),
-            ),
-            ("SWIFT_OPTIMIZATION_LEVEL", "\"-O\""),
-            ("VALIDATE_PRODUCT", "YES"),
-            ("VERSIONING_SYSTEM", "\"apple-generic\""),
-            ("VERSION_INFO_PREFIX", "\"\""),
-        ]);
-
-        let shared_release_id = id_maker(
-            id_base,
-            kind,
+            ids.allocate(kind, format!("{}-shared-Debug", xfw_name).as_str());
+
+        let shared_release_build_settings = pbx! {
+            ALWAYS_SEARCH_USER_PATHS = NO;
+            CLANG_ANALYZER_NONNULL = YES;
+            CLANG_ANALYZER_NUMBER_OBJECT_CONVERSION = YES_AGGRESSIVE;
+            CLANG_CXX_LANGUAGE_STANDARD = "gnu++17";
+            CLANG_CXX_LIBRARY = "libc++";
+            CLANG_ENABLE_MODULES = YES;
+            CLANG_ENABLE_OBJC_ARC = YES;
+            CLANG_ENABLE_OBJC_WEAK = YES;
+            CLANG_WARN_BLOCK_CAPTURE_AUTORELEASING = YES;
+            CLANG_WARN_BOOL_CONVERSION = YES;
+            CLANG_WARN_COMMA = YES;
+            CLANG_WARN_CONSTANT_CONVERSION = YES;
+            CLANG_WARN_DEPRECATED_OBJC_IMPLEMENTATIONS = YES;
+            CLANG_WARN_DIRECT_OBJC_ISA_USAGE = YES_ERROR;
+            CLANG_WARN_DOCUMENTATION_COMMENTS = YES;
+            CLANG_WARN_EMPTY_BODY = YES;
+            CLANG_WARN_ENUM_CONVERSION = YES;
+            CLANG_WARN_INFINITE_RECURSION = YES;
+            CLANG_WARN_INT_CONVERSION = YES;
+            CLANG_WARN_NON_LITERAL_NULL_CONVERSION = YES;
+            CLANG_WARN_OBJC_IMPLICIT_RETAIN_SELF = YES;
+            CLANG_WARN_OBJC_LITERAL_CONVERSION = YES;
+            CLANG_WARN_OBJC_ROOT_CLASS = YES_ERROR;
+            CLANG_WARN_QUOTED_INCLUDE_IN_FRAMEWORK_HEADER = YES;
+            CLANG_WARN_RANGE_LOOP_ANALYSIS = YES;
+            CLANG_WARN_STRICT_PROTOTYPES = YES;
+            CLANG_WARN_SUSPICIOUS_MOVE = YES;
+            CLANG_WARN_UNGUARDED_AVAILABILITY = YES_AGGRESSIVE;
+            CLANG_WARN_UNREACHABLE_CODE = YES;
+            CLANG_WARN__DUPLICATE_METHOD_MATCH = YES;
+            COPY_PHASE_STRIP = NO;
+            CURRENT_PROJECT_VERSION = "1";
+            DEBUG_INFORMATION_FORMAT = "dwarf-with-dsym";
+            ENABLE_BITCODE = NO;
+            ENABLE_NS_ASSERTIONS = NO;
+            ENABLE_STRICT_OBJC_MSGSEND = YES;
+            GCC_C_LANGUAGE_STANDARD = gnu11;
+            GCC_NO_COMMON_BLOCKS = YES;
+            GCC_WARN_64_TO_32_BIT_CONVERSION = YES;
+            GCC_WARN_ABOUT_RETURN_TYPE = YES_ERROR;
+            GCC_WARN_UNDECLARED_SELECTOR = YES;
+            GCC_WARN_UNINITIALIZED_AUTOS = YES_AGGRESSIVE;
+            GCC_WARN_UNUSED_FUNCTION = YES;
+            GCC_WARN_UNUSED_VARIABLE = YES;
+            OTHER_LDFLAGS = [format!("-l{}", cargo_lib_name)];
+            HEADER_SEARCH_PATHS = [format!("$(SRCROOT)/rust_libs/{}", xfw_name)];
+            IPHONEOS_DEPLOYMENT_TARGET = "15.2";
+            "LIBRARY_SEARCH_PATHS[sdk=iphoneos*]" = [format!(
+                "$(SRCROOT)/{}/target/universal/release/ios",
+                cargo_relative_path_to_xcode_project
+            )];
+            "LIBRARY_SEARCH_PATHS[sdk=iphonesimulator*]" = [format!(
+                "$(SRCROOT)/{}/target/universal/release/ios_sim",
+                cargo_relative_path_to_xcode_project
+            )];
+            "LIBRARY_SEARCH_PATHS[sdk=macosx*]" = [format!(
+                "$(SRCROOT)/{}/target/universal/release/osx",
+                cargo_relative_path_to_xcode_project
+            )];
+            MTL_ENABLE_DEBUG_INFO = NO;
+            MTL_FAST_MATH = YES;
+            SDKROOT = iphoneos;
+            SWIFT_COMPILATION_MODE = wholemodule;
+            SWIFT_INCLUDE_PATHS = [format!("$(SRCROOT)/rust_libs/{}", xfw_name)];
+            SWIFT_OPTIMIZATION_LEVEL = "-O";
+            VALIDATE_PRODUCT = YES;
+            VERSIONING_SYSTEM = "apple-generic";
+            VERSION_INFO_PREFIX = "";
+        };
+        let shared_release_id = ids.allocate(kind,
             format!("{}-shared-Release", xfw_name).as_str(),
         );
-        let shared_release_item = PlistKeyValueItem::create_array_type(
-            shared_release_id.as_str(),
-            Some("Release"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type(
-                    "buildSettings",
-                    None,
-                    shared_release_build_settings,
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Release", None),
-            ],
-        );
 
         let get_ios_config = || {
-            let mut ios_debug_build_settings = PlistKeyValueItem::create_value_type_by_array(vec![
-                ("CODE_SIGN_STYLE", "Automatic"),
-                ("CURRENT_PROJECT_VERSION", "1"),
-                ("DEFINES_MODULE", "YES"),
-                ("DEVELOPMENT_TEAM", "\"\""),
-                ("DYLIB_COMPATIBILITY_VERSION", "1"),
-                ("DYLIB_CURRENT_VERSION", "1"),
-                ("DYLIB_INSTALL_NAME_BASE", "\"@rpath\""),
-                ("GENERATE_INFOPLIST_FILE", "YES"),
-                ("INFOPLIST_KEY_NSHumanReadableCopyright", "\"\""),
-                ("INSTALL_PATH", "\"$(LOCAL_LIBRARY_DIR)/Frameworks\""),
-                ("MARKETING_VERSION", "1.0"),
-                (
-                    "PRODUCT_BUNDLE_IDENTIFIER",
-                    format!("\"{}.{}-ios\"", base_bundle_identifier, xfw_name).as_str(),
-                ),
-                ("PRODUCT_NAME", format!("{}", xfw_name).as_str() ), //"\"$(TARGET_NAME:c99extidentifier)\""),
-                ("SKIP_INSTALL", "YES"),
-                ("SWIFT_EMIT_LOC_STRINGS", "YES"),
-                ("SWIFT_VERSION", "5.0"),
-                ("TARGETED_DEVICE_FAMILY", "\"1,2\""),
-            ]);
-            ios_debug_build_settings.push(PlistTupleItem::create(
-                "LD_RUNPATH_SEARCH_PATHS",
-                vec![
-                    ("\"$(inherited)\"", None),
-                    ("\"@executable_path/Frameworks\"", None),
-                    ("\"@loader_path/Frameworks\"", None),
-                ],
-            ));
-            ios_debug_build_settings
+            pbx! {
+                CODE_SIGN_STYLE = Automatic;
+                CURRENT_PROJECT_VERSION = "1";
+                DEFINES_MODULE = YES;
+                DEVELOPMENT_TEAM = "";
+                DYLIB_COMPATIBILITY_VERSION = "1";
+                DYLIB_CURRENT_VERSION = "1";
+                DYLIB_INSTALL_NAME_BASE = "@rpath";
+                GENERATE_INFOPLIST_FILE = YES;
+                INFOPLIST_KEY_NSHumanReadableCopyright = "";
+                INSTALL_PATH = "$(LOCAL_LIBRARY_DIR)/Frameworks";
+                MARKETING_VERSION = "1.0";
+                PRODUCT_BUNDLE_IDENTIFIER = [format!("{}.{}-ios", base_bundle_identifier, xfw_name)];
+                PRODUCT_NAME = [xfw_name]; //"$(TARGET_NAME:c99extidentifier)"
+                SKIP_INSTALL = YES;
+                SWIFT_EMIT_LOC_STRINGS = YES;
+                SWIFT_VERSION = "5.0";
+                TARGETED_DEVICE_FAMILY = "1,2";
+                LD_RUNPATH_SEARCH_PATHS = (
+                    "$(inherited)",
+                    "@executable_path/Frameworks",
+                    "@loader_path/Frameworks",
+                );
+            }
         };
+        let ios_debug_id = ids.allocate(kind, format!("{}_ios-Debug", xfw_name).as_str());
+        let ios_release_id = ids.allocate(kind, format!("{}_ios-Release", xfw_name).as_str());
 
-        let ios_debug_id = id_maker(id_base, kind, format!("{}_ios-Debug", xfw_name).as_str());
-        let ios_debug_item = PlistKeyValueItem::create_array_type(
-            ios_debug_id.as_str(),
-            Some("Debug"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type("buildSettings", None, get_ios_config()),
-                PlistKeyValueItem::create_value_type("name", None, "Debug", None),
-            ],
-        );
-
-        let ios_release_id = id_maker(id_base, kind, format!("{}_ios-Release", xfw_name).as_str());
-        let ios_release_item = PlistKeyValueItem::create_array_type(
-            ios_release_id.as_str(),
-            Some("Release"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type("buildSettings", None, get_ios_config()),
-                PlistKeyValueItem::create_value_type("name", None, "Release", None),
-            ],
-        );
         // ----- IOS Debug Tests
         let get_ios_tests_build_settings = || {
-            PlistKeyValueItem::create_value_type_by_array(vec![
-                ("CODE_SIGN_STYLE", "Automatic"),
-                ("CURRENT_PROJECT_VERSION", "1"),
-                ("DEVELOPMENT_TEAM", "\"\""),
-                ("GENERATE_INFOPLIST_FILE", "YES"),
-                ("MARKETING_VERSION", "1.0"),
-                (
-                    "PRODUCT_BUNDLE_IDENTIFIER",
-                    format!("\"{}.{}-iosTest\"", base_bundle_identifier, xfw_name).as_str(),
-                ),
-                ("PRODUCT_NAME", "\"$(TARGET_NAME)\""),
-                ("SWIFT_EMIT_LOC_STRINGS", "NO"),
-                ("SWIFT_VERSION", "5.0"),
-                ("TARGETED_DEVICE_FAMILY", "\"1,2\""),
-            ])
+            pbx! {
+                CODE_SIGN_STYLE = Automatic;
+                CURRENT_PROJECT_VERSION = "1";
+                DEVELOPMENT_TEAM = "";
+                GENERATE_INFOPLIST_FILE = YES;
+                MARKETING_VERSION = "1.0";
+                PRODUCT_BUNDLE_IDENTIFIER = [format!("{}.{}-iosTest", base_bundle_identifier, xfw_name)];
+                PRODUCT_NAME = "$(TARGET_NAME)";
+                SWIFT_EMIT_LOC_STRINGS = NO;
+                SWIFT_VERSION = "5.0";
+                TARGETED_DEVICE_FAMILY = "1,2";
+            }
         };
-        let ios_tests_debug_id = id_maker(
-            id_base,
-            kind,
+        let ios_tests_debug_id = ids.allocate(kind,
             format!("{}_iosTests-Debug", xfw_name).as_str(),
         );
-        let ios_tests_debug_item = PlistKeyValueItem::create_array_type(
-            ios_tests_debug_id.as_str(),
-            Some("Debug"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type(
-                    "buildSettings",
-                    None,
-                    get_ios_tests_build_settings(),
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Debug", None),
-            ],
-        );
-
-        let ios_tests_release_id = id_maker(
-            id_base,
-            kind,
+        let ios_tests_release_id = ids.allocate(kind,
             format!("{}_iosTests-Release", xfw_name).as_str(),
         );
-        let ios_tests_release_item = PlistKeyValueItem::create_array_type(
-            ios_tests_release_id.as_str(),
-            Some("Release"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type(
-                    "buildSettings",
-                    None,
-                    get_ios_tests_build_settings(),
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Release", None),
-            ],
-        );
 
         let get_macos_build_settings = || {
-            let mut items = PlistKeyValueItem::create_value_type_by_array(vec![
-                ("CODE_SIGN_STYLE", "Automatic"),
-                ("COMBINE_HIDPI_IMAGES", "YES"),
-                ("CURRENT_PROJECT_VERSION", "1"),
-                ("DEFINES_MODULE", "YES"),
-                ("DEVELOPMENT_TEAM", "\"\""),
-                ("DYLIB_COMPATIBILITY_VERSION", "1"),
-                ("DYLIB_CURRENT_VERSION", "1"),
-                ("DYLIB_INSTALL_NAME_BASE", "\"@rpath\""),
-                ("GENERATE_INFOPLIST_FILE", "YES"),
-                ("INFOPLIST_KEY_NSHumanReadableCopyright", "\"\""),
-                ("INSTALL_PATH", "\"$(LOCAL_LIBRARY_DIR)/Frameworks\""),
-                ("MACOSX_DEPLOYMENT_TARGET", "12.1"),
-                ("MARKETING_VERSION", "1.0"),
-                ("MODULEMAP_FILE", "\"\""),
-                (
-                    "PRODUCT_BUNDLE_IDENTIFIER",
-                    format!("\"{}.{}-macos\"", base_bundle_identifier, xfw_name).as_str(),
-                ),
-                ("PRODUCT_NAME", format!("{}", xfw_name).as_str() ),
-                ("SDKROOT", "macosx"),
-                ("SKIP_INSTALL", "YES"),
-                ("SWIFT_EMIT_LOC_STRINGS", "YES"),
-                ("SWIFT_VERSION", "5.0"),
-            ]);
-            items.push(PlistTupleItem::create(
-                "LD_RUNPATH_SEARCH_PATHS",
-                vec![
-                    ("\"$(inherited)\"", None),
-                    ("\"@executable_path/../Frameworks\"", None),
-                    ("\"@loader_path/Frameworks\"", None),
-                ],
-            ));
-            items
+            pbx! {
+                CODE_SIGN_STYLE = Automatic;
+                COMBINE_HIDPI_IMAGES = YES;
+                CURRENT_PROJECT_VERSION = "1";
+                DEFINES_MODULE = YES;
+                DEVELOPMENT_TEAM = "";
+                DYLIB_COMPATIBILITY_VERSION = "1";
+                DYLIB_CURRENT_VERSION = "1";
+                DYLIB_INSTALL_NAME_BASE = "@rpath";
+                GENERATE_INFOPLIST_FILE = YES;
+                INFOPLIST_KEY_NSHumanReadableCopyright = "";
+                INSTALL_PATH = "$(LOCAL_LIBRARY_DIR)/Frameworks";
+                MACOSX_DEPLOYMENT_TARGET = "12.1";
+                MARKETING_VERSION = "1.0";
+                MODULEMAP_FILE = "";
+                PRODUCT_BUNDLE_IDENTIFIER = [format!("{}.{}-macos", base_bundle_identifier, xfw_name)];
+                PRODUCT_NAME = [xfw_name];
+                SDKROOT = macosx;
+                SKIP_INSTALL = YES;
+                SWIFT_EMIT_LOC_STRINGS = YES;
+                SWIFT_VERSION = "5.0";
+                LD_RUNPATH_SEARCH_PATHS = (
+                    "$(inherited)",
+                    "@executable_path/../Frameworks",
+                    "@loader_path/Frameworks",
+                );
+            }
         };
-
-        let macos_debug_id = id_maker(id_base, kind, format!("{}_macos-Debug", xfw_name).as_str());
-        let macos_debug_item = PlistKeyValueItem::create_array_type(
-            macos_debug_id.as_str(),
-            Some("Debug"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type(
-                    "buildSettings",
-                    None,
-                    get_macos_build_settings(),
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Debug", None),
-            ],
-        );
-
+        let macos_debug_id = ids.allocate(kind, format!("{}_macos-Debug", xfw_name).as_str());
         let macos_release_id =
-            id_maker(id_base, kind, format!("{}_macos-Release", xfw_name).as_str());
-        let macos_release_item = PlistKeyValueItem::create_array_type(
-            macos_release_id.as_str(),
-            Some("Release"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type(
-                    "buildSettings",
-                    None,
-                    get_macos_build_settings(),
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Release", None),
-            ],
-        );
+            ids.allocate(kind, format!("{}_macos-Release", xfw_name).as_str());
 
         let get_macos_tests_build_settings = || {
-            PlistKeyValueItem::create_value_type_by_array(vec![
-                ("CODE_SIGN_STYLE", "Automatic"),
-                ("CURRENT_PROJECT_VERSION", "1"),
-                ("DEVELOPMENT_TEAM", "\"\""),
-                ("GENERATE_INFOPLIST_FILE", "YES"),
-                ("MACOSX_DEPLOYMENT_TARGET", "12.1"),
-                ("MARKETING_VERSION", "1.0"),
-                (
-                    "PRODUCT_BUNDLE_IDENTIFIER",
-                    format!("\"{}.{}-macosTests\"", base_bundle_identifier, xfw_name).as_str(),
-                ),
-                ("PRODUCT_NAME", "\"$(TARGET_NAME)\""),
-                ("SDKROOT", "macosx"),
-                ("SWIFT_EMIT_LOC_STRINGS", "NO"),
-                ("SWIFT_VERSION", "5.0"),
-            ])
+            pbx! {
+                CODE_SIGN_STYLE = Automatic;
+                CURRENT_PROJECT_VERSION = "1";
+                DEVELOPMENT_TEAM = "";
+                GENERATE_INFOPLIST_FILE = YES;
+                MACOSX_DEPLOYMENT_TARGET = "12.1";
+                MARKETING_VERSION = "1.0";
+                PRODUCT_BUNDLE_IDENTIFIER = [format!("{}.{}-macosTests", base_bundle_identifier, xfw_name)];
+                PRODUCT_NAME = "$(TARGET_NAME)";
+                SDKROOT = macosx;
+                SWIFT_EMIT_LOC_STRINGS = NO;
+                SWIFT_VERSION = "5.0";
+            }
         };
-
-        let macos_tests_debug_id = id_maker(
-            id_base,
-            kind,
+        let macos_tests_debug_id = ids.allocate(kind,
             format!("{}_macosTests-Debug", xfw_name).as_str(),
         );
-        let macos_tests_debug_item = PlistKeyValueItem::create_array_type(
-            macos_tests_debug_id.as_str(),
-            Some("Debug"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type(
-                    "buildSettings",
-                    None,
-                    get_macos_tests_build_settings(),
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Debug", None),
-            ],
-        );
-
-        let macos_tests_release_id = id_maker(
-            id_base,
-            kind,
+        let macos_tests_release_id = ids.allocate(kind,
             format!("{}_macosTests-Release", xfw_name).as_str(),
         );
-        let macos_tests_release_item = PlistKeyValueItem::create_array_type(
-            macos_tests_release_id.as_str(),
-            Some("Release"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type(
-                    "buildSettings",
-                    None,
-                    get_macos_tests_build_settings(),
-                ),
-                PlistKeyValueItem::create_value_type("name", None, "Release", None),
-            ],
-        );
+
+        let items = pbx! {
+            [shared_debug_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..shared_debug_build_settings.into_entries(); };
+                name = Debug;
+            };
+            [shared_release_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..shared_release_build_settings.into_entries(); };
+                name = Release;
+            };
+            [ios_debug_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..get_ios_config().into_entries(); };
+                name = Debug;
+            };
+            [ios_release_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..get_ios_config().into_entries(); };
+                name = Release;
+            };
+            [ios_tests_debug_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..get_ios_tests_build_settings().into_entries(); };
+                name = Debug;
+            };
+            [ios_tests_release_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..get_ios_tests_build_settings().into_entries(); };
+                name = Release;
+            };
+            [macos_debug_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..get_macos_build_settings().into_entries(); };
+                name = Debug;
+            };
+            [macos_release_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..get_macos_build_settings().into_entries(); };
+                name = Release;
+            };
+            [macos_tests_debug_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..get_macos_tests_build_settings().into_entries(); };
+                name = Debug;
+            };
+            [macos_tests_release_id] = {
+                isa = XCBuildConfiguration;
+                buildSettings = { ..get_macos_tests_build_settings().into_entries(); };
+                name = Release;
+            };
+        };
 
         let ids = XCBuildConfigurationIds {
             shared_debug: shared_debug_id,
@@ -1939,233 +1082,68 @@ impl PlistKeyValueItem {
             macos_tests_release: macos_tests_release_id,
         };
 
-        (
-            ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![
-                    shared_debug_item,
-                    shared_release_item,
-                    ios_debug_item,
-                    ios_release_item,
-                    ios_tests_debug_item,
-                    ios_tests_release_item,
-                    macos_debug_item,
-                    macos_release_item,
-                    macos_tests_debug_item,
-                    macos_tests_release_item,
-                ],
-            }),
-        )
+        (ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_xc_configuration_list_section(
         pbx_build_config_ref_ids: &XCBuildConfigurationIds,
         xfw_name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (XCConfigurationListIds, PlistItem) {
         let kind = "XCConfigurationList";
 
-        let shared_build_id = id_maker(
-            id_base,
-            kind,
+        let shared_build_id = ids.allocate(kind,
             format!("{}-shared-config", xfw_name).as_str(),
         );
-        let shared_build_item = PlistKeyValueItem::create_array_type(
-            shared_build_id.as_str(),
-            Some(format!("Build configuration list for PBXProject \"{}\"", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "buildConfigurations",
-                    vec![
-                        (
-                            pbx_build_config_ref_ids.shared_debug.as_str(),
-                            Some("Debug".to_string()),
-                        ),
-                        (
-                            pbx_build_config_ref_ids.shared_release.as_str(),
-                            Some("Release".to_string()),
-                        ),
-                    ],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationIsVisible",
-                    None,
-                    "0",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationName",
-                    None,
-                    "Release",
-                    None,
-                ),
-            ],
-        );
-
-        let ios_build_id = id_maker(id_base, kind, format!("{}_ios-config", xfw_name).as_str());
-        let ios_build_item = PlistKeyValueItem::create_array_type(
-            ios_build_id.as_str(),
-            Some(
-                format!(
-                    "Build configuration list for PBXNativeTarget \"{}_ios\"",
-                    xfw_name
-                )
-                .as_str(),
-            ),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "buildConfigurations",
-                    vec![
-                        (
-                            pbx_build_config_ref_ids.ios_debug.as_str(),
-                            Some("Debug".to_string()),
-                        ),
-                        (
-                            pbx_build_config_ref_ids.ios_release.as_str(),
-                            Some("Release".to_string()),
-                        ),
-                    ],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationIsVisible",
-                    None,
-                    "0",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationName",
-                    None,
-                    "Release",
-                    None,
-                ),
-            ],
-        );
-
+        let ios_build_id = ids.allocate(kind, format!("{}_ios-config", xfw_name).as_str());
         let ios_tests_build_id =
-            id_maker(id_base, kind, format!("{}_iosTests-config", xfw_name).as_str());
-        let ios_tests_build_item = PlistKeyValueItem::create_array_type(
-            ios_tests_build_id.as_str(),
-            Some(
-                format!(
-                    "Build configuration list for PBXNativeTarget \"{}_iosTest\"",
-                    xfw_name
-                )
-                .as_str(),
-            ),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "buildConfigurations",
-                    vec![
-                        (
-                            pbx_build_config_ref_ids.ios_tests_debug.as_str(),
-                            Some("Debug".to_string()),
-                        ),
-                        (
-                            pbx_build_config_ref_ids.ios_tests_release.as_str(),
-                            Some("Release".to_string()),
-                        ),
-                    ],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationIsVisible",
-                    None,
-                    "0",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationName",
-                    None,
-                    "Release",
-                    None,
-                ),
-            ],
-        );
-
-        let macos_build_id = id_maker(id_base, kind, format!("{}_macos-config", xfw_name).as_str());
-        let macos_build_item = PlistKeyValueItem::create_array_type(
-            macos_build_id.as_str(),
-            Some(
-                format!(
-                    "Build configuration list for PBXNativeTarget \"{}_macos\"",
-                    xfw_name
-                )
-                .as_str(),
-            ),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "buildConfigurations",
-                    vec![
-                        (
-                            pbx_build_config_ref_ids.macos_debug.as_str(),
-                            Some("Debug".to_string()),
-                        ),
-                        (
-                            pbx_build_config_ref_ids.macos_release.as_str(),
-                            Some("Release".to_string()),
-                        ),
-                    ],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationIsVisible",
-                    None,
-                    "0",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationName",
-                    None,
-                    "Release",
-                    None,
-                ),
-            ],
-        );
-
+            ids.allocate(kind, format!("{}_iosTests-config", xfw_name).as_str());
+        let macos_build_id = ids.allocate(kind, format!("{}_macos-config", xfw_name).as_str());
         let macos_test_build_id =
-            id_maker(id_base, kind, format!("{}_macosTests-config", xfw_name).as_str());
-        let macos_test_build_item = PlistKeyValueItem::create_array_type(
-            macos_test_build_id.as_str(),
-            Some(
-                format!(
-                    "Build configuration list for PBXNativeTarget \"{}_macosTests\"",
-                    xfw_name
-                )
-                .as_str(),
-            ),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistTupleItem::create(
-                    "buildConfigurations",
-                    vec![
-                        (
-                            pbx_build_config_ref_ids.macos_tests_debug.as_str(),
-                            Some("Debug".to_string()),
-                        ),
-                        (
-                            pbx_build_config_ref_ids.macos_tests_release.as_str(),
-                            Some("Release".to_string()),
-                        ),
-                    ],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationIsVisible",
-                    None,
-                    "0",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "defaultConfigurationName",
-                    None,
-                    "Release",
-                    None,
-                ),
-            ],
-        );
+            ids.allocate(kind, format!("{}_macosTests-config", xfw_name).as_str());
+
+        let configuration_list = |debug: &str, release: &str| {
+            pbx! {
+                isa = XCConfigurationList;
+                buildConfigurations = ([debug], [release]);
+                defaultConfigurationIsVisible = "0";
+                defaultConfigurationName = Release;
+            }
+            .into_entries()
+        };
+
+        let items = pbx! {
+            [shared_build_id; format!("Build configuration list for PBXProject \"{}\"", xfw_name)] = {
+                ..configuration_list(
+                    &pbx_build_config_ref_ids.shared_debug,
+                    &pbx_build_config_ref_ids.shared_release,
+                );
+            };
+            [ios_build_id] = {
+                ..configuration_list(
+                    &pbx_build_config_ref_ids.ios_debug,
+                    &pbx_build_config_ref_ids.ios_release,
+                );
+            };
+            [ios_tests_build_id] = {
+                ..configuration_list(
+                    &pbx_build_config_ref_ids.ios_tests_debug,
+                    &pbx_build_config_ref_ids.ios_tests_release,
+                );
+            };
+            [macos_build_id] = {
+                ..configuration_list(
+                    &pbx_build_config_ref_ids.macos_debug,
+                    &pbx_build_config_ref_ids.macos_release,
+                );
+            };
+            [macos_test_build_id] = {
+                ..configuration_list(
+                    &pbx_build_config_ref_ids.macos_tests_debug,
+                    &pbx_build_config_ref_ids.macos_tests_release,
+                );
+            };
+        };
 
         let ids = XCConfigurationListIds {
             shared: shared_build_id,
@@ -2174,93 +1152,45 @@ impl PlistKeyValueItem {
             macos: macos_build_id,
             macos_test: macos_test_build_id,
         };
-        (
-            ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![
-                    shared_build_item,
-                    ios_build_item,
-                    ios_tests_build_item,
-                    macos_build_item,
-                    macos_test_build_item,
-                ],
-            }),
-        )
+        (ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_target_dependency_section(
         xfw_name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXTargetDependencyIds, PlistItem) {
         let kind = "PBXTargetDependency";
 
         let native_target_kind = "PBXNativeTarget";
-        let ios_native_target_id = id_maker(
-            id_base,
-            native_target_kind,
+        let ios_native_target_id = ids.allocate(native_target_kind,
             format!("{}_ios", xfw_name).as_str(),
         );
-        let macos_native_target_id = id_maker(
-            id_base,
-            native_target_kind,
+        let macos_native_target_id = ids.allocate(native_target_kind,
             format!("{}_macos", xfw_name).as_str(),
         );
 
         let container_item_proxy_kind = "PBXContainerItemProxy";
-        let ios_container_item_proxy_id = id_maker(
-            id_base,
-            container_item_proxy_kind,
+        let ios_container_item_proxy_id = ids.allocate(container_item_proxy_kind,
             format!("{}_ios", xfw_name).as_str(),
         );
-        let macos_container_item_proxy_id = id_maker(
-            id_base,
-            container_item_proxy_kind,
+        let macos_container_item_proxy_id = ids.allocate(container_item_proxy_kind,
             format!("{}_macos", xfw_name).as_str(),
         );
 
-        let ios_id = id_maker(id_base, kind, format!("{}_ios", xfw_name).as_str());
-        let macos_id = id_maker(id_base, kind, format!("{}_macos", xfw_name).as_str());
-        let ios_item = PlistKeyValueItem::create_array_type(
-            ios_id.as_str(),
-            Some(kind),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "target",
-                    None,
-                    ios_native_target_id.as_str(),
-                    Some(format!("{}_ios", xfw_name).as_str()),
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "targetProxy",
-                    None,
-                    ios_container_item_proxy_id.as_str(),
-                    Some(container_item_proxy_kind),
-                ),
-            ],
-        );
-
-        let macos_item = PlistKeyValueItem::create_array_type(
-            macos_id.as_str(),
-            Some(kind),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "target",
-                    None,
-                    macos_native_target_id.as_str(),
-                    Some(format!("{}_macos", xfw_name).as_str()),
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "targetProxy",
-                    None,
-                    macos_container_item_proxy_id.as_str(),
-                    Some(container_item_proxy_kind),
-                ),
-            ],
-        );
+        let ios_id = ids.allocate(kind, format!("{}_ios", xfw_name).as_str());
+        let macos_id = ids.allocate(kind, format!("{}_macos", xfw_name).as_str());
+        let items = pbx! {
+            [ios_id] = {
+                isa = PBXTargetDependency;
+                target = [ios_native_target_id];
+                targetProxy = [ios_container_item_proxy_id];
+            };
+            [macos_id] = {
+                isa = PBXTargetDependency;
+                target = [macos_native_target_id];
+                targetProxy = [macos_container_item_proxy_id];
+            };
+        };
 
         let ids = PBXTargetDependencyIds {
             ios_id,
@@ -2270,93 +1200,44 @@ impl PlistKeyValueItem {
             ios_container_item_proxy_id,
             macos_container_item_proxy_id,
         };
-        (
-            ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![ios_item, macos_item],
-            }),
-        )
+        (ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_container_item_proxy_section(
         project_object_id: &str,
         pbx_target_dependency_ids: &PBXTargetDependencyIds,
         xfw_name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXContainerItemProxyIds, PlistItem) {
         let kind = "PBXContainerItemProxy";
 
-        let ios_id = id_maker(id_base, kind, format!("{}_ios", xfw_name).as_str());
-        let ios_item = PlistKeyValueItem::create_array_type(
-            ios_id.as_str(),
-            Some(kind),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("containerPortal", None, project_object_id, Some("Project object")),
-                PlistKeyValueItem::create_value_type(
-                    "proxyType",
-                    None,
-                    "1",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "remoteGlobalIDString",
-                    None,
-                    pbx_target_dependency_ids.ios_native_target_id.as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "remoteInfo",
-                    None,
-                    format!("\"{}_ios\"", xfw_name).as_str(),
-                    None,
-                ),
-            ],
-        );
-
-        let macos_id = id_maker(id_base, kind, format!("{}_macos", xfw_name).as_str());
-        let macos_item = PlistKeyValueItem::create_array_type(
-            macos_id.as_str(),
-            Some(kind),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type("containerPortal", None, project_object_id, Some("Project object")),
-                PlistKeyValueItem::create_value_type(
-                    "proxyType",
-                    None,
-                    "1",
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "remoteGlobalIDString",
-                    None,
-                    pbx_target_dependency_ids.macos_native_target_id.as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "remoteInfo",
-                    None,
-                    format!("\"{}_macos\"", xfw_name).as_str(),
-                    None,
-                ),
-            ],
-        );
+        let ios_id = ids.allocate(kind, format!("{}_ios", xfw_name).as_str());
+        let macos_id = ids.allocate(kind, format!("{}_macos", xfw_name).as_str());
+        let items = pbx! {
+            [ios_id] = {
+                isa = PBXContainerItemProxy;
+                containerPortal = [project_object_id];
+                proxyType = "1";
+                remoteGlobalIDString = [pbx_target_dependency_ids.ios_native_target_id];
+                remoteInfo = [format!("{}_ios", xfw_name)];
+            };
+            [macos_id] = {
+                isa = PBXContainerItemProxy;
+                containerPortal = [project_object_id];
+                proxyType = "1";
+                remoteGlobalIDString = [pbx_target_dependency_ids.macos_native_target_id];
+                remoteInfo = [format!("{}_macos", xfw_name)];
+            };
+        };
 
         let ids = PBXContainerItemProxyIds {
             ios: ios_id,
             macos: macos_id,
         };
-        (
-            ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![ios_item, macos_item],
-            }),
-        )
+        (ids, PlistSectionItem::create(kind, items))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn create_pbx_native_target_section(
         pbx_target_dependency_ids: &PBXTargetDependencyIds,
         pbx_config_list: &XCConfigurationListIds,
@@ -2368,304 +1249,78 @@ impl PlistKeyValueItem {
         pbx_resources_build_ids_ref: &PBXResourcesBuildPhaseIds,
         pbx_build_rule_ids_ref: &PBXBuildRuleIds,
         xfw_name: &str,
-        id_base: u64,
-        id_maker: fn(u64, kind: &str, name: &str) -> String,
+        ids: &IdAllocator,
     ) -> (PBXNativeTargetIds, PlistItem) {
         let kind = "PBXNativeTarget";
-
-        let ios_id = id_maker(id_base, kind, format!("{}_ios", xfw_name).as_str());
-        let ios_item = PlistKeyValueItem::create_array_type(
-            ios_id.as_str(),
-            Some(format!("{}_ios", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "buildConfigurationList",
-                    None,
-                    pbx_config_list.ios.as_str(),
-                    Some(
-                        format!(
-                            "Build configuration list for PBXNativeTarget \"{}_ios\"",
-                            xfw_name
-                        )
-                        .as_str(),
-                    ),
-                ),
-                PlistTupleItem::create(
-                    "buildPhases",
-                    vec![
-                        (
-                            pbx_shell_script_ids_ref.ios.as_str(),
-                            Some("ShellScript".to_string()),
-                        ),
-                        (
-                            pbx_headers_build_ids_ref.headers_ios.as_str(),
-                            Some("Headers".to_string()),
-                        ),
-                        (
-                            pbx_sources_build_ids_ref.ios_docc.as_str(),
-                            Some("Sources".to_string()),
-                        ),
-                        (
-                            pbx_framework_build_ids_ref.ios_framework.as_str(),
-                            Some("Frameworks".to_string()),
-                        ),
-                        (
-                            pbx_resources_build_ids_ref.ios.as_str(),
-                            Some("Resources".to_string()),
-                        ),
-                    ],
-                ),
-                PlistTupleItem::create(
-                    "buildRules",
-                    vec![(
-                        pbx_build_rule_ids_ref.ios.as_str(),
-                        Some("PBXBuildRule".to_string()),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "name",
-                    None,
-                    format!("\"{}_ios\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productName",
-                    None,
-                    format!("\"{}_ios\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productReference",
-                    None,
-                    pbx_file_ref_ids.ios_framework.as_str(),
-                    Some(format!("\"{}_ios.framework\"", xfw_name).as_str()),
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productType",
-                    None,
-                    "\"com.apple.product-type.framework\"",
-                    None,
-                ),
-            ],
-        );
-
-        let ios_tests_id = id_maker(id_base, kind, format!("{}_iosTests", xfw_name).as_str());
-        let ios_tests_item = PlistKeyValueItem::create_array_type(
-            ios_tests_id.as_str(),
-            Some(format!("{}_iosTests", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "buildConfigurationList",
-                    None,
-                    pbx_config_list.ios_tests.as_str(),
-                    Some(
-                        format!(
-                            "Build configuration list for PBXNativeTarget \"{}_iosTests\"",
-                            xfw_name
-                        )
-                        .as_str(),
-                    ),
-                ),
-                PlistTupleItem::create(
-                    "buildPhases",
-                    vec![
-                        (
-                            pbx_sources_build_ids_ref.ios_tests_swift.as_str(),
-                            Some("Sources".to_string()),
-                        ),
-                        (
-                            pbx_framework_build_ids_ref.ios_tests_xctest.as_str(),
-                            Some("Frameworks".to_string()),
-                        ),
-                        (
-                            pbx_resources_build_ids_ref.ios_tests.as_str(),
-                            Some("Resources".to_string()),
-                        ),
-                    ],
-                ),
-                PlistTupleItem::create("buildRules", vec![]),
-                PlistTupleItem::create(
-                    "dependencies",
-                    vec![(
-                        pbx_target_dependency_ids.ios_id.as_str(),
-                        Some("PBXTargetDependency".to_string()),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "name",
-                    None,
-                    format!("\"{}_iosTests\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productName",
-                    None,
-                    format!("\"{}_iosTests\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productReference",
-                    None,
-                    pbx_file_ref_ids.ios_tests_xc.as_str(),
-                    Some(format!("\"{}_iosTests.xctest\"", xfw_name).as_str()),
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productType",
-                    None,
-                    "\"com.apple.product-type.bundle.unit-test\"",
-                    None,
-                ),
-            ],
-        );
-
-        let macos_id = id_maker(id_base, kind, format!("{}_macos", xfw_name).as_str());
-        let macos_item = PlistKeyValueItem::create_array_type(
-            macos_id.as_str(),
-            Some(format!("{}_macos", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "buildConfigurationList",
-                    None,
-                    pbx_config_list.macos.as_str(),
-                    Some(
-                        format!(
-                            "Build configuration list for PBXNativeTarget \"{}_ios\"",
-                            xfw_name
-                        )
-                        .as_str(),
-                    ),
-                ),
-                PlistTupleItem::create(
-                    "buildPhases",
-                    vec![
-                        (
-                            pbx_shell_script_ids_ref.macos.as_str(),
-                            Some("ShellScript".to_string()),
-                        ),
-                        (
-                            pbx_headers_build_ids_ref.headers_macos.as_str(),
-                            Some("Headers".to_string()),
-                        ),
-                        (
-                            pbx_sources_build_ids_ref.macos_docc.as_str(),
-                            Some("Sources".to_string()),
-                        ),
-                        (
-                            pbx_framework_build_ids_ref.macos_framework.as_str(),
-                            Some("Frameworks".to_string()),
-                        ),
-                        (
-                            pbx_resources_build_ids_ref.macos.as_str(),
-                            Some("Resources".to_string()),
-                        ),
-                    ],
-                ),
-                PlistTupleItem::create(
-                    "buildRules",
-                    vec![(
-                        pbx_build_rule_ids_ref.macos.as_str(),
-                        Some("PBXBuildRule".to_string()),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "name",
-                    None,
-                    format!("\"{}_macos\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productName",
-                    None,
-                    format!("\"{}_macos\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productReference",
-                    None,
-                    pbx_file_ref_ids.macos_framework.as_str(),
-                    Some(format!("\"{}_macos.framework\"", xfw_name).as_str()),
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productType",
-                    None,
-                    "\"com.apple.product-type.framework\"",
-                    None,
-                ),
-            ],
-        );
-
-        let macos_tests_id = id_maker(id_base, kind, format!("{}_macosTests", xfw_name).as_str());
-        let macos_tests_item = PlistKeyValueItem::create_array_type(
-            macos_tests_id.as_str(),
-            Some(format!("{}_macosTests", xfw_name).as_str()),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_value_type(
-                    "buildConfigurationList",
-                    None,
-                    pbx_config_list.macos_test.as_str(),
-                    Some(
-                        format!(
-                            "Build configuration list for PBXNativeTarget \"{}_macosTests\"",
-                            xfw_name
-                        )
-                        .as_str(),
-                    ),
-                ),
-                PlistTupleItem::create(
-                    "buildPhases",
-                    vec![
-                        (
-                            pbx_sources_build_ids_ref.macos_tests_swift.as_str(),
-                            Some("Sources".to_string()),
-                        ),
-                        (
-                            pbx_framework_build_ids_ref.macos_tests_xctest.as_str(),
-                            Some("Frameworks".to_string()),
-                        ),
-                        (
-                            pbx_resources_build_ids_ref.macos_tests.as_str(),
-                            Some("Resources".to_string()),
-                        ),
-                    ],
-                ),
-                PlistTupleItem::create("buildRules", vec![]),
-                PlistTupleItem::create(
-                    "dependencies",
-                    vec![(
-                        pbx_target_dependency_ids.macos_id.as_str(),
-                        Some("PBXTargetDependency".to_string()),
-                    )],
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "name",
-                    None,
-                    format!("\"{}_macosTests\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productName",
-                    None,
-                    format!("\"{}_macosTests\"", xfw_name).as_str(),
-                    None,
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productReference",
-                    None,
-                    pbx_file_ref_ids.macos_tests_xc.as_str(),
-                    Some(format!("\"{}_macosTests.xctest\"", xfw_name).as_str()),
-                ),
-                PlistKeyValueItem::create_value_type(
-                    "productType",
-                    None,
-                    "\"com.apple.product-type.bundle.unit-test\"",
-                    None,
-                ),
-            ],
-        );
+        let ios_id = ids.allocate(kind, format!("{}_ios", xfw_name).as_str());
+        let ios_tests_id = ids.allocate(kind, format!("{}_iosTests", xfw_name).as_str());
+        let macos_id = ids.allocate(kind, format!("{}_macos", xfw_name).as_str());
+        let macos_tests_id = ids.allocate(kind, format!("{}_macosTests", xfw_name).as_str());
+
+        let items = pbx! {
+            [ios_id] = {
+                isa = PBXNativeTarget;
+                buildConfigurationList = [pbx_config_list.ios];
+                buildPhases = (
+                    [pbx_shell_script_ids_ref.ios],
+                    [pbx_headers_build_ids_ref.headers_ios],
+                    [pbx_sources_build_ids_ref.ios_docc],
+                    [pbx_framework_build_ids_ref.ios_framework],
+                    [pbx_resources_build_ids_ref.ios],
+                );
+                buildRules = ([pbx_build_rule_ids_ref.ios]);
+                name = [format!("{}_ios", xfw_name)];
+                productName = [format!("{}_ios", xfw_name)];
+                productReference = [pbx_file_ref_ids.ios_framework];
+                productType = "com.apple.product-type.framework";
+            };
+            [ios_tests_id] = {
+                isa = PBXNativeTarget;
+                buildConfigurationList = [pbx_config_list.ios_tests];
+                buildPhases = (
+                    [pbx_sources_build_ids_ref.ios_tests_swift],
+                    [pbx_framework_build_ids_ref.ios_tests_xctest],
+                    [pbx_resources_build_ids_ref.ios_tests],
+                );
+                buildRules = ();
+                dependencies = ([pbx_target_dependency_ids.ios_id]);
+                name = [format!("{}_iosTests", xfw_name)];
+                productName = [format!("{}_iosTests", xfw_name)];
+                productReference = [pbx_file_ref_ids.ios_tests_xc];
+                productType = "com.apple.product-type.bundle.unit-test";
+            };
+            [macos_id] = {
+                isa = PBXNativeTarget;
+                buildConfigurationList = [pbx_config_list.macos];
+                buildPhases = (
+                    [pbx_shell_script_ids_ref.macos],
+                    [pbx_headers_build_ids_ref.headers_macos],
+                    [pbx_sources_build_ids_ref.macos_docc],
+                    [pbx_framework_build_ids_ref.macos_framework],
+                    [pbx_resources_build_ids_ref.macos],
+                );
+                buildRules = ([pbx_build_rule_ids_ref.macos]);
+                name = [format!("{}_macos", xfw_name)];
+                productName = [format!("{}_macos", xfw_name)];
+                productReference = [pbx_file_ref_ids.macos_framework];
+                productType = "com.apple.product-type.framework";
+            };
+            [macos_tests_id] = {
+                isa = PBXNativeTarget;
+                buildConfigurationList = [pbx_config_list.macos_test];
+                buildPhases = (
+                    [pbx_sources_build_ids_ref.macos_tests_swift],
+                    [pbx_framework_build_ids_ref.macos_tests_xctest],
+                    [pbx_resources_build_ids_ref.macos_tests],
+                );
+                buildRules = ();
+                dependencies = ([pbx_target_dependency_ids.macos_id]);
+                name = [format!("{}_macosTests", xfw_name)];
+                productName = [format!("{}_macosTests", xfw_name)];
+                productReference = [pbx_file_ref_ids.macos_tests_xc];
+                productType = "com.apple.product-type.bundle.unit-test";
+            };
+        };
 
         let ids = PBXNativeTargetIds {
             ios: ios_id,
@@ -2673,13 +1328,7 @@ impl PlistKeyValueItem {
             macos: macos_id,
             macos_test: macos_tests_id,
         };
-        (
-            ids,
-            PlistItem::SectionItem(PlistSectionItem {
-                name: kind.to_string(),
-                items: vec![ios_item, ios_tests_item, macos_item, macos_tests_item],
-            }),
-        )
+        (ids, PlistSectionItem::create(kind, items))
     }
 
     pub(crate) fn create_pbx_project_section(
@@ -2690,45 +1339,41 @@ impl PlistKeyValueItem {
         xfw_name: &str,
     ) -> PlistItem {
         let kind = "PBXProject";
-        let project_item = PlistKeyValueItem::create_array_type(
-            project_object_id,
-            Some("Project object"),
-            vec![
-                PlistKeyValueItem::create_value_type("isa", None, kind, None),
-                PlistKeyValueItem::create_array_type( "attributes", None, vec![
-                    PlistKeyValueItem::create_value_type("BuildIndependentTargetsInParallel", None, "1", None),
-                    PlistKeyValueItem::create_value_type("LastSwiftUpdateCheck", None, "1320", None),
-                    PlistKeyValueItem::create_value_type("LastUpgradeCheck", None, "1320", None),
-                    PlistKeyValueItem::create_array_type( "TargetAttributes", None, vec![
-                        //PlistKeyValueItem::create_array_type(""
-                        PlistKeyValueItem::create_array_type(pbx_native_target_ids.ios.as_str(), None, vec![PlistKeyValueItem::create_value_type("CreatedOnToolsVersion", None, "13.2.1", None),]),
-                        PlistKeyValueItem::create_array_type(pbx_native_target_ids.ios_tests.as_str(), None, vec![PlistKeyValueItem::create_value_type("CreatedOnToolsVersion", None, "13.2.1", None),]),
-                        PlistKeyValueItem::create_array_type(pbx_native_target_ids.macos.as_str(), None, vec![PlistKeyValueItem::create_value_type("CreatedOnToolsVersion", None, "13.2.1", None),]),
-                        PlistKeyValueItem::create_array_type(pbx_native_target_ids.macos_test.as_str(), None, vec![PlistKeyValueItem::create_value_type("CreatedOnToolsVersion", None, "13.2.1", None),]),
-                    ]),
-                ]),
-                PlistKeyValueItem::create_value_type("buildConfigurationList", None, pbx_xc_config_list_ids.shared.as_str(), Some(format!("Build configuration list for PBXProject \"{}\"", xfw_name).as_str())),
-                PlistKeyValueItem::create_value_type("compatibilityVersion", None, "\"Xcode 13.0\"", None),
-                PlistKeyValueItem::create_value_type("developmentRegion", None, "en", None),
-                PlistKeyValueItem::create_value_type("hasScannedForEncodings", None, "0", None),
-                PlistTupleItem::create("knownRegions", vec![ ( "en", None, ), ( "Base", None, ), ], ),
-                PlistKeyValueItem::create_value_type("mainGroup", None, pbx_group_ids.groups.as_str(), None),
-                PlistKeyValueItem::create_value_type("productRefGroup", None, pbx_group_ids.products.as_str(), Some("Products")),
-                PlistKeyValueItem::create_value_type("projectDirPath", None, "\"\"", None),
-                PlistKeyValueItem::create_value_type("projectRoot", None, "\"\"", None),
-                PlistTupleItem::create("targets", vec![
-                    ( pbx_native_target_ids.ios.as_str(), Some(format!("{}_ios", xfw_name)) ),
-                    ( pbx_native_target_ids.ios_tests.as_str(), Some(format!("{}_iosTests", xfw_name)) ),
-                    ( pbx_native_target_ids.macos.as_str(), Some(format!("{}_macos", xfw_name)) ),
-                    ( pbx_native_target_ids.macos_test.as_str(), Some(format!("{}_macosTests", xfw_name)) )
-                ], ),
-            ]);
-
-        PlistItem::SectionItem(PlistSectionItem {
-            name: kind.to_string(),
-            items: vec![project_item],
-        })
+        let items = pbx! {
+            [project_object_id] = {
+                isa = PBXProject;
+                attributes = {
+                    BuildIndependentTargetsInParallel = "1";
+                    LastSwiftUpdateCheck = "1320";
+                    LastUpgradeCheck = "1320";
+                    TargetAttributes = {
+                        [pbx_native_target_ids.ios] = { CreatedOnToolsVersion = "13.2.1"; };
+                        [pbx_native_target_ids.ios_tests] = { CreatedOnToolsVersion = "13.2.1"; };
+                        [pbx_native_target_ids.macos] = { CreatedOnToolsVersion = "13.2.1"; };
+                        [pbx_native_target_ids.macos_test] = { CreatedOnToolsVersion = "13.2.1"; };
+                    };
+                };
+                buildConfigurationList = [
+                    pbx_xc_config_list_ids.shared;
+                    format!("Build configuration list for PBXProject \"{}\"", xfw_name)
+                ];
+                compatibilityVersion = "Xcode 13.0";
+                developmentRegion = en;
+                hasScannedForEncodings = "0";
+                knownRegions = (en, Base);
+                mainGroup = [pbx_group_ids.groups];
+                productRefGroup = [pbx_group_ids.products];
+                projectDirPath = "";
+                projectRoot = "";
+                targets = (
+                    [pbx_native_target_ids.ios],
+                    [pbx_native_target_ids.ios_tests],
+                    [pbx_native_target_ids.macos],
+                    [pbx_native_target_ids.macos_test],
+                );
+            };
+        };
+
+        PlistSectionItem::create(kind, items)
     }
 }
-
-
@@ -0,0 +1,200 @@
+use std::borrow::Cow;
+
+use super::*;
+
+/// Borrowed plist value, strings and comments point into the parsed text.
+///
+/// Only strings with escapes own an unescaped copy.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub enum PlistRef<'a> {
+    /// String and its trailing comment.
+    String(Cow<'a, str>, Option<&'a str>),
+    /// `<hex>` data and its trailing comment.
+    Data(Vec<u8>, Option<&'a str>),
+    Dictionary(Vec<PlistEntryRef<'a>>),
+    Array(Vec<PlistRef<'a>>),
+}
+
+/// `key = value;` entry of a borrowed dictionary.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct PlistEntryRef<'a> {
+    pub key: Cow<'a, str>,
+    pub key_comment: Option<&'a str>,
+    pub value: PlistRef<'a>,
+    /// `X` of the enclosing `/* Begin X section */`.
+    pub section: Option<&'a str>,
+}
+
+impl<'a> PlistRef<'a> {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PlistRef::String(value, _) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn comment(&self) -> Option<&'a str> {
+        match self {
+            PlistRef::String(_, comment) | PlistRef::Data(_, comment) => *comment,
+            _ => None,
+        }
+    }
+
+    pub fn as_dictionary(&self) -> Option<&[PlistEntryRef<'a>]> {
+        match self {
+            PlistRef::Dictionary(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[PlistRef<'a>]> {
+        match self {
+            PlistRef::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Value of `key` when this is a dictionary.
+    pub fn get(&self, key: &str) -> Option<&PlistRef<'a>> {
+        self.as_dictionary()?
+            .iter()
+            .find(|entry| entry.key == key)
+            .map(|entry| &entry.value)
+    }
+
+    /// Owned copy of the value of a dictionary entry.
+    pub fn into_value(self) -> PlistItem {
+        match self {
+            PlistRef::String(value, comment) => PlistItem::Value(PlistValue {
+                value: PlistString::from(value),
+                comment: comment.map(str::to_string),
+            }),
+            PlistRef::Data(value, comment) => PlistItem::Data(PlistData {
+                value,
+                comment: comment.map(str::to_string),
+            }),
+            PlistRef::Dictionary(entries) => PlistItem::Array(PlistEntryRef::into_items(entries)),
+            PlistRef::Array(elements) => {
+                PlistItem::Tuple(elements.into_iter().map(PlistRef::into_element).collect())
+            }
+        }
+    }
+
+    /// Owned copy of an array element.
+    pub fn into_element(self) -> PlistItem {
+        match self {
+            PlistRef::String(value, comment) => PlistItem::TupleValue(PlistTupleValue {
+                value: PlistString::from(value),
+                comment: comment.map(str::to_string),
+            }),
+            value => value.into_value(),
+        }
+    }
+}
+
+impl<'a> From<&'a PlistItem> for PlistRef<'a> {
+    /// Borrowed view of an owned value. A `key = value;` item gives its value, a section
+    /// gives a dictionary of its entries.
+    fn from(item: &'a PlistItem) -> Self {
+        match item {
+            PlistItem::Value(value) => {
+                PlistRef::String(Cow::Borrowed(value.value.as_str()), value.comment.as_deref())
+            }
+            PlistItem::TupleValue(value) => {
+                PlistRef::String(Cow::Borrowed(value.value.as_str()), value.comment.as_deref())
+            }
+            PlistItem::Data(data) => PlistRef::Data(data.value.clone(), data.comment.as_deref()),
+            PlistItem::Item(kv) => PlistRef::from(kv.value.as_ref()),
+            PlistItem::Array(items) => PlistRef::Dictionary(PlistEntryRef::from_items(items)),
+            PlistItem::SectionItem(section) => {
+                PlistRef::Dictionary(PlistEntryRef::from_items(&section.items))
+            }
+            PlistItem::TupleItem(PlistTupleItem { items, .. }) | PlistItem::Tuple(items) => {
+                PlistRef::Array(items.iter().map(PlistRef::from).collect())
+            }
+        }
+    }
+}
+
+impl<'a> PlistEntryRef<'a> {
+    /// Borrowed entries of an owned dictionary, entries of sections are flattened.
+    pub fn from_items(items: &'a [PlistItem]) -> Vec<PlistEntryRef<'a>> {
+        let mut entries = vec![];
+        for item in items {
+            match item {
+                PlistItem::Item(kv) => entries.push(PlistEntryRef {
+                    key: Cow::Borrowed(kv.key.name.as_str()),
+                    key_comment: kv.key.comment.as_deref(),
+                    value: PlistRef::from(kv.value.as_ref()),
+                    section: None,
+                }),
+                PlistItem::TupleItem(tuple) => entries.push(PlistEntryRef {
+                    key: Cow::Borrowed(tuple.name.as_str()),
+                    key_comment: None,
+                    value: PlistRef::from(item),
+                    section: None,
+                }),
+                PlistItem::SectionItem(section) => {
+                    entries.extend(PlistEntryRef::from_items(&section.items).into_iter().map(
+                        |entry| PlistEntryRef {
+                            section: Some(section.name.as_str()),
+                            ..entry
+                        },
+                    ))
+                }
+                _ => {}
+            }
+        }
+        entries
+    }
+
+    /// Owned copy of the entry, arrays become `TupleItem`s like in parsed projects.
+    pub fn into_item(self) -> PlistItem {
+        match self.value {
+            PlistRef::Array(elements) => PlistItem::TupleItem(PlistTupleItem {
+                name: PlistString::from(self.key),
+                items: elements.into_iter().map(PlistRef::into_element).collect(),
+            }),
+            value => PlistItem::Item(PlistKeyValueItem {
+                key: Box::new(PlistKey {
+                    name: PlistString::from(self.key),
+                    comment: self.key_comment.map(str::to_string),
+                }),
+                value: Box::new(value.into_value()),
+            }),
+        }
+    }
+
+    /// Owned copies of dictionary entries, consecutive entries of a section are grouped
+    /// into a `SectionItem`.
+    pub fn into_items(entries: Vec<PlistEntryRef<'a>>) -> Vec<PlistItem> {
+        let mut items = vec![];
+        let mut open: Option<PlistSectionItem> = None;
+        for entry in entries {
+            let section = entry.section;
+            let item = entry.into_item();
+            match (section, open.as_mut()) {
+                (Some(name), Some(current)) if current.name == name => current.items.push(item),
+                (Some(name), _) => {
+                    if let Some(closed) = open.take() {
+                        items.push(PlistItem::SectionItem(closed));
+                    }
+                    open = Some(PlistSectionItem {
+                        name: name.to_string(),
+                        items: vec![item],
+                    });
+                }
+                (None, _) => {
+                    if let Some(closed) = open.take() {
+                        items.push(PlistItem::SectionItem(closed));
+                    }
+                    items.push(item);
+                }
+            }
+        }
+        if let Some(closed) = open {
+            items.push(PlistItem::SectionItem(closed));
+        }
+        items
+    }
+}
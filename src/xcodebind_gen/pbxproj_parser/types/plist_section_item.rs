@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, std::cmp::Eq, std::cmp::PartialEq)]
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
 pub struct PlistSectionItem {
     pub name: String,
     pub items: Vec<PlistItem>,
@@ -19,3 +19,13 @@ impl InternalXcodePlistSerializer for PlistSectionItem {
         string_val
     }
 }
+
+impl PlistSectionItem {
+    /// Section holding the entries of `dictionary`, e.g. objects built with `pbx!`.
+    pub(crate) fn create(name: &str, dictionary: PlistItem) -> PlistItem {
+        PlistItem::SectionItem(PlistSectionItem {
+            name: name.to_string(),
+            items: dictionary.into_entries(),
+        })
+    }
+}
@@ -0,0 +1,320 @@
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::ops::Deref;
+use std::str::Chars;
+
+/// A plist string (key or value) holding its raw, unescaped text.
+///
+/// Quoting is decided when the string is written: Xcode leaves a string bare when it is
+/// non-empty, only uses ASCII letters, digits and `_$/:.`, and contains neither `___` nor `//`.
+/// Everything else is written between double quotes with `\\`, `\"`, `\n`, `\t` and
+/// `\Uxxxx` escapes.
+#[derive(Debug, Clone, Default, Hash, PartialOrd, Ord, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct PlistString(String);
+
+impl PlistString {
+    pub fn new<S: Into<String>>(value: S) -> Self {
+        PlistString(value.into())
+    }
+
+    /// Reads a token as written in a plist file: quoted tokens are unquoted and unescaped,
+    /// bare tokens are taken as is.
+    pub fn from_token(token: &str) -> Self {
+        PlistString(PlistString::token_text(token).into_owned())
+    }
+
+    /// Like `from_token`, but borrows from `token` unless escapes had to be replaced.
+    pub fn token_text(token: &str) -> Cow<'_, str> {
+        match token
+            .strip_prefix('"')
+            .and_then(|token| token.strip_suffix('"'))
+        {
+            Some(quoted) if token.len() >= 2 && quoted.contains('\\') => {
+                Cow::Owned(PlistString::unescape(quoted))
+            }
+            Some(quoted) if token.len() >= 2 => Cow::Borrowed(quoted),
+            _ => Cow::Borrowed(token),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    pub fn needs_quotes(&self) -> bool {
+        self.0.is_empty()
+            || self.0.contains("___")
+            || self.0.contains("//")
+            || !self
+                .0
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_$/:.".contains(c))
+    }
+
+    /// The string as written in a plist file.
+    pub fn to_quoted(&self) -> String {
+        if !self.needs_quotes() {
+            return self.0.clone();
+        }
+        let mut out = String::with_capacity(self.0.len() + 2);
+        out.push('"');
+        for c in self.0.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '"' => out.push_str("\\\""),
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                c if (c as u32) < 0x20 => out.push_str(format!("\\U{:04x}", c as u32).as_str()),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+        out
+    }
+
+    /// Replaces the escapes of a quoted string the way CoreFoundation reads them: the C
+    /// escapes, `\Uxxxx` with up to four hex digits (UTF-16 surrogate pairs are combined) and
+    /// `\ooo` with up to three octal digits. Octal escapes above `\177` stand for NeXTSTEP
+    /// characters and are taken as Latin-1, which agrees with NeXTSTEP on most letters.
+    fn unescape(quoted: &str) -> String {
+        let mut out = String::with_capacity(quoted.len());
+        let mut chars = quoted.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('r') => out.push('\r'),
+                Some('a') => out.push('\u{07}'),
+                Some('b') => out.push('\u{08}'),
+                Some('f') => out.push('\u{0c}'),
+                Some('v') => out.push('\u{0b}'),
+                Some(letter @ ('U' | 'u')) => {
+                    match PlistString::take_digits(&mut chars, 16, 4, 0) {
+                        // without hex digits the letter stands for itself, like unknown escapes
+                        None => out.push(letter),
+                        Some(high @ 0xd800..=0xdbff) => {
+                            let mut rest = chars.clone();
+                            let low = match (rest.next(), rest.next()) {
+                                (Some('\\'), Some('U' | 'u')) => {
+                                    PlistString::take_digits(&mut rest, 16, 4, 0)
+                                }
+                                _ => None,
+                            };
+                            match low {
+                                Some(low @ 0xdc00..=0xdfff) => {
+                                    chars = rest;
+                                    let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                                    out.extend(char::from_u32(code));
+                                }
+                                _ => out.push(char::REPLACEMENT_CHARACTER),
+                            }
+                        }
+                        Some(unit) => {
+                            out.push(char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER))
+                        }
+                    }
+                }
+                Some(digit @ '0'..='7') => {
+                    let first = digit as u32 - '0' as u32;
+                    let code = PlistString::take_digits(&mut chars, 8, 2, first).unwrap_or(first);
+                    out.extend(char::from_u32(code & 0xff));
+                }
+                // `\\`, `\"`, `\'` and unknown escapes stand for the character itself
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+        out
+    }
+
+    /// `value` followed by the next digits in `radix`, at most `max` of them, `None` if there
+    /// is none.
+    fn take_digits(chars: &mut Peekable<Chars>, radix: u32, max: usize, value: u32) -> Option<u32> {
+        let mut taken = None;
+        for _ in 0..max {
+            match chars.peek().and_then(|c| c.to_digit(radix)) {
+                Some(digit) => {
+                    taken = Some(taken.unwrap_or(value) * radix + digit);
+                    chars.next();
+                }
+                None => break,
+            }
+        }
+        taken
+    }
+}
+
+impl Display for PlistString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_quoted())
+    }
+}
+
+impl Deref for PlistString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for PlistString {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for PlistString {
+    fn from(value: &str) -> Self {
+        PlistString(value.to_string())
+    }
+}
+
+impl From<Cow<'_, str>> for PlistString {
+    fn from(value: Cow<'_, str>) -> Self {
+        PlistString(value.into_owned())
+    }
+}
+
+impl From<String> for PlistString {
+    fn from(value: String) -> Self {
+        PlistString(value)
+    }
+}
+
+impl PartialEq<str> for PlistString {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for PlistString {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xcode_leaves_identifier_like_strings_bare() {
+        for bare in [
+            "App",
+            "main.swift",
+            "Sources/main.swift",
+            "$SRCROOT",
+            "_x",
+            "1.0",
+            "a__b",
+            "a:b",
+        ] {
+            assert!(!PlistString::from(bare).needs_quotes(), "{:?}", bare);
+        }
+        for quoted in [
+            "",
+            "a b",
+            "a-b",
+            "https://example.com",
+            "<group>",
+            "$(SRCROOT)",
+            "a___b",
+            "https//x",
+            "caf\u{e9}",
+            "\"",
+        ] {
+            assert!(PlistString::from(quoted).needs_quotes(), "{:?}", quoted);
+        }
+    }
+
+    #[test]
+    fn to_quoted_escapes() {
+        assert_eq!(PlistString::from("App").to_quoted(), "App");
+        assert_eq!(PlistString::from("").to_quoted(), "\"\"");
+        assert_eq!(
+            PlistString::from("a\"b\\c\nd\te\u{1}f\u{e9}").to_quoted(),
+            "\"a\\\"b\\\\c\\nd\\te\\U0001f\u{e9}\""
+        );
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        for text in [
+            "",
+            "App",
+            "com.apple.product-type.framework",
+            "$(inherited) \"$(SRCROOT)/lib\"",
+            "line\nnext\ttab\\slash",
+            "\u{0}\u{7}\u{1b}\u{1f}",
+            "caf\u{e9} \u{1f600}",
+            "// not a comment",
+        ] {
+            let quoted = PlistString::from(text).to_quoted();
+            assert_eq!(PlistString::from_token(&quoted), text, "{}", quoted);
+        }
+    }
+
+    #[test]
+    fn unescape_reads_c_escapes() {
+        assert_eq!(
+            PlistString::from_token(r#""\a\b\f\n\r\t\v\\\"\'""#),
+            "\u{7}\u{8}\u{c}\n\r\t\u{b}\\\"'"
+        );
+        // unknown escapes stand for the character, a trailing backslash for itself
+        assert_eq!(PlistString::from_token(r#""\q\""#), "q\\");
+    }
+
+    #[test]
+    fn unescape_reads_octal_escapes() {
+        assert_eq!(PlistString::from_token(r#""\012""#), "\n");
+        assert_eq!(PlistString::from_token(r#""\101\102""#), "AB");
+        assert_eq!(PlistString::from_token(r#""\0""#), "\u{0}");
+        assert_eq!(PlistString::from_token(r#""\7x""#), "\u{7}x");
+        // at most three digits
+        assert_eq!(PlistString::from_token(r#""\1234""#), "S4");
+        assert_eq!(PlistString::from_token(r#""\351""#), "\u{e9}");
+        assert_eq!(PlistString::from_token(r#""\8""#), "8");
+    }
+
+    #[test]
+    fn unescape_reads_unicode_escapes() {
+        assert_eq!(PlistString::from_token(r#""\U00e9""#), "\u{e9}");
+        assert_eq!(PlistString::from_token(r#""é""#), "\u{e9}");
+        // up to four digits
+        assert_eq!(PlistString::from_token(r#""\U41z""#), "Az");
+        assert_eq!(PlistString::from_token(r#""\U00411""#), "A1");
+        assert_eq!(PlistString::from_token(r#""\UD83D\UDE00""#), "\u{1f600}");
+        assert_eq!(PlistString::from_token(r#""\UD83Dx""#), "\u{fffd}x");
+        assert_eq!(PlistString::from_token(r#""\UDE00""#), "\u{fffd}");
+        // without digits the letter stands for itself
+        assert_eq!(PlistString::from_token(r#""\Uzz""#), "Uzz");
+        assert_eq!(PlistString::from_token(r#""\u""#), "u");
+    }
+
+    #[test]
+    fn tokens_without_escapes_are_borrowed() {
+        assert!(matches!(
+            PlistString::token_text("\"a b\""),
+            Cow::Borrowed("a b")
+        ));
+        assert!(matches!(
+            PlistString::token_text("bare"),
+            Cow::Borrowed("bare")
+        ));
+        assert!(matches!(
+            PlistString::token_text("\"a\\nb\""),
+            Cow::Owned(_)
+        ));
+    }
+}
@@ -1,9 +1,3 @@
-use super::end_reason::*;
-
-pub trait PlistItemCustomDeserializeCompatible {
-    fn from_plist_result(input: &(&str, Option<String>, PlistIResultEndReason)) -> Self;
-}
-
 pub trait XcodePlistSerializer {
     fn serialize(&self) -> String;
 }
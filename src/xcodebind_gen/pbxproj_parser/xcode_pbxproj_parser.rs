@@ -1,276 +1,269 @@
-use nom::{character::complete::multispace0, IResult};
+use std::collections::BTreeMap;
 
-use crate::xcodebind_gen::helpers::*;
 use crate::xcodebind_gen::types::*;
+use super::PlistDocument;
+use crate::xcodebind_gen::helpers::*;
+use crate::xcodebind_gen::{Result, XcodeUniffiError};
 
-#[allow(dead_code)]
-struct StringItemSlice {
-    begin: usize,
-    end: usize,
-    begin_set: bool,
-    end_set: bool,
+/// Parsed `project.pbxproj` file.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct XcodePbxProject {
+    items: Vec<PlistItem>,
 }
 
-impl StringItemSlice {
-    fn new() -> Self {
-        StringItemSlice {
-            begin: 0,
-            end: 0,
-            begin_set: false,
-            end_set: false,
-        }
-    }
-    fn is_set(&self) -> bool {
-        self.begin_set && self.end_set
+impl XcodePbxProject {
+    /// Unlike `PlistDocument::parse`, requires the `// !$*UTF8*$!` header Xcode writes.
+    pub fn parse_from_string(content: &str) -> Result<Self> {
+        content
+            .validate_header()
+            .map_err(|_| XcodeUniffiError::Parse("Header not found".to_string()))?;
+        Ok(PlistDocument::parse(content)?.into_project())
     }
-    fn is_incomplete(&self) -> bool {
-        (self.begin_set != self.end_set) & (self.begin_set | self.end_set)
+
+    pub fn from_items(items: Vec<PlistItem>) -> Self {
+        XcodePbxProject { items }
     }
-    fn set_begin(&mut self, val: usize) {
-        self.begin = val;
-        self.begin_set = true;
+
+    /// Entries of the root dictionary (`archiveVersion`, `objects`, `rootObject`, ...).
+    pub fn items(&self) -> &[PlistItem] {
+        &self.items
     }
-    fn set_end(&mut self, val: usize) {
-        self.end = val;
-        self.end_set = true;
+
+    pub fn items_mut(&mut self) -> &mut Vec<PlistItem> {
+        &mut self.items
     }
-}
 
-pub(crate) struct XcodePbxProject {
-    items: Vec<PlistItem>,
-}
+    pub fn into_items(self) -> Vec<PlistItem> {
+        self.items
+    }
 
-impl XcodePbxProject {
-    pub(crate) fn parse_from_string(content: &str) -> Self {
-        let res = content
-            .validate_header()
-            .expect("Xcode Project plist format is invalid. Header not found");
+    /// Serializes the project in pbxproj text format, with objects grouped and ordered the way
+    /// Xcode writes them (see `group_objects_by_isa` and `sort_objects`) and the comments of
+    /// object IDs derived from the objects (see `annotate`), so Xcode does not rewrite the whole
+    /// file on its first save.
+    pub fn serialize(&self) -> String {
+        let mut sorted = self.clone();
+        sorted.group_objects_by_isa();
+        sorted.sort_objects();
+        sorted.annotate();
+        sorted.items.serialize()
+    }
 
-        let result = XcodePbxProject::parse(res.0).expect("---");
-        println!("Parser:\n{}", result.1.serialize());
-        XcodePbxProject { items: result.1 }
+    /// Serializes the project keeping sections, objects and comments as they are.
+    pub fn serialize_lossless(&self) -> String {
+        self.items.serialize()
     }
 
-    fn parse(input: &str) -> IResult<&str, Vec<PlistItem>> {
-        let mut input_ref = input;
-        let mut max_iter = 0;
-        let mut plist_keys_at_array_depth: Vec<Box<PlistKey>> = vec![];
-        let mut plist_values_at_array_depth: Vec<Vec<PlistItem>> = vec![vec![]];
-        let mut array_depth: usize = 0;
-        plist_values_at_array_depth[array_depth] = vec![];
-        loop {
-            let item = XcodePbxProject::get_item_from_plist(input_ref)?;
-            input_ref = item.0;
-            match &item.1 .2 {
-                PlistIResultEndReason::KeyEnd => {
-                    let res = PlistKey::from_plist_result(&item.1);
-                    plist_keys_at_array_depth.push(Box::new(res));
+    /// Puts every entry of `objects` in the section of its `isa`, the way Xcode groups them.
+    /// Entries without `isa` stay outside sections.
+    pub fn group_objects_by_isa(&mut self) {
+        for item in self.items.iter_mut() {
+            let objects = match item {
+                PlistItem::Item(kv) if kv.key.name == "objects" => match kv.value.as_mut() {
+                    PlistItem::Array(objects) => objects,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let mut sections: BTreeMap<String, Vec<PlistItem>> = BTreeMap::new();
+            let mut grouped = vec![];
+            for object in std::mem::take(objects).into_iter().flat_map(|object| match object {
+                PlistItem::SectionItem(section) => section.items,
+                object => vec![object],
+            }) {
+                match object.isa() {
+                    Some(isa) => sections.entry(isa.to_string()).or_default().push(object),
+                    None => grouped.push(object),
                 }
-                PlistIResultEndReason::ValueEnd => {
-                    let res = PlistValue::from_plist_result(&item.1);
-                    if let Some(item_key) = plist_keys_at_array_depth.pop() {
-                        let key_val_item = PlistItem::Item(PlistKeyValueItem {
-                            key: item_key,
-                            value: Box::new(PlistItem::Value(res)),
-                        });
-                        plist_values_at_array_depth[array_depth].push(key_val_item);
+            }
+            grouped.extend(sections.into_iter().map(|(name, items)| {
+                PlistItem::SectionItem(PlistSectionItem { name, items })
+            }));
+            *objects = grouped;
+        }
+    }
+
+    /// Orders the project the way Xcode writes it: dictionary keys alphabetically with `isa`
+    /// first, `objects` sections by `isa` and the objects of a section by ID.
+    /// Array elements keep their order, it is meaningful (e.g. group children).
+    pub fn sort_objects(&mut self) {
+        XcodePbxProject::sort_dictionary(&mut self.items);
+    }
+
+    fn sort_dictionary(items: &mut [PlistItem]) {
+        items.sort_by(|a, b| {
+            XcodePbxProject::dictionary_sort_key(a).cmp(&XcodePbxProject::dictionary_sort_key(b))
+        });
+        for item in items.iter_mut() {
+            match item {
+                PlistItem::Item(kv) => {
+                    if let PlistItem::Array(items) = kv.value.as_mut() {
+                        XcodePbxProject::sort_dictionary(items);
                     }
                 }
-                PlistIResultEndReason::ArrayBegin => {
-                    array_depth += 1;
-                    plist_values_at_array_depth.push(vec![]);
-                }
-                PlistIResultEndReason::ArrayEnd => {
-                    let values = plist_values_at_array_depth[array_depth].remove_all();
-                    array_depth -= 1;
-                    let item = PlistItem::Item(PlistKeyValueItem {
-                        key: plist_keys_at_array_depth
-                            .pop()
-                            .expect("Xcode project Plist file is invalid"),
-                        value: Box::new(PlistItem::Array(values)),
-                    });
-                    plist_values_at_array_depth[array_depth].push(item);
-                }
-                PlistIResultEndReason::TupleBegin => {
-                    array_depth += 1;
-                    plist_values_at_array_depth.push(vec![]);
-                }
-                PlistIResultEndReason::TupleValueEnd => {
-                    let res = PlistTupleValue::from_plist_result(&item.1);
-                    plist_values_at_array_depth[array_depth].push(PlistItem::TupleValue(res));
-                }
-                PlistIResultEndReason::TupleEnd => {
-                    let all_tuples = plist_values_at_array_depth[array_depth].remove_all();
-                    array_depth -= 1;
-                    let item = PlistItem::TupleItem(PlistTupleItem {
-                        name: plist_keys_at_array_depth
-                            .pop()
-                            .unwrap_or_else(|| panic!("Xcode project Plist file is invalid: \n{:?}", item) )
-                            .name,
-                        items: all_tuples,
-                    });
-                    plist_values_at_array_depth[array_depth].push(item);
-                }
-                // Sections are treated like an array but without initial key fetch.
-                PlistIResultEndReason::SectionBegin => {
-                    let res = PlistKey::from_plist_result(&item.1);
-                    plist_keys_at_array_depth.push(Box::new(res));
-                    array_depth += 1;
-                    plist_values_at_array_depth.push(vec![]);
-                }
-                PlistIResultEndReason::SectionEnd => {
-                    let values = plist_values_at_array_depth[array_depth].remove_all();
-                    array_depth -= 1;
-                    let item = PlistItem::SectionItem(PlistSectionItem {
-                        name: plist_keys_at_array_depth
-                            .pop()
-                            .expect("Xcode project Plist file is invalid")
-                            .name,
-                        items: values,
-                    });
-                    plist_values_at_array_depth[array_depth].push(item);
-                }
-                PlistIResultEndReason::Eof => {
-                    break;
+                PlistItem::SectionItem(section) => {
+                    XcodePbxProject::sort_dictionary(&mut section.items)
                 }
-                _ => panic!("Unhandled type"),
-            }
-
-            max_iter += 1;
-            if input_ref.len() <= 1 && max_iter < 5000 {
-                break;
+                PlistItem::TupleItem(tuple) => XcodePbxProject::sort_elements(&mut tuple.items),
+                _ => {}
             }
         }
-        Ok(("", plist_values_at_array_depth[0].remove_all()))
     }
 
-    fn get_item_from_plist(
-        input: &str,
-    ) -> IResult<&str, (&str, Option<String>, PlistIResultEndReason)> {
-        let non_ws = multispace0(input)?;
-        let input_ref = non_ws.0;
-
-        if let Ok(section_res) = input_ref.get_section_name(false) {
-            return Ok((
-                section_res.0,
-                (section_res.1, None, PlistIResultEndReason::SectionBegin),
-            ));
-        } else if let Ok(section_res) = input_ref.get_section_name(true) {
-            return Ok((
-                section_res.0,
-                (section_res.1, None, PlistIResultEndReason::SectionEnd),
-            ));
+    /// Sorts the dictionaries found in an array, leaving the elements in place.
+    fn sort_elements(items: &mut [PlistItem]) {
+        for item in items.iter_mut() {
+            match item {
+                PlistItem::Array(items) => XcodePbxProject::sort_dictionary(items),
+                PlistItem::Tuple(items) => XcodePbxProject::sort_elements(items),
+                _ => {}
+            }
         }
+    }
 
-        let mut is_inside_quote = false;
-        let mut is_parsing_comment = 0;
-        let mut comment_begin_end: StringItemSlice = StringItemSlice::new();
-        let mut value_begin_end: StringItemSlice = StringItemSlice::new();
-        for (i, c) in input_ref.chars().enumerate() {
-            if !is_inside_quote
-                && is_parsing_comment < 2
-                && (c == '\t'
-                    || c == '\r'
-                    || c == '\n'
-                    || c == ';'
-                    || c == ','
-                    || c == '{'
-                    || c == '}'
-                    || c == '('
-                    || c == ')'
-                    || c == '=')
-            {
-                let mut current_index = i;
-                // Form Comment
-                let comment_str = if comment_begin_end.is_set() || comment_begin_end.is_incomplete()
-                {
-                    Some(
-                        input_ref[comment_begin_end.begin..comment_begin_end.end]
-                            .trim()
-                            .to_string(),
-                    )
-                } else {
-                    None
-                };
+    /// Keys first (`isa` before the others), then sections by name.
+    /// Anything else keeps its position after them.
+    fn dictionary_sort_key(item: &PlistItem) -> (u8, bool, &str) {
+        let key = match item {
+            PlistItem::Item(kv) => kv.key.name.as_str(),
+            PlistItem::TupleItem(tuple) => tuple.name.as_str(),
+            PlistItem::SectionItem(section) => return (1, false, section.name.as_str()),
+            _ => return (2, false, ""),
+        };
+        (0, key != "isa", key)
+    }
 
-                let value_str = if value_begin_end.is_set() {
-                    &input_ref[value_begin_end.begin..value_begin_end.end]
-                } else if value_begin_end.is_incomplete() {
-                    &input_ref[value_begin_end.begin..current_index]
-                } else {
-                    ""
-                };
+    /// Every `key = value;` pair with a scalar value, depth first, unquoted.
+    pub fn leaf_values(&self) -> Vec<(&str, &str)> {
+        let mut values = vec![];
+        XcodePbxProject::collect_leaf_values(&self.items, &mut values);
+        values
+    }
 
-                let mut end_reason = PlistIResultEndReason::new(c);
-                if c == '}' {
-                    if input_ref.chars().nth(current_index + 1) == Some(';') {
-                        current_index += 1;
-                        end_reason = PlistIResultEndReason::ArrayEnd;
+    fn collect_leaf_values<'a>(items: &'a [PlistItem], values: &mut Vec<(&'a str, &'a str)>) {
+        for item in items {
+            match item {
+                PlistItem::Item(item) => match item.value.as_ref() {
+                    PlistItem::Value(value) => {
+                        values.push((item.key.name.as_str(), value.value.as_str()))
+                    }
+                    value => {
+                        XcodePbxProject::collect_leaf_values(std::slice::from_ref(value), values)
                     }
-                } else if c == ')' && input_ref.chars().nth(current_index + 1) == Some(';') {
-                    current_index += 1;
-                    end_reason = PlistIResultEndReason::TupleEnd;
+                },
+                PlistItem::SectionItem(section) => {
+                    XcodePbxProject::collect_leaf_values(&section.items, values)
                 }
-                return Ok((
-                    &input_ref[current_index + 1..],
-                    (value_str, comment_str, end_reason),
-                ));
-            }
-
-            if is_inside_quote && is_parsing_comment < 2 {
-                if is_inside_quote && c == '"' && input_ref.chars().nth(i - 1) != Some('\\') {
-                    is_inside_quote = false;
+                PlistItem::TupleItem(tuple) => {
+                    XcodePbxProject::collect_leaf_values(&tuple.items, values)
                 }
-                continue;
+                PlistItem::Array(items) | PlistItem::Tuple(items) => {
+                    XcodePbxProject::collect_leaf_values(items, values)
+                }
+                PlistItem::Value(_) | PlistItem::TupleValue(_) | PlistItem::Data(_) => {}
             }
+        }
+    }
+}
 
-            if !is_inside_quote && is_parsing_comment == 2 {
-                if !comment_begin_end.begin_set && c != ' ' {
-                    comment_begin_end.set_begin(i);
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
 
-                if c == '*' && input_ref.chars().nth(i + 1) == Some('/') {
-                    is_parsing_comment = 0;
-                    comment_begin_end.set_end(i - 1);
-                }
-                continue;
-            }
+    fn read(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("test_data")
+            .join(name);
+        std::fs::read_to_string(path).unwrap()
+    }
 
-            // Ignore whitespaces
-            if !value_begin_end.begin_set && c == ' ' {
-                continue;
-            } else if value_begin_end.is_incomplete() && (c == ' ') {
-                value_begin_end.set_end(i);
-            }
+    #[test]
+    fn xcode_written_projects_serialize_to_the_same_bytes() {
+        for name in ["project.pbxproj", "proj_1.pbxproj"] {
+            let content = read(name);
+            let project = XcodePbxProject::parse_from_string(&content).unwrap();
+            assert!(project.serialize() == content, "{} changed", name);
+            assert!(project.serialize_lossless() == content, "{} changed", name);
+        }
+    }
 
-            // Detect start of a comment (if the comment has no white-space after the value)
-            if is_parsing_comment == 0 && c == '/' {
-                is_parsing_comment = 1;
-            } else if is_parsing_comment == 1 && c == '*' {
-                // If comment and value have no spaces between
-                if !value_begin_end.end_set {
-                    value_begin_end.set_end(i - 1);
+    /// Reverses the entries of every dictionary and takes the objects out of their sections.
+    fn shuffle(items: &mut Vec<PlistItem>) {
+        *items = std::mem::take(items)
+            .into_iter()
+            .rev()
+            .flat_map(|item| match item {
+                PlistItem::SectionItem(section) => section.items.into_iter().rev().collect(),
+                item => vec![item],
+            })
+            .collect();
+        for item in items.iter_mut() {
+            if let PlistItem::Item(kv) = item {
+                if let PlistItem::Array(items) = kv.value.as_mut() {
+                    shuffle(items);
                 }
-                is_parsing_comment = 2;
-            } else if is_parsing_comment == 1 && c != '*' {
-                is_parsing_comment = 0;
             }
+        }
+    }
 
-            if !value_begin_end.begin_set {
-                value_begin_end.set_begin(i);
-            }
+    #[test]
+    fn shuffled_projects_serialize_in_xcode_order() {
+        for name in ["project.pbxproj", "proj_1.pbxproj"] {
+            let content = read(name);
+            let mut project = XcodePbxProject::parse_from_string(&content).unwrap();
+            shuffle(project.items_mut());
+            let shuffled = project.serialize_lossless();
+            assert!(shuffled != content);
+            assert!(!shuffled.contains("/* Begin "));
 
-            // Quote detection
-            if !is_inside_quote && c == '"' {
-                if i > 0 && input_ref.chars().nth(i - 1) == Some('\\') {
-                    is_inside_quote = false;
-                } else {
-                    is_inside_quote = true;
-                }
-            }
+            let project = XcodePbxProject::parse_from_string(&shuffled).unwrap();
+            assert!(project.serialize() == content, "{}", name);
+
+            let mut sorted = project.clone();
+            sorted.group_objects_by_isa();
+            sorted.sort_objects();
+            assert!(sorted.serialize_lossless() == content, "{}", name);
         }
-        Ok((input_ref, ("", None, PlistIResultEndReason::Eof)))
     }
 
-    pub(crate) fn create(_project_name: String) {}
+    #[test]
+    fn lossless_serialization_keeps_the_parsed_order() {
+        let content = concat!(
+            "// !$*UTF8*$!\n",
+            "{\n",
+            "\tobjects = {\n",
+            "\n",
+            "/* Begin PBXGroup section */\n",
+            "\t\tG = {\n",
+            "\t\t\tsourceTree = \"<group>\";\n",
+            "\t\t\tisa = PBXGroup;\n",
+            "\t\t\tchildren = (\n",
+            "\t\t\t\tB /* b.swift */,\n",
+            "\t\t\t\tA /* a.swift */,\n",
+            "\t\t\t);\n",
+            "\t\t};\n",
+            "/* End PBXGroup section */\n",
+            "\n",
+            "/* Begin PBXFileReference section */\n",
+            "\t\tB = {isa = PBXFileReference; path = b.swift; sourceTree = \"<group>\"; };\n",
+            "\t\tA = {isa = PBXFileReference; path = a.swift; sourceTree = \"<group>\"; };\n",
+            "/* End PBXFileReference section */\n",
+            "\t};\n",
+            "\trootObject = G;\n",
+            "\tarchiveVersion = 1;\n",
+            "}\n",
+        );
+        let project = XcodePbxProject::parse_from_string(content).unwrap();
+        assert_eq!(project.serialize_lossless(), content);
+
+        let serialized = project.serialize();
+        let position = |text: &str| serialized.find(text).unwrap();
+        assert!(position("archiveVersion") < position("objects"));
+        assert!(position("objects") < position("rootObject"));
+        assert!(position("PBXFileReference section") < position("PBXGroup section"));
+        assert!(position("A /* a.swift */ = {") < position("B /* b.swift */ = {"));
+        // group children keep their order
+        assert!(position("B /* b.swift */,") < position("A /* a.swift */,"));
+    }
 }
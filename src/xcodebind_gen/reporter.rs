@@ -0,0 +1,296 @@
+use super::{
+    CheckStatus, DoctorCheck, LintDiagnostic, MergeConflict, ProjectChange, Severity,
+    XcodeUniffiError,
+};
+use colored::Colorize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Output format selected with `--message-format`.
+#[derive(Debug, Clone, Copy, std::cmp::Eq, std::cmp::PartialEq)]
+pub enum MessageFormat {
+    /// Colored, human readable messages.
+    Human,
+    /// One JSON object per line, each tagged with a `reason` field.
+    Json,
+}
+
+impl MessageFormat {
+    pub const VALUES: [&'static str; 2] = ["human", "json"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "human" => Some(MessageFormat::Human),
+            "json" => Some(MessageFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Emits progress events either as human text or as structured JSON lines, on stdout unless
+/// `to_stderr` is set. Human warnings and errors always go to stderr.
+#[derive(Debug, Clone, Copy)]
+pub struct Reporter {
+    format: MessageFormat,
+    stderr: bool,
+}
+
+impl Reporter {
+    pub fn new(format: MessageFormat) -> Self {
+        Reporter {
+            format,
+            stderr: false,
+        }
+    }
+
+    /// Writes every event to stderr, for commands whose stdout carries their result, e.g. a
+    /// project written with `--output -`.
+    pub fn to_stderr(mut self, to_stderr: bool) -> Self {
+        self.stderr = to_stderr;
+        self
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.format == MessageFormat::Json
+    }
+
+    pub fn directory_created(&self, path: &Path) {
+        match self.format {
+            MessageFormat::Human => self.print(format!("{} {:?}", "  Created".green(), path)),
+            MessageFormat::Json => {
+                self.emit(json!({ "reason": "directory-created", "path": path }))
+            }
+        }
+    }
+
+    pub fn file_written(&self, path: &Path) {
+        match self.format {
+            MessageFormat::Human => self.print(format!("{} {:?}", "    Wrote".green(), path)),
+            MessageFormat::Json => self.emit(json!({ "reason": "file-written", "path": path })),
+        }
+    }
+
+    /// Change a dry run would make to `path`, as a unified diff.
+    pub fn file_diff(&self, path: &Path, diff: &str) {
+        match self.format {
+            MessageFormat::Human => {
+                for line in diff.lines() {
+                    if line.starts_with("+++") || line.starts_with("---") {
+                        self.print(line.bold());
+                    } else if line.starts_with('+') {
+                        self.print(line.green());
+                    } else if line.starts_with('-') {
+                        self.print(line.red());
+                    } else if line.starts_with("@@") {
+                        self.print(line.cyan());
+                    } else {
+                        self.print(line);
+                    }
+                }
+            }
+            MessageFormat::Json => self.emit(json!({
+                "reason": "file-diff",
+                "path": path,
+                "diff": diff,
+            })),
+        }
+    }
+
+    /// Objects are only reported in JSON mode, human output prints a summary instead.
+    pub fn object_added(&self, id: &str, isa: &str, comment: Option<&str>) {
+        if self.is_json() {
+            self.emit(json!({
+                "reason": "object-added",
+                "id": id,
+                "isa": isa,
+                "comment": comment,
+            }));
+        }
+    }
+
+    pub fn doctor_check(&self, check: &DoctorCheck) {
+        match self.format {
+            MessageFormat::Human => {
+                let status = match check.status {
+                    CheckStatus::Pass => "[PASS]".green(),
+                    CheckStatus::Warn => "[WARN]".yellow(),
+                    CheckStatus::Fail => "[FAIL]".red(),
+                };
+                self.print(format!(
+                    "{} {}: {}",
+                    status.bold(),
+                    check.name,
+                    check.message
+                ));
+                if let Some(remedy) = &check.remedy {
+                    self.print(format!("       {} {}", "help:".cyan(), remedy));
+                }
+            }
+            MessageFormat::Json => self.emit(json!({
+                "reason": "doctor-check",
+                "name": check.name,
+                "status": check.status.as_str(),
+                "message": check.message,
+                "remedy": check.remedy,
+            })),
+        }
+    }
+
+    pub fn lint_diagnostic(&self, path: &Path, diagnostic: &LintDiagnostic) {
+        match self.format {
+            MessageFormat::Human => {
+                let label = format!("{}[{}]:", diagnostic.severity.as_str(), diagnostic.code);
+                let label = match diagnostic.severity {
+                    Severity::Error => label.red(),
+                    Severity::Warning => label.yellow(),
+                };
+                self.print(format!(
+                    "{} {}: {}",
+                    label.bold(),
+                    path.display(),
+                    diagnostic.message
+                ));
+            }
+            MessageFormat::Json => self.emit(json!({
+                "reason": "lint-diagnostic",
+                "path": path,
+                "code": diagnostic.code,
+                "severity": diagnostic.severity.as_str(),
+                "object": diagnostic.object,
+                "message": diagnostic.message,
+            })),
+        }
+    }
+
+    pub fn merge_conflict(&self, conflict: &MergeConflict) {
+        match self.format {
+            MessageFormat::Human => {
+                let side = |value: &Option<String>| match value {
+                    Some(value) => value.clone(),
+                    None => "(removed)".to_string(),
+                };
+                eprintln!("{} {}", "conflict:".red().bold(), conflict.path);
+                eprintln!("    base:   {}", side(&conflict.base));
+                eprintln!("    ours:   {}", side(&conflict.ours));
+                eprintln!("    theirs: {}", side(&conflict.theirs));
+            }
+            MessageFormat::Json => self.emit(json!({
+                "reason": "merge-conflict",
+                "path": conflict.path,
+                "base": conflict.base,
+                "ours": conflict.ours,
+                "theirs": conflict.theirs,
+            })),
+        }
+    }
+
+    pub fn project_change(&self, change: &ProjectChange) {
+        match self.format {
+            MessageFormat::Human => {
+                // empty values and values naming the subject itself, e.g. of `target-added`,
+                // are left out
+                let value = |value: &String| {
+                    if value.is_empty() || change.subject.rsplit('/').next() == Some(value) {
+                        String::new()
+                    } else {
+                        format!(": {}", value)
+                    }
+                };
+                let line = match (&change.old, &change.new) {
+                    (Some(old), Some(new)) => {
+                        format!("~ {} {}: {} -> {}", change.kind, change.subject, old, new)
+                            .yellow()
+                    }
+                    (None, Some(new)) => {
+                        format!("+ {} {}{}", change.kind, change.subject, value(new)).green()
+                    }
+                    (Some(old), None) => {
+                        format!("- {} {}{}", change.kind, change.subject, value(old)).red()
+                    }
+                    (None, None) => format!("  {} {}", change.kind, change.subject).normal(),
+                };
+                self.print(line);
+            }
+            MessageFormat::Json => self.emit(json!({
+                "reason": "project-change",
+                "kind": change.kind,
+                "subject": change.subject,
+                "old": change.old,
+                "new": change.new,
+            })),
+        }
+    }
+
+    /// Evaluated build settings, printed like `xcodebuild -showBuildSettings` does.
+    pub fn build_settings(
+        &self,
+        target: Option<&str>,
+        configuration: &str,
+        settings: &BTreeMap<String, String>,
+    ) {
+        match self.format {
+            MessageFormat::Human => {
+                let owner = match target {
+                    Some(target) => format!("target {}", target),
+                    None => "the project".to_string(),
+                };
+                self.print(format!("Build settings for {} ({}):", owner, configuration).bold());
+                for (name, value) in settings {
+                    self.print(format!("    {} = {}", name, value));
+                }
+            }
+            MessageFormat::Json => self.emit(json!({
+                "reason": "build-settings",
+                "target": target,
+                "configuration": configuration,
+                "settings": settings,
+            })),
+        }
+    }
+
+    pub fn info(&self, message: &str) {
+        match self.format {
+            MessageFormat::Human => self.print(message),
+            MessageFormat::Json => self.emit(json!({ "reason": "info", "message": message })),
+        }
+    }
+
+    pub fn warning(&self, message: &str) {
+        match self.format {
+            MessageFormat::Human => eprintln!("{} {}", "warning:".yellow().bold(), message),
+            MessageFormat::Json => self.emit(json!({ "reason": "warning", "message": message })),
+        }
+    }
+
+    pub fn error(&self, message: &str) {
+        match self.format {
+            MessageFormat::Human => eprintln!("{} {}", "error:".red().bold(), message),
+            MessageFormat::Json => self.emit(json!({ "reason": "error", "message": message })),
+        }
+    }
+
+    /// Reports an error returned by the library, JSON output carries `XcodeUniffiError::kind`.
+    pub fn failure(&self, error: &XcodeUniffiError) {
+        match self.format {
+            MessageFormat::Human => self.error(error.to_string().as_str()),
+            MessageFormat::Json => self.emit(json!({
+                "reason": "error",
+                "kind": error.kind(),
+                "message": error.to_string(),
+            })),
+        }
+    }
+
+    fn emit(&self, value: serde_json::Value) {
+        self.print(value);
+    }
+
+    fn print(&self, line: impl std::fmt::Display) {
+        if self.stderr {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+}
@@ -1,13 +1,12 @@
 use crate::CargoPackage;
 use convert_case::{Case, Casing};
-use chrono::Local;
 
 pub(crate) struct SourceFileGenerator {
 }
 
 impl SourceFileGenerator {
 
-    pub(crate) fn create_objc_header(package: &CargoPackage, is_macos: bool) -> String {
+    pub(crate) fn create_objc_header(package: &CargoPackage, is_macos: bool, generated_on: Option<&str>) -> String {
         let mut content = String::new();
         let ext = if is_macos { "_macos" } else { "_ios" };
         content.push_str("//\n");
@@ -17,8 +16,9 @@ impl SourceFileGenerator {
         content.push_str(format!("//  Generated by {} - {}\n",
                                  clap::crate_name!().to_case(Case::Pascal),
                                  clap::crate_version!()).as_str());
-        content.push_str(format!("//   on {} \n",
-                                 Local::now()).as_str());
+        if let Some(generated_on) = generated_on {
+            content.push_str(format!("//   on {} \n", generated_on).as_str());
+        }
         content.push_str("//\n\n");
 
         content.push_str("#import <Foundation/Foundation.h>\n\n\n");
@@ -45,7 +45,7 @@ impl SourceFileGenerator {
         content
     }
 
-    pub(crate) fn create_swift_file(package: &CargoPackage, is_macos: bool) -> String {
+    pub(crate) fn create_swift_file(package: &CargoPackage, is_macos: bool, generated_on: Option<&str>) -> String {
         let mut content = String::new();
         let ext = if is_macos { "_macos" } else { "_ios" };
         content.push_str("//\n");
@@ -55,8 +55,9 @@ impl SourceFileGenerator {
         content.push_str(format!("//  Generated by {} - {}\n",
                                  clap::crate_name!().to_case(Case::Pascal),
                                  clap::crate_version!()).as_str());
-        content.push_str(format!("//   on {} \n",
-                                 Local::now()).as_str());
+        if let Some(generated_on) = generated_on {
+            content.push_str(format!("//   on {} \n", generated_on).as_str());
+        }
         content.push_str("//\n\n");
 
         content.push_str("import XCTest\n");
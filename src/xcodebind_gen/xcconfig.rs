@@ -0,0 +1,551 @@
+//! Reader and writer of `.xcconfig` build configuration files, as referenced by the
+//! `baseConfigurationReference` of build configurations.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::{FileSystem, Result, XcodeUniffiError};
+use crate::xcodebind_gen::types::*;
+
+/// Includes nested deeper than this are taken for a cycle.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// `NAME[sdk=iphoneos*][arch=arm64] = value` assignment, from an xcconfig file or the
+/// `buildSettings` of a build configuration.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct BuildSetting {
+    pub name: String,
+    /// `(sdk, iphoneos*)` for `[sdk=iphoneos*]`.
+    pub conditions: Vec<(String, String)>,
+    /// Value as written, `$(inherited)` and `$(VAR)` references unevaluated.
+    pub value: String,
+}
+
+impl BuildSetting {
+    /// Splits `NAME[condition=value]...` into the name and its conditions.
+    pub fn parse_key(key: &str) -> Option<(String, Vec<(String, String)>)> {
+        let (name, mut rest) = match key.find('[') {
+            Some(index) => (&key[..index], &key[index..]),
+            None => (key, ""),
+        };
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return None;
+        }
+        let mut conditions = vec![];
+        while let Some(condition) = rest.strip_prefix('[') {
+            let end = condition.find(']')?;
+            let (variable, value) = condition[..end].split_once('=')?;
+            conditions.push((variable.trim().to_string(), value.trim().to_string()));
+            rest = condition[end + 1..].trim_start();
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+        Some((name.to_string(), conditions))
+    }
+
+    /// `NAME[condition=value]...`, the key of the setting in xcconfig files and `buildSettings`.
+    pub fn key(&self) -> String {
+        let mut key = self.name.clone();
+        for (variable, value) in &self.conditions {
+            key.push_str(&format!("[{}={}]", variable, value));
+        }
+        key
+    }
+}
+
+/// Line of an xcconfig file. `comment` is the text following `//`, kept as written.
+#[derive(Debug, Clone, std::cmp::Eq, std::cmp::PartialEq)]
+pub enum XcconfigLine {
+    /// Empty line, or a line holding only a comment.
+    Comment(Option<String>),
+    /// `#include "path"`, or `#include? "path"` when a missing file is not an error.
+    Include {
+        path: String,
+        optional: bool,
+        comment: Option<String>,
+    },
+    Setting {
+        setting: BuildSetting,
+        comment: Option<String>,
+    },
+}
+
+/// Parsed xcconfig file. Serializing it gives back the lines in their order, comments and
+/// blank lines included; only the spacing around `=` is normalized.
+///
+/// ```
+/// let mut xcconfig = xcode_uniffi::Xcconfig::parse(
+///     "// Shared settings\n#include \"Base.xcconfig\"\nOTHER_LDFLAGS = $(inherited) -lmy_lib\n",
+/// )?;
+/// xcconfig.set("LIBRARY_SEARCH_PATHS[sdk=iphonesimulator*]", "$(SRCROOT)/target/ios_sim")?;
+/// assert_eq!(xcconfig.get("OTHER_LDFLAGS"), Some("$(inherited) -lmy_lib"));
+/// assert_eq!(
+///     xcconfig.to_string(),
+///     "// Shared settings\n#include \"Base.xcconfig\"\nOTHER_LDFLAGS = $(inherited) -lmy_lib\n\
+///      LIBRARY_SEARCH_PATHS[sdk=iphonesimulator*] = $(SRCROOT)/target/ios_sim\n"
+/// );
+/// # Ok::<(), xcode_uniffi::XcodeUniffiError>(())
+/// ```
+#[derive(Debug, Clone, Default, std::cmp::Eq, std::cmp::PartialEq)]
+pub struct Xcconfig {
+    pub lines: Vec<XcconfigLine>,
+}
+
+impl Xcconfig {
+    pub fn parse(text: &str) -> Result<Xcconfig> {
+        parse_lines(text, |number| format!("line {}", number))
+    }
+
+    /// Settings of the `buildSettings` dictionary of a build configuration, one per line in
+    /// the dictionary order. Array elements are separated by spaces.
+    pub fn from_build_settings(build_settings: &PlistRef) -> Xcconfig {
+        let lines = build_settings
+            .as_dictionary()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|entry| {
+                let (name, conditions) = BuildSetting::parse_key(&entry.key)?;
+                Some(XcconfigLine::Setting {
+                    setting: BuildSetting {
+                        name,
+                        conditions,
+                        value: setting_value(&entry.value),
+                    },
+                    comment: None,
+                })
+            })
+            .collect();
+        Xcconfig { lines }
+    }
+
+    pub fn settings(&self) -> impl Iterator<Item = &BuildSetting> {
+        self.lines.iter().filter_map(|line| match line {
+            XcconfigLine::Setting { setting, .. } => Some(setting),
+            _ => None,
+        })
+    }
+
+    /// Paths of the `#include`s and whether they are optional, in file order.
+    pub fn includes(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.lines.iter().filter_map(|line| match line {
+            XcconfigLine::Include { path, optional, .. } => Some((path.as_str(), *optional)),
+            _ => None,
+        })
+    }
+
+    /// Value of the last assignment of `key` in this file, `NAME[sdk=...]` for a conditional
+    /// one. Includes are not followed and references are not evaluated.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let (name, conditions) = BuildSetting::parse_key(key)?;
+        self.settings()
+            .filter(|setting| setting.name == name && setting.conditions == conditions)
+            .last()
+            .map(|setting| setting.value.as_str())
+    }
+
+    /// Sets the value of the last assignment of `key`, keeping its comment, or adds one at
+    /// the end of the file. `key` must be a setting name, optionally with conditions.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let (name, conditions) = BuildSetting::parse_key(key).ok_or_else(|| {
+            XcodeUniffiError::Xcconfig(format!("{:?} is not a build setting name", key))
+        })?;
+        let existing = self.lines.iter_mut().rev().find_map(|line| match line {
+            XcconfigLine::Setting { setting, .. }
+                if setting.name == name && setting.conditions == conditions =>
+            {
+                Some(setting)
+            }
+            _ => None,
+        });
+        match existing {
+            Some(setting) => setting.value = value.to_string(),
+            None => self.lines.push(XcconfigLine::Setting {
+                setting: BuildSetting {
+                    name,
+                    conditions,
+                    value: value.to_string(),
+                },
+                comment: None,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Removes every assignment of `key`, returns whether there was one.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let (name, conditions) = match BuildSetting::parse_key(key) {
+            Some(key) => key,
+            None => return false,
+        };
+        let count = self.lines.len();
+        self.lines.retain(|line| {
+            !matches!(line, XcconfigLine::Setting { setting, .. }
+                if setting.name == name && setting.conditions == conditions)
+        });
+        self.lines.len() != count
+    }
+}
+
+impl fmt::Display for Xcconfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            let (text, comment) = match line {
+                XcconfigLine::Comment(comment) => (String::new(), comment),
+                XcconfigLine::Include {
+                    path,
+                    optional,
+                    comment,
+                } => (
+                    format!("#include{} \"{}\"", if *optional { "?" } else { "" }, path),
+                    comment,
+                ),
+                // `$()` expands to nothing, so `/$()/` keeps a `//` in a value from starting a comment
+                XcconfigLine::Setting { setting, comment } => (
+                    format!(
+                        "{} = {}",
+                        setting.key(),
+                        setting.value.replace("//", "/$()/")
+                    ),
+                    comment,
+                ),
+            };
+            let line = match comment {
+                Some(comment) if text.is_empty() => format!("//{}", comment),
+                Some(comment) => format!("{} //{}", text, comment),
+                None => text,
+            };
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Text of a `buildSettings` value, array elements separated by spaces like in xcconfig files.
+pub(crate) fn setting_value(value: &PlistRef) -> String {
+    match value {
+        PlistRef::Array(elements) => elements
+            .iter()
+            .filter_map(PlistRef::as_str)
+            .map(|element| match element.contains(char::is_whitespace) {
+                true => format!("\"{}\"", element),
+                false => element.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        value => value.as_str().unwrap_or_default().to_string(),
+    }
+}
+
+/// `location` names the line (1-based) in error messages.
+fn parse_lines(text: &str, location: impl Fn(usize) -> String) -> Result<Xcconfig> {
+    let invalid = |number: usize, message: &str| {
+        XcodeUniffiError::Xcconfig(format!("{}: {}", location(number + 1), message))
+    };
+    let mut lines = vec![];
+    for (number, line) in text.lines().enumerate() {
+        // `//` starts a comment anywhere, even in a value (`$()` is the usual escape in URLs)
+        let (line, comment) = match line.split_once("//") {
+            Some((line, comment)) => (line.trim(), Some(comment.to_string())),
+            None => (line.trim(), None),
+        };
+        if line.is_empty() {
+            lines.push(XcconfigLine::Comment(comment));
+            continue;
+        }
+        if let Some(include) = line.strip_prefix("#include") {
+            let (optional, include) = match include.strip_prefix('?') {
+                Some(include) => (true, include),
+                None => (false, include),
+            };
+            let path = include
+                .trim()
+                .strip_prefix('"')
+                .and_then(|include| include.strip_suffix('"'))
+                .ok_or_else(|| invalid(number, "expected #include \"file.xcconfig\""))?;
+            lines.push(XcconfigLine::Include {
+                path: path.to_string(),
+                optional,
+                comment,
+            });
+            continue;
+        }
+        let (key, value) =
+            assignment(line).ok_or_else(|| invalid(number, "expected NAME = value"))?;
+        let (name, conditions) = BuildSetting::parse_key(key.trim())
+            .ok_or_else(|| invalid(number, "invalid setting name"))?;
+        let value = value.trim();
+        lines.push(XcconfigLine::Setting {
+            setting: BuildSetting {
+                name,
+                conditions,
+                value: value
+                    .strip_suffix(';')
+                    .unwrap_or(value)
+                    .trim_end()
+                    .to_string(),
+            },
+            comment,
+        });
+    }
+    Ok(Xcconfig { lines })
+}
+
+/// Assignments of the xcconfig file at `path`, with the ones of its `#include`s in place.
+/// `#include?` of a missing file is skipped.
+pub(crate) fn read_xcconfig(
+    file_system: &dyn FileSystem,
+    path: &Path,
+) -> Result<Vec<BuildSetting>> {
+    let mut settings = vec![];
+    read_into(file_system, path, 0, &mut settings)?;
+    Ok(settings)
+}
+
+fn read_into(
+    file_system: &dyn FileSystem,
+    path: &Path,
+    depth: usize,
+    settings: &mut Vec<BuildSetting>,
+) -> Result<()> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(XcodeUniffiError::Xcconfig(format!(
+            "{:?}: #include nested too deep, the files probably include each other",
+            path
+        )));
+    }
+    let content = file_system
+        .read_to_string(path)
+        .map_err(|e| XcodeUniffiError::io(path, e))?;
+    let xcconfig = parse_lines(&content, |number| format!("{:?}:{}", path, number))?;
+
+    for line in xcconfig.lines {
+        match line {
+            XcconfigLine::Include {
+                path: include,
+                optional,
+                ..
+            } => {
+                let included: PathBuf = match path.parent() {
+                    Some(directory) => directory.join(include),
+                    None => PathBuf::from(include),
+                };
+                if optional && !file_system.exists(&included) {
+                    continue;
+                }
+                read_into(file_system, &included, depth + 1, settings)?;
+            }
+            XcconfigLine::Setting { setting, .. } => settings.push(setting),
+            XcconfigLine::Comment(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Splits `key = value` at the first `=` outside of the `[condition=value]`s of the key.
+fn assignment(line: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    for (index, c) in line.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            '=' if depth == 0 => return Some((&line[..index], &line[index + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xcodebind_gen::MemoryFileSystem;
+
+    fn file_system(files: &[(&str, &str)]) -> MemoryFileSystem {
+        let fs = MemoryFileSystem::new();
+        for (path, contents) in files {
+            fs.write(Path::new(path), contents).unwrap();
+        }
+        fs
+    }
+
+    fn settings(settings: &[BuildSetting]) -> Vec<(String, &str)> {
+        settings
+            .iter()
+            .map(|setting| (setting.key(), setting.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn includes_are_read_in_place() {
+        let fs = file_system(&[
+            (
+                "/app/Config/App.xcconfig",
+                "// App\n#include \"Shared/Base.xcconfig\"\n#include? \"Local.xcconfig\" // not checked in\n\
+                 OTHER_LDFLAGS = $(inherited) -lmy_lib;\n",
+            ),
+            ("/app/Config/Shared/Base.xcconfig", "SWIFT_VERSION = 5.0\nOTHER_LDFLAGS[sdk=macosx*] = -lc++\n"),
+        ]);
+        let read = read_xcconfig(&fs, Path::new("/app/Config/App.xcconfig")).unwrap();
+        assert_eq!(
+            settings(&read),
+            [
+                ("SWIFT_VERSION".to_string(), "5.0"),
+                ("OTHER_LDFLAGS[sdk=macosx*]".to_string(), "-lc++"),
+                ("OTHER_LDFLAGS".to_string(), "$(inherited) -lmy_lib"),
+            ]
+        );
+
+        fs.write(
+            Path::new("/app/Config/Local.xcconfig"),
+            "SWIFT_VERSION = 5.9\n",
+        )
+        .unwrap();
+        let read = read_xcconfig(&fs, Path::new("/app/Config/App.xcconfig")).unwrap();
+        assert_eq!(settings(&read)[2], ("SWIFT_VERSION".to_string(), "5.9"));
+    }
+
+    #[test]
+    fn missing_files_are_errors() {
+        let fs = file_system(&[("/app/App.xcconfig", "#include \"Missing.xcconfig\"\n")]);
+        let error = read_xcconfig(&fs, Path::new("/app/App.xcconfig")).unwrap_err();
+        assert_eq!(error.kind(), "io");
+        assert!(error.to_string().contains("Missing.xcconfig"));
+
+        let error = read_xcconfig(&fs, Path::new("/app/Other.xcconfig")).unwrap_err();
+        assert_eq!(error.kind(), "io");
+    }
+
+    #[test]
+    fn include_cycles_are_errors() {
+        let fs = file_system(&[
+            ("/app/A.xcconfig", "#include \"B.xcconfig\"\nA = 1\n"),
+            ("/app/B.xcconfig", "#include? \"A.xcconfig\"\nB = 1\n"),
+            ("/app/Self.xcconfig", "#include \"Self.xcconfig\"\n"),
+        ]);
+        for path in ["/app/A.xcconfig", "/app/Self.xcconfig"] {
+            let error = read_xcconfig(&fs, Path::new(path)).unwrap_err();
+            assert_eq!(error.kind(), "xcconfig");
+            assert!(error.to_string().contains("include each other"));
+        }
+    }
+
+    #[test]
+    fn malformed_lines_name_the_file_and_line() {
+        let fs = file_system(&[("/app/App.xcconfig", "A = 1\nnot a setting\n")]);
+        let error = read_xcconfig(&fs, Path::new("/app/App.xcconfig")).unwrap_err();
+        assert_eq!(error.kind(), "xcconfig");
+        assert_eq!(
+            error.to_string(),
+            "Invalid xcconfig file: \"/app/App.xcconfig\":2: expected NAME = value"
+        );
+    }
+
+    const APP: &str = "// App settings\n\
+                       #include? \"Local.xcconfig\" // not checked in\n\
+                       \n\
+                       SWIFT_VERSION = 5.0 // keep in sync with Package.swift\n\
+                       OTHER_LDFLAGS = $(inherited) -lmy_lib\n\
+                       OTHER_LDFLAGS[sdk=macosx*][arch=arm64] = -lc++ // Apple silicon\n\
+                       //   trailing comment\n";
+
+    #[test]
+    fn writer_gives_back_the_parsed_text() {
+        let xcconfig = Xcconfig::parse(APP).unwrap();
+        assert_eq!(xcconfig.to_string(), APP);
+        assert_eq!(
+            xcconfig.includes().collect::<Vec<_>>(),
+            [("Local.xcconfig", true)]
+        );
+        assert_eq!(
+            xcconfig.get("OTHER_LDFLAGS[sdk=macosx*][arch=arm64]"),
+            Some("-lc++")
+        );
+
+        // only the spacing around `=` and the trailing `;` are normalized
+        let xcconfig = Xcconfig::parse("A=1;\nB   =   x y  // b\n").unwrap();
+        assert_eq!(xcconfig.to_string(), "A = 1\nB = x y // b\n");
+    }
+
+    #[test]
+    fn set_keeps_comments() {
+        let mut xcconfig = Xcconfig::parse(APP).unwrap();
+        xcconfig.set("SWIFT_VERSION", "5.9").unwrap();
+        xcconfig
+            .set("OTHER_LDFLAGS[sdk=macosx*][arch=arm64]", "-lc++ -lz")
+            .unwrap();
+        xcconfig.set("ENABLE_BITCODE[sdk=iphoneos*]", "NO").unwrap();
+        assert_eq!(
+            xcconfig.to_string(),
+            "// App settings\n\
+             #include? \"Local.xcconfig\" // not checked in\n\
+             \n\
+             SWIFT_VERSION = 5.9 // keep in sync with Package.swift\n\
+             OTHER_LDFLAGS = $(inherited) -lmy_lib\n\
+             OTHER_LDFLAGS[sdk=macosx*][arch=arm64] = -lc++ -lz // Apple silicon\n\
+             //   trailing comment\n\
+             ENABLE_BITCODE[sdk=iphoneos*] = NO\n"
+        );
+    }
+
+    #[test]
+    fn set_rejects_invalid_names() {
+        let mut xcconfig = Xcconfig::parse(APP).unwrap();
+        for key in [
+            "",
+            "OTHER LDFLAGS",
+            "A = B",
+            "NAME[sdk=macosx*",
+            "NAME[sdk]",
+        ] {
+            let error = xcconfig.set(key, "x").unwrap_err();
+            assert_eq!(error.kind(), "xcconfig", "{:?}", key);
+        }
+        assert_eq!(xcconfig, Xcconfig::parse(APP).unwrap());
+    }
+
+    #[test]
+    fn remove_keeps_the_other_comments() {
+        let mut xcconfig = Xcconfig::parse(APP).unwrap();
+        assert!(xcconfig.remove("SWIFT_VERSION"));
+        assert!(!xcconfig.remove("SWIFT_VERSION"));
+        // conditional assignments are other keys
+        assert!(xcconfig.remove("OTHER_LDFLAGS"));
+        assert_eq!(
+            xcconfig.to_string(),
+            "// App settings\n\
+             #include? \"Local.xcconfig\" // not checked in\n\
+             \n\
+             OTHER_LDFLAGS[sdk=macosx*][arch=arm64] = -lc++ // Apple silicon\n\
+             //   trailing comment\n"
+        );
+    }
+
+    #[test]
+    fn double_slashes_in_values_are_escaped() {
+        let mut xcconfig = Xcconfig::default();
+        xcconfig.set("HOMEPAGE", "https://example.com").unwrap();
+        let text = xcconfig.to_string();
+        assert_eq!(text, "HOMEPAGE = https:/$()/example.com\n");
+        assert_eq!(
+            Xcconfig::parse(&text).unwrap().get("HOMEPAGE"),
+            Some("https:/$()/example.com")
+        );
+    }
+
+    #[test]
+    fn build_settings_are_written_one_per_line() {
+        let content = "// !$*UTF8*$!\n{\n\tbuildSettings = {\n\
+                       \t\tSWIFT_VERSION = 5.0;\n\
+                       \t\t\"LIBRARY_SEARCH_PATHS[sdk=iphoneos*]\" = (\"$(inherited)\", \"$(SRCROOT)/My Lib\", );\n\
+                       \t};\n}\n";
+        let document = crate::xcodebind_gen::PlistDocument::parse(content).unwrap();
+        let settings = document.get("buildSettings").unwrap();
+        assert_eq!(
+            Xcconfig::from_build_settings(settings).to_string(),
+            "SWIFT_VERSION = 5.0\n\
+             LIBRARY_SEARCH_PATHS[sdk=iphoneos*] = $(inherited) \"$(SRCROOT)/My Lib\"\n"
+        );
+    }
+}
@@ -1,212 +1,364 @@
 use super::pbxproj_parser::*;
 use super::CargoPackage;
-use crate::xcodebind_gen::{CommandLineParser, SourceFileGenerator};
+use crate::pbx;
+use crate::xcodebind_gen::{
+    FileSystem, IdAllocator, MessageFormat, PromptPolicy, RealFileSystem, Reporter, Result,
+    SourceFileGenerator, XcodeUniffiError,
+};
+use chrono::{DateTime, Local, Utc};
 use colored::Colorize;
-use crc::{Crc, CRC_64_ECMA_182};
 use std::ffi::OsStr;
-use std::fs::{create_dir, create_dir_all, File};
-use std::io::Read;
-use std::io::Write;
-use std::path::PathBuf;
-use std::process::exit;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Build script copied next to Cargo.toml, invoked by the generated shell script build phases.
+const UNIVERSAL_BINARY_SCRIPT: &str = include_str!("../../scripts/rust-xc-universal-binary.sh");
 
 // const APPLE_PRODUCT_TYPE_FRAMEWORK: &str = "com.apple.product-type.framework";
 // const APPLE_PRODUCT_TYPE_STATIC_LIB: &str = "com.apple.product-type.library.static";
 // const APPLE_PRODUCT_TYPE_EXECUTABLE: &str = "com.apple.product-type.tool";
 
-pub(crate) struct XcodeProject {
-    crc: Crc<u64>,
-    id_base: u64,
+/// Switches that change how a project is created.
+///
+/// The default never prompts, does not overwrite an existing project and reports in human format.
+/// ```
+/// let options = xcode_uniffi::XcodeProjectOptions::default()
+///     .force(true)
+///     .message_format(xcode_uniffi::MessageFormat::Json);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct XcodeProjectOptions {
+    pub(crate) prompt: PromptPolicy,
+    /// Overwrite an existing project instead of offering to update it.
+    pub(crate) force: bool,
+    /// Files are written to a memory filesystem and only reported, see `MemoryFileSystem`.
+    pub(crate) dry_run: bool,
+    /// Leave the generation time out of generated files, see `generated_on`.
+    pub(crate) reproducible: bool,
+    /// Seconds since the epoch written as the generation time, see `generated_on`.
+    pub(crate) source_date_epoch: Option<i64>,
+    pub(crate) reporter: Reporter,
+}
+
+impl Default for XcodeProjectOptions {
+    fn default() -> Self {
+        XcodeProjectOptions {
+            prompt: PromptPolicy {
+                assume_yes: false,
+                no_input: true,
+            },
+            force: false,
+            dry_run: false,
+            reproducible: false,
+            source_date_epoch: None,
+            reporter: Reporter::new(MessageFormat::Human),
+        }
+    }
+}
+
+impl XcodeProjectOptions {
+    /// Answer yes to every question.
+    pub fn assume_yes(mut self, assume_yes: bool) -> Self {
+        self.prompt.assume_yes = assume_yes;
+        self
+    }
+
+    /// Ask questions on stdin when it is a terminal. Disabled by default.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.prompt.no_input = !interactive;
+        self
+    }
+
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Only marks writes as not performed in the report, pair it with a `MemoryFileSystem`.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Generate byte-identical files on every run: source file headers carry no timestamp.
+    pub fn reproducible(mut self, reproducible: bool) -> Self {
+        self.reproducible = reproducible;
+        self
+    }
+
+    /// Time written in the header of generated source files instead of the current time, in
+    /// seconds since the epoch. The command line takes it from `SOURCE_DATE_EPOCH`
+    /// (https://reproducible-builds.org/specs/source-date-epoch/).
+    pub fn source_date_epoch(mut self, source_date_epoch: Option<i64>) -> Self {
+        self.source_date_epoch = source_date_epoch;
+        self
+    }
+
+    pub fn message_format(mut self, format: MessageFormat) -> Self {
+        self.reporter = Reporter::new(format);
+        self
+    }
+
+    pub fn reporter(&self) -> Reporter {
+        self.reporter
+    }
+
+    /// Time written in the header of generated source files.
+    /// `source_date_epoch` pins it, otherwise it is omitted in reproducible mode and the local
+    /// time is used.
+    pub(crate) fn generated_on(&self) -> Option<String> {
+        let pinned = self
+            .source_date_epoch
+            .and_then(|epoch| DateTime::<Utc>::from_timestamp(epoch, 0));
+        match pinned {
+            Some(date) => Some(date.to_string()),
+            None if self.reproducible => None,
+            None => Some(Local::now().to_string()),
+        }
+    }
+
+    /// Asks a yes/no question according to `assume_yes`/`interactive`.
+    pub fn confirm(&self, msg: &str) -> bool {
+        self.prompt.confirm(msg)
+    }
+}
+
+pub struct XcodeProject {
     package: CargoPackage,
+    options: XcodeProjectOptions,
+    fs: Rc<dyn FileSystem>,
 }
 
 impl XcodeProject {
-    pub(crate) fn new(package: CargoPackage) -> Self {
-        let crc = Crc::<u64>::new(&CRC_64_ECMA_182);
-        let id_base = crc.checksum(package.target_name.as_bytes());
+    pub fn new(package: CargoPackage, options: XcodeProjectOptions) -> Self {
+        XcodeProject::with_file_system(package, options, Rc::new(RealFileSystem {}))
+    }
 
+    pub fn with_file_system(
+        package: CargoPackage,
+        options: XcodeProjectOptions,
+        fs: Rc<dyn FileSystem>,
+    ) -> Self {
         Self {
-            crc,
-            id_base,
             package,
+            options,
+            fs,
         }
     }
-    fn check_for_existing_project(&self) -> bool {
-        if self.package.xcode_framework_path.exists() {
-            // check for project name
-            if !self.package.xcode_framework_path.is_dir() {
-                eprintln!(
-                    "Specified Xcode project Path is a file and not a directory: {:?}\n{}\n\n",
-                    self.package.xcode_framework_path,
-                    "Xcode framework path must be directory path and not a file path."
-                        .red()
-                        .bold()
-                        .underline()
-                );
-                exit(1);
-            }
 
-            let dir_entry = match std::fs::read_dir(&self.package.xcode_framework_path) {
-                Ok(paths) => {
-                    paths.filter(|f| f.is_ok())
-                        .map(|f| f.expect("Failed to convert Directory Entry to path").path() ).map(|f| {
-                        match (f.file_name(), f.extension()) {
-                            (Some(file_name), Some(ext)) => {
-                                if ext == OsStr::new("xcodeproj") {
-                                    if file_name == OsStr::new(format!("{}.xcodeproj", self.package.xcode_framework_name).as_str()) {
-                                        Some(f)
-                                    } else {
-                                        eprintln!("An other xcode framework project exist in the same directory with different name.");
-                                        eprintln!("{}\n\n", file_name.to_str().unwrap_or("").red());
-                                        exit(1);
-                                    }
-                                } else {
-                                    None
-                                }
-                            },
-                            _ => None
-                        }
-                    })
-                },
-                Err(e) => {
-                    eprintln!("Unable to open existing Xcode Project Directory at: {:?}\nError: {:?}",
-                    self.package.xcode_framework_path, e);
-                    exit(1);
-                }
-            }.filter(|f| f.is_some());
+    pub fn package(&self) -> &CargoPackage {
+        &self.package
+    }
 
-            // Check for existing project
-            return dir_entry.count() > 0;
+    fn check_for_existing_project(&self) -> Result<bool> {
+        let project_path = &self.package.xcode_framework_path;
+        if !self.fs.exists(project_path) {
+            return Ok(false);
+        }
+        // check for project name
+        if !self.fs.is_dir(project_path) {
+            return Err(XcodeUniffiError::ProjectPathIsFile(project_path.clone()));
         }
-        false
-    }
 
-    pub(crate) fn create(&self) -> &Self {
-        if self.check_for_existing_project() {
-            // Update project instead of create
-            println!(
-                "\n\n{}: {:?}\n",
-                "Existing project found in:".bright_blue(),
-                self.package.xcode_framework_path
-            );
-            if CommandLineParser::get_user_yes_or_no_input("hello") {
-                println!("Updating project");
-                return self.update();
-            } else {
-                exit(0);
+        let expected_name = format!("{}.xcodeproj", self.package.xcode_framework_name);
+        let mut found = false;
+        for f in self
+            .fs
+            .read_dir(project_path)
+            .map_err(|e| XcodeUniffiError::io(project_path, e))?
+        {
+            if let (Some(file_name), Some(ext)) = (f.file_name(), f.extension()) {
+                if ext == OsStr::new("xcodeproj") {
+                    if file_name == OsStr::new(expected_name.as_str()) {
+                        found = true;
+                    } else {
+                        return Err(XcodeUniffiError::ConflictingProject {
+                            directory: project_path.clone(),
+                            name: file_name.to_string_lossy().to_string(),
+                        });
+                    }
+                }
             }
         }
-        let items = self.generate_pbx_project();
-        self.create_project(items);
-        self
+        Ok(found)
     }
 
-    pub(crate) fn update(&self) -> &Self {
-        self
+    pub fn create(&self) -> Result<&Self> {
+        let reporter = &self.options.reporter;
+        if self.check_for_existing_project()? {
+            if self.options.force {
+                reporter.warning(
+                    format!(
+                        "Overwriting existing project in: {:?}",
+                        self.package.xcode_framework_path
+                    )
+                    .as_str(),
+                );
+            } else {
+                // Update project instead of create
+                reporter.info(
+                    format!(
+                        "{} {:?}",
+                        "Existing project found in:".bright_blue(),
+                        self.package.xcode_framework_path
+                    )
+                    .as_str(),
+                );
+                if self
+                    .options
+                    .prompt
+                    .confirm("Do you want to update the existing project")
+                {
+                    reporter.info("Updating project");
+                    return self.update();
+                } else {
+                    reporter.info("Existing project left untouched. Use --force to overwrite it.");
+                    return Ok(self);
+                }
+            }
+        }
+        let project = self.generate_pbx_project();
+        self.report_objects(project.items());
+        self.create_project(&project)?;
+        Ok(self)
     }
 
-    // fn open_existing_project() {}
-
-    // fn update_pbxproj_for_udl_files() {}
+    /// Regenerates an existing project in place, keeping what was changed in Xcode: objects the
+    /// generator does not create, elements added to arrays (group children, build phase files)
+    /// and values that differ from the generated ones (reported as warnings). Objects for new
+    /// UDL files are added. Source files that exist are left alone, missing ones are written.
+    ///
+    /// Update is always reproducible (see `XcodeProjectOptions::reproducible`), files are only
+    /// written when their contents change.
+    pub fn update(&self) -> Result<&Self> {
+        let reporter = &self.options.reporter;
+        if !self.check_for_existing_project()? {
+            return Err(XcodeUniffiError::ProjectNotFound(
+                self.package.xcode_framework_path.clone(),
+            ));
+        }
+        let pbxproj_path = self.pbxproj_path();
+        let content = self
+            .fs
+            .read_to_string(&pbxproj_path)
+            .map_err(|e| XcodeUniffiError::io(&pbxproj_path, e))?;
+        let existing = XcodePbxProject::parse_from_string(&content)?;
+        let generated = self.generate_pbx_project_with_ids(&self.update_ids(&existing)?);
+
+        // without a common base every difference is a conflict, which keeps the project's side
+        let base = XcodePbxProject::from_items(vec![]);
+        let (project, conflicts) = XcodePbxProject::merge(&base, &existing, &generated);
+        for conflict in &conflicts {
+            reporter.warning(
+                format!(
+                    "Kept {} = {}, generated: {}",
+                    conflict.path,
+                    conflict.ours.as_deref().unwrap_or_default(),
+                    conflict.theirs.as_deref().unwrap_or_default()
+                )
+                .as_str(),
+            );
+        }
+        let text = project.serialize();
+        if text != content {
+            self.write_file(&pbxproj_path, &text)?;
+        }
 
-    fn make_id(&self, kind: &str, name: &str) -> String {
-        let mut crc = self.crc.digest();
-        crc.update(&self.id_base.to_ne_bytes());
-        crc.update(kind.as_bytes());
-        let kind = crc.finalize();
+        let options = XcodeProjectOptions {
+            reproducible: true,
+            ..self.options
+        };
+        self.write_sources(options.generated_on().as_deref(), false)?;
+        Ok(self)
+    }
 
-        let name = self.crc.checksum(name.as_bytes());
-        let mut out = format!("CA60{:08X}{:012X}", kind as u32, name);
-        out.truncate(24);
-        out
+    /// IDs for regenerating `existing`: objects the generator creates keep their ID, even one
+    /// derived by an older version (see `IdAllocator::reuse_legacy_ids`), and the IDs
+    /// of the other objects (added in Xcode) are reserved so that objects generated for the
+    /// first time, e.g. for a new UDL file, never take one of them.
+    fn update_ids(&self, existing: &XcodePbxProject) -> Result<IdAllocator> {
+        let ids = IdAllocator::new(&self.package.target_name);
+        ids.reuse_legacy_ids(existing);
+        let mut foreign = existing.clone();
+        let generated = self.generate_pbx_project_with_ids(&ids);
+        for (path, object) in generated.query("objects/*")? {
+            let isa = object.get("isa").and_then(PlistRef::as_str);
+            let existing_isa = existing.get(&format!("{}/isa", path))?;
+            if isa.is_some() && isa == existing_isa.as_ref().and_then(PlistRef::as_str) {
+                foreign.remove(&path)?;
+            }
+        }
+        let ids = IdAllocator::new(&self.package.target_name);
+        ids.reuse_legacy_ids(existing);
+        ids.reserve_project(&foreign);
+        Ok(ids)
     }
 
-    fn make_id_inst(id_base: u64, kind: &str, name: &str) -> String {
-        let m_crc = Crc::<u64>::new(&CRC_64_ECMA_182);
-        let mut crc = m_crc.digest();
-        crc.update(&id_base.to_ne_bytes());
-        crc.update(kind.as_bytes());
-        let kind = crc.finalize();
+    fn pbxproj_path(&self) -> PathBuf {
+        self.package
+            .xcode_framework_path
+            .join(format!("{}.xcodeproj", self.package.xcode_framework_name))
+            .join("project.pbxproj")
+    }
 
-        let name = m_crc.checksum(name.as_bytes());
-        let mut out = format!("CA60{:08X}{:012X}", kind as u32, name);
-        out.truncate(24);
-        out
+    /// Builds the complete `project.pbxproj` object graph for the package.
+    pub fn generate_pbx_project(&self) -> XcodePbxProject {
+        self.generate_pbx_project_with_ids(&IdAllocator::new(&self.package.target_name))
     }
 
-    fn generate_pbx_project(&self) -> Vec<PlistItem> {
-        let mut items: Vec<PlistItem> = vec![];
-        let root_object_id = self.make_id("rootObject", self.package.xcode_framework_name.as_str());
-        let root_object = PlistKeyValueItem::create_value_type(
-            "rootObject",
-            None,
-            &root_object_id,
-            Some("Project object"),
-        );
-        let archive_version =
-            PlistKeyValueItem::create_value_type("archiveVersion", None, "1", None);
-        let classes = PlistKeyValueItem::create_array_type("classes", None, vec![]);
-        let object_version =
-            PlistKeyValueItem::create_value_type("objectVersion", None, "55", None);
-        items.push(archive_version);
-        items.push(classes);
-        items.push(object_version);
+    /// Same as `generate_pbx_project`, taking object IDs from `ids`.
+    /// Reserve the IDs of objects that must be kept (see `IdAllocator::reserve_project`)
+    /// so that generated objects never reuse them.
+    pub fn generate_pbx_project_with_ids(&self, ids: &IdAllocator) -> XcodePbxProject {
+        let root_object_id = ids.allocate("rootObject", self.package.xcode_framework_name.as_str());
 
         // PBXFileReference section
         let (pbx_file_reference_section_ids, pbx_file_reference_section) =
             PlistKeyValueItem::create_pbx_file_reference_section(
                 self.package.xcode_framework_name.as_str(),
                 &self.package.udl_relative_files_path,
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
         let (pbx_build_file_section_ids, pbx_build_file_section) =
             PlistKeyValueItem::create_pbx_build_file_section(
                 &pbx_file_reference_section_ids,
                 self.package.xcode_framework_name.as_str(),
                 &self.package.udl_relative_files_path,
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
         let (pbx_headers_build_section_ids, pbx_headers_build_section) =
             PlistKeyValueItem::create_pbx_headers_build_section(
                 &pbx_build_file_section_ids,
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
         let (pbx_sources_build_section_ids, pbx_sources_build_section) =
             PlistKeyValueItem::create_pbx_sources_build_section(
                 &pbx_build_file_section_ids,
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
         let (pbx_frameworks_build_section_ids, pbx_frameworks_build_section) =
             PlistKeyValueItem::create_pbx_frameworks_build_section(
                 &pbx_build_file_section_ids,
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
         let (pbx_build_rule_section_ids, pbx_build_rule_section) =
             PlistKeyValueItem::create_pbx_build_rule_section(
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
         let (pbx_resource_build_section_ids, pbx_resource_build_section) =
             PlistKeyValueItem::create_pbx_resource_build_phase_section(
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
         let (pbx_group_section_ids, pbx_group_section) =
             PlistKeyValueItem::create_pbx_group_section(
                 &pbx_file_reference_section_ids,
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
 
         let (pbx_shell_script_build_ids, pbx_shell_script_build_section) =
@@ -219,8 +371,7 @@ impl XcodeProject {
                 &self.package.package_name,
                 self.package.target_name.as_str(),
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
 
         let (pbx_xc_build_config_ids, pbx_xc_build_config_item) =
@@ -231,24 +382,21 @@ impl XcodeProject {
                     .to_path_buf()
                     .to_str()
                     .unwrap(),
-                    &self.package.target_name,
+                &self.package.target_name,
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
 
         let (pbx_xc_config_list_ids, pbx_xc_config_list_item) =
             PlistKeyValueItem::create_pbx_xc_configuration_list_section(
                 &pbx_xc_build_config_ids,
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
         let (pbx_target_dependency_ids, pbx_target_dependency_item) =
             PlistKeyValueItem::create_pbx_target_dependency_section(
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
 
         let (_ /* pbx_container_item_proxy_ids */, pbx_container_item_proxy_item) =
@@ -256,8 +404,7 @@ impl XcodeProject {
                 &root_object_id,
                 &pbx_target_dependency_ids,
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
 
         let (pbx_native_target_ids, pbx_native_target_item) =
@@ -272,53 +419,126 @@ impl XcodeProject {
                 &pbx_resource_build_section_ids,
                 &pbx_build_rule_section_ids,
                 self.package.xcode_framework_name.as_str(),
-                self.id_base,
-                XcodeProject::make_id_inst,
+                ids,
             );
 
-        let pbx_project_item = PlistKeyValueItem::create_pbx_project_section(&root_object_id, &pbx_group_section_ids, &pbx_native_target_ids,
-        &pbx_xc_config_list_ids, self.package.xcode_framework_name.as_str());
-        // create_pbx_xc_configuration_list_section
-        let objects = PlistKeyValueItem::create_array_type(
-            "objects",
-            None,
-            vec![
-                pbx_build_file_section,
-                pbx_build_rule_section,
-                pbx_container_item_proxy_item,
-                pbx_file_reference_section,
-                pbx_frameworks_build_section,
-                pbx_group_section,
-                pbx_headers_build_section,
-                pbx_native_target_item,
-                pbx_project_item,
-                pbx_resource_build_section,
-                pbx_shell_script_build_section,
-                pbx_sources_build_section,
-                pbx_target_dependency_item,
-                pbx_xc_build_config_item,
-                pbx_xc_config_list_item,
-            ],
+        let pbx_project_item = PlistKeyValueItem::create_pbx_project_section(
+            &root_object_id,
+            &pbx_group_section_ids,
+            &pbx_native_target_ids,
+            &pbx_xc_config_list_ids,
+            self.package.xcode_framework_name.as_str(),
         );
-        items.push(objects);
+        let project = pbx! {
+            archiveVersion = "1";
+            classes = {};
+            objectVersion = "55";
+            objects = {
+                ..[
+                    pbx_build_file_section,
+                    pbx_build_rule_section,
+                    pbx_container_item_proxy_item,
+                    pbx_file_reference_section,
+                    pbx_frameworks_build_section,
+                    pbx_group_section,
+                    pbx_headers_build_section,
+                    pbx_native_target_item,
+                    pbx_project_item,
+                    pbx_resource_build_section,
+                    pbx_shell_script_build_section,
+                    pbx_sources_build_section,
+                    pbx_target_dependency_item,
+                    pbx_xc_build_config_item,
+                    pbx_xc_config_list_item,
+                ];
+            };
+            rootObject = [root_object_id];
+        };
+
+        XcodePbxProject::from_items(project.into_entries())
+    }
 
-        items.push(root_object);
+    /// Emits an `object-added` event for every object in the generated `objects` dictionary.
+    fn report_objects(&self, items: &[PlistItem]) {
+        let reporter = &self.options.reporter;
+        let mut count = 0;
+        for item in items {
+            let objects = match item {
+                PlistItem::Item(kv) if kv.key.name == "objects" => &kv.value,
+                _ => continue,
+            };
+            let sections = match objects.as_ref() {
+                PlistItem::Array(sections) => sections,
+                _ => continue,
+            };
+            for section in sections {
+                if let PlistItem::SectionItem(section) = section {
+                    for object in &section.items {
+                        if let PlistItem::Item(kv) = object {
+                            reporter.object_added(
+                                &kv.key.name,
+                                &section.name,
+                                kv.key.comment.as_deref(),
+                            );
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+        if !reporter.is_json() {
+            reporter.info(format!("Generated {} project objects", count).as_str());
+        }
+    }
 
-        items
+    fn create_directory(&self, path: &Path) -> Result<()> {
+        if !self.fs.exists(path) {
+            self.fs
+                .create_dir_all(path)
+                .map_err(|e| XcodeUniffiError::io(path, e))?;
+            if !self.options.dry_run {
+                self.options.reporter.directory_created(path);
+            }
+        }
+        Ok(())
     }
 
-    fn create_project(&self, items: Vec<PlistItem>) {
-        
+    fn write_file(&self, path: &Path, contents: &str) -> Result<()> {
+        self.fs
+            .write(path, contents)
+            .map_err(|e| XcodeUniffiError::io(path, e))?;
+        if !self.options.dry_run {
+            self.options.reporter.file_written(path);
+        }
+        Ok(())
+    }
 
+    fn create_project(&self, project: &XcodePbxProject) -> Result<()> {
         let project_dir = &self.package.xcode_framework_path;
         let project_name = &self.package.xcode_framework_name;
+        let generated_on = self.options.generated_on();
 
         let xcodeproj_dir = &project_dir.join(format!("{}.xcodeproj", project_name));
-        let pbxproject_file = &project_dir.join(format!("{}.xcodeproj", project_name)).join("project.pbxproj");
-        let xcworkspace_dir = &project_dir.join(format!("{}.xcodeproj", project_name)).join("project.xcworkspace");
-        let xcuserdata_dir = &project_dir.join(format!("{}.xcodeproj", project_name)).join("xcuserdata");
-        let xcuserdata_in_xcworkspace_dir = &xcworkspace_dir.join("xcuserdata");
-        let xcshareddata_in_xcworkspace_dir = &xcworkspace_dir.join("xcshareddata");
+        let xcworkspace_dir = &xcodeproj_dir.join("project.xcworkspace");
+
+        // Create Project Directory
+        self.create_directory(project_dir)?;
+        self.create_directory(xcodeproj_dir)?;
+        self.create_directory(xcworkspace_dir)?;
+        self.create_directory(&xcodeproj_dir.join("xcuserdata"))?;
+        self.create_directory(&xcworkspace_dir.join("xcuserdata"))?;
+        self.create_directory(&xcworkspace_dir.join("xcshareddata"))?;
+
+        self.write_file(&self.pbxproj_path(), project.serialize().as_str())?;
+        self.write_sources(generated_on.as_deref(), true)
+    }
+
+    /// Headers, docc and test files of the framework and the build script next to Cargo.toml.
+    /// Without `overwrite` existing source files are kept and the build script is only written
+    /// when it changed.
+    fn write_sources(&self, generated_on: Option<&str>, overwrite: bool) -> Result<()> {
+        let project_dir = &self.package.xcode_framework_path;
+        let project_name = &self.package.xcode_framework_name;
 
         let ios_framework_directory = &project_dir.join(format!("{}_ios", project_name));
         let ios_framework_docc_directory = &project_dir.join(format!("{}_ios", project_name)).join(format!("{}_ios.docc", self.package.xcode_framework_name));
@@ -334,97 +554,223 @@ impl XcodeProject {
         let framework_macos_tests_directory = &project_dir.join(format!("{}_macosTests", project_name));
         let framework_macos_tests_swift_file = framework_macos_tests_directory.join(format!("{}_macosTests.swift", self.package.xcode_framework_name));
 
-        // Create Project Directory
-        if !project_dir.exists() {
-            create_dir_all(project_dir)
-                .unwrap_or_else(|_| panic!("Failed to create xcode project directory at: {:?}", project_dir));
+        self.create_directory(ios_framework_directory)?;
+        self.create_directory(macos_framework_directory)?;
+        self.create_directory(ios_framework_docc_directory)?;
+        self.create_directory(macos_framework_docc_directory)?;
+        self.create_directory(framework_ios_tests_directory)?;
+        self.create_directory(framework_macos_tests_directory)?;
+
+        let sources = [
+            (ios_framework_objc_header_file, SourceFileGenerator::create_objc_header(&self.package, false, generated_on)),
+            (macos_framework_objc_header_file, SourceFileGenerator::create_objc_header(&self.package, true, generated_on)),
+            (ios_framework_docc_file, SourceFileGenerator::create_docc_file(&self.package, false)),
+            (macos_framework_docc_file, SourceFileGenerator::create_docc_file(&self.package, true)),
+            (framework_ios_tests_swift_file, SourceFileGenerator::create_swift_file(&self.package, false, generated_on)),
+            (framework_macos_tests_swift_file, SourceFileGenerator::create_swift_file(&self.package, true, generated_on)),
+        ];
+        for (path, contents) in &sources {
+            if overwrite || !self.fs.exists(path) {
+                self.write_file(path, contents)?;
+            }
         }
 
-        if !ios_framework_directory.exists() {
-            create_dir(ios_framework_directory)
-                .unwrap_or_else(|_| panic!("Failed to create framework inside xcode project directory at: {:?}", ios_framework_directory));
+        let dst_dir = &self.package.cargo_base_dir.join("rust-xc-universal-binary.sh");
+        if !overwrite {
+            if self.fs.read_to_string(dst_dir).ok().as_deref() != Some(UNIVERSAL_BINARY_SCRIPT) {
+                self.write_file(dst_dir, UNIVERSAL_BINARY_SCRIPT)?;
+            }
+            return Ok(());
         }
-        if !macos_framework_directory.exists() {
-            create_dir(macos_framework_directory)
-                .unwrap_or_else(|_| panic!("Failed to create framework inside xcode project directory at: {:?}", macos_framework_directory));
+        if self.fs.exists(dst_dir) {
+            self.options.reporter.warning(format!("Replacing existing build script at: {:?}", dst_dir).as_str());
         }
+        self.write_file(dst_dir, UNIVERSAL_BINARY_SCRIPT)?;
+        Ok(())
+    }
 
-        if !ios_framework_docc_directory.exists() {
-            create_dir(ios_framework_docc_directory)
-                .unwrap_or_else(|_| panic!("Failed to create framework inside xcode project directory at: {:?}", ios_framework_docc_directory));
-        }
-        if !macos_framework_docc_directory.exists() {
-            create_dir(macos_framework_docc_directory)
-                .unwrap_or_else(|_| panic!("Failed to create framework inside xcode project directory at: {:?}", macos_framework_docc_directory));
-        }
+}
 
-        if !framework_ios_tests_directory.exists() {
-            create_dir(framework_ios_tests_directory)
-                .unwrap_or_else(|_| panic!("Failed to create framework tests inside xcode project directory at: {:?}", framework_ios_tests_directory));
-        }
-        if !framework_macos_tests_directory.exists() {
-            create_dir(framework_macos_tests_directory)
-                .unwrap_or_else(|_| panic!("Failed to create framework tests inside xcode project directory at: {:?}", framework_macos_tests_directory));
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xcodebind_gen::MemoryFileSystem;
+
+    /// Package of the Cargo project `test_data/cargo/<name>`.
+    fn fixture(name: &str) -> CargoPackage {
+        CargoPackage::builder(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("test_data/cargo")
+                .join(name),
+        )
+        .framework_path("/work/xcode")
+        .build()
+        .unwrap()
+    }
 
-        if !xcodeproj_dir.exists() {
-            create_dir(xcodeproj_dir)
-                .unwrap_or_else(|_| panic!("Failed to create xcodeproj (workspace) directory at: {:?}", xcodeproj_dir));
-        }
+    fn package() -> CargoPackage {
+        fixture("my_lib")
+    }
 
-        //xcworkspace_dir
-        if !xcworkspace_dir.exists() {
-            create_dir(xcworkspace_dir)
-                .unwrap_or_else(|_| panic!("Failed to create project.xcworkspace directory inside xcode project directory at: {:?}", xcworkspace_dir));
-        }
+    fn project(fs: &Rc<MemoryFileSystem>, options: XcodeProjectOptions) -> XcodeProject {
+        XcodeProject::with_file_system(package(), options, fs.clone())
+    }
 
-        if !xcuserdata_dir.exists() {
-            create_dir(xcuserdata_dir)
-                .unwrap_or_else(|_| panic!("Failed to create xcuserdata directory inside xcode project directory at: {:?}", xcuserdata_dir));
-        }
+    /// Files written by `create` in a new memory filesystem.
+    fn create(options: XcodeProjectOptions) -> Vec<(PathBuf, String)> {
+        create_package(package(), options)
+    }
 
-        if !xcuserdata_in_xcworkspace_dir.exists() {
-            create_dir(xcuserdata_in_xcworkspace_dir)
-                .unwrap_or_else(|_| panic!("Failed to create xcuserdata directory inside xcode project.xcworkspace  directory at: {:?}", xcuserdata_in_xcworkspace_dir));
-        }
+    fn create_package(
+        package: CargoPackage,
+        options: XcodeProjectOptions,
+    ) -> Vec<(PathBuf, String)> {
+        let fs = Rc::new(MemoryFileSystem::new());
+        XcodeProject::with_file_system(package, options, fs.clone())
+            .create()
+            .unwrap();
+        fs.written_files()
+    }
 
-        if !xcshareddata_in_xcworkspace_dir.exists() {
-            create_dir(xcshareddata_in_xcworkspace_dir)
-                .unwrap_or_else(|_| panic!("Failed to create xcshareddata directory inside xcode project.xcworkspace  directory at: {:?}", xcshareddata_in_xcworkspace_dir));
-        }
+    fn header(files: &[(PathBuf, String)]) -> &str {
+        files
+            .iter()
+            .find(|(path, _)| path.ends_with("MyLib_ios/MyLib_ios.h"))
+            .map(|(_, contents)| contents.as_str())
+            .unwrap()
+    }
 
-        let mut file = File::create(pbxproject_file).unwrap_or_else(|_| panic!("Unable to create project.pbxproj file"));
-        file.write_all(items.serialize().as_bytes()).unwrap_or_else(|_| panic!("Failed to write data to {:?} file", &pbxproject_file));
+    #[test]
+    fn generating_twice_is_byte_identical() {
+        let options = XcodeProjectOptions::default().reproducible(true);
 
-        let mut file = File::create(&ios_framework_objc_header_file).unwrap_or_else(|_| panic!("Unable to create {:?} file", &ios_framework_objc_header_file));
-        file.write_all(SourceFileGenerator::create_objc_header(&self.package, false).as_bytes()).unwrap_or_else(|_| panic!("Failed to write data to {:?} file", &ios_framework_objc_header_file));
+        let first = create(options);
+        assert_eq!(first, create(options));
+        assert!(!header(&first).contains("//   on "));
 
-        let mut file = File::create(&macos_framework_objc_header_file).unwrap_or_else(|_| panic!("Unable to create {:?} file", &macos_framework_objc_header_file));
-        file.write_all(SourceFileGenerator::create_objc_header(&self.package, true).as_bytes()).unwrap_or_else(|_| panic!("Failed to write data to {:?} file", &macos_framework_objc_header_file));
+        let first = create(options.source_date_epoch(Some(1700000000)));
+        let second = create(XcodeProjectOptions::default().source_date_epoch(Some(1700000000)));
+        assert_eq!(first, second);
+        assert!(header(&first).contains("//   on 2023-11-14 22:13:20 UTC \n"));
 
+        // the `files` and `children` arrays list every UDL file in the same order
+        let first = create_package(fixture("multi_udl"), options);
+        for _ in 0..4 {
+            assert_eq!(first, create_package(fixture("multi_udl"), options));
+        }
+        let pbxproj = &first[1].1;
+        let phases = &pbxproj[pbxproj.find("Begin PBXSourcesBuildPhase").unwrap()..];
+        let positions: Vec<usize> = ["alpha", "beta", "delta", "epsilon", "gamma", "zeta"]
+            .iter()
+            .map(|name| phases.find(&format!("{}.udl in Sources", name)).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
 
-        let mut file = File::create(&ios_framework_docc_file).unwrap_or_else(|_| panic!("Unable to create {:?} file", &ios_framework_docc_file));
-        file.write_all(SourceFileGenerator::create_docc_file(&self.package, false).as_bytes()).unwrap_or_else(|_| panic!("Failed to write data to {:?} file", &ios_framework_docc_file));
+    #[test]
+    fn update_leaves_an_unchanged_project_alone() {
+        let fs = Rc::new(MemoryFileSystem::new());
+        let options = XcodeProjectOptions::default().reproducible(true);
+        project(&fs, options).create().unwrap();
+        let created = fs.written_files();
 
-        let mut file = File::create(&macos_framework_docc_file).unwrap_or_else(|_| panic!("Unable to create {:?} file", &macos_framework_docc_file));
-        file.write_all(SourceFileGenerator::create_docc_file(&self.package, true).as_bytes()).unwrap_or_else(|_| panic!("Failed to write data to {:?} file", &macos_framework_docc_file));
+        project(&fs, options).update().unwrap();
+        assert_eq!(fs.written_files(), created);
+    }
+
+    #[test]
+    fn update_keeps_changes_made_in_xcode() {
+        let fs = Rc::new(MemoryFileSystem::new());
+        project(&fs, XcodeProjectOptions::default())
+            .create()
+            .unwrap();
+        let xcode_project = project(&fs, XcodeProjectOptions::default());
+        let pbxproj_path = xcode_project.pbxproj_path();
+        let mut edited =
+            XcodePbxProject::parse_from_string(&fs.read_to_string(&pbxproj_path).unwrap()).unwrap();
+        let name = "objects/*[isa=PBXNativeTarget][name=MyLib_ios]/productName";
+        let renamed = PlistRef::String("Renamed".into(), None).into_value();
+        assert_eq!(edited.set(name, renamed).unwrap(), 1);
+        fs.write(&pbxproj_path, &edited.serialize()).unwrap();
+
+        xcode_project.update().unwrap();
+        let updated =
+            XcodePbxProject::parse_from_string(&fs.read_to_string(&pbxproj_path).unwrap()).unwrap();
+        let product_name = updated.get(name).unwrap().unwrap();
+        assert_eq!(product_name.as_str(), Some("Renamed"));
+    }
 
-        // framework_ios_tests_swift_file
-        let mut file = File::create(&framework_ios_tests_swift_file).unwrap_or_else(|_| panic!("Unable to create {:?} file", &framework_ios_tests_swift_file));
-        file.write_all(SourceFileGenerator::create_swift_file(&self.package, false).as_bytes()).unwrap_or_else(|_| panic!("Failed to write data to {:?} file", &framework_ios_tests_swift_file));
+    #[test]
+    fn update_writes_missing_sources_without_a_timestamp() {
+        let fs = Rc::new(MemoryFileSystem::new());
+        let pbxproj = create(XcodeProjectOptions::default().reproducible(true))
+            .into_iter()
+            .find(|(path, _)| path.ends_with("project.pbxproj"))
+            .unwrap();
+        fs.write(&pbxproj.0, &pbxproj.1).unwrap();
+
+        project(&fs, XcodeProjectOptions::default())
+            .update()
+            .unwrap();
+        let files = fs.written_files();
+        assert!(!header(&files).contains("//   on "));
+        assert_eq!(
+            files.iter().find(|(path, _)| *path == pbxproj.0),
+            Some(&pbxproj)
+        );
+    }
 
-        let mut file = File::create(&framework_macos_tests_swift_file).unwrap_or_else(|_| panic!("Unable to create {:?} file", &framework_macos_tests_swift_file));
-        file.write_all(SourceFileGenerator::create_swift_file(&self.package, true).as_bytes()).unwrap_or_else(|_| panic!("Failed to write data to {:?} file", &framework_macos_tests_swift_file));
+    #[test]
+    fn update_without_a_project_fails() {
+        let fs = Rc::new(MemoryFileSystem::new());
+        let error = project(&fs, XcodeProjectOptions::default())
+            .update()
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), "project-not-found");
+        assert!(fs.written_files().is_empty());
+    }
 
-        let mut script_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        script_file = script_file.join("scripts").join("rust-xc-universal-binary.sh");
-        let mut file = File::open(&script_file).unwrap_or_else(|_| panic!("Unable to open {:?} file", &script_file));
-        let mut scripts_file_contents = String::new();
-        file.read_to_string(&mut scripts_file_contents).unwrap_or_else(|_| panic!("Failed to read data from {:?} file", &script_file));
+    #[test]
+    fn create_writes_the_whole_project_to_the_file_system() {
+        let files = create(XcodeProjectOptions::default().reproducible(true));
+        let paths: Vec<&Path> = files.iter().map(|(path, _)| path.as_path()).collect();
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/cargo/my_lib");
+        assert_eq!(
+            paths,
+            [
+                manifest_dir.join("rust-xc-universal-binary.sh").as_path(),
+                Path::new("/work/xcode/MyLib/MyLib.xcodeproj/project.pbxproj"),
+                Path::new("/work/xcode/MyLib/MyLib_ios/MyLib_ios.docc/MyLib_ios.md"),
+                Path::new("/work/xcode/MyLib/MyLib_ios/MyLib_ios.h"),
+                Path::new("/work/xcode/MyLib/MyLib_iosTests/MyLib_iosTests.swift"),
+                Path::new("/work/xcode/MyLib/MyLib_macos/MyLib_macos.docc/MyLib_macos.md"),
+                Path::new("/work/xcode/MyLib/MyLib_macos/MyLib_macos.h"),
+                Path::new("/work/xcode/MyLib/MyLib_macosTests/MyLib_macosTests.swift"),
+            ]
+        );
+        assert!(!Path::new("/work/xcode").exists());
+        assert!(!manifest_dir.join("rust-xc-universal-binary.sh").exists());
 
-        let dst_dir = &self.package.cargo_base_dir.join("rust-xc-universal-binary.sh");
-        let mut file = File::create(&dst_dir).unwrap_or_else(|_| panic!("Unable to create {:?} file", &dst_dir));
-        file.write_all(scripts_file_contents.as_bytes()).unwrap_or_else(|_| panic!("Failed to write data to {:?} file", &dst_dir));
+        let fs = Rc::new(MemoryFileSystem::new());
+        let xcode_project = project(&fs, XcodeProjectOptions::default());
+        assert_eq!(files[1].1, xcode_project.generate_pbx_project().serialize());
+        assert_eq!(files[0].1, UNIVERSAL_BINARY_SCRIPT);
     }
 
+    #[test]
+    fn dry_run_over_the_disk_writes_nothing() {
+        let fs = Rc::new(MemoryFileSystem::overlay());
+        let options = XcodeProjectOptions::default().dry_run(true);
+        project(&fs, options).create().unwrap();
+
+        let diffs = fs.diff_against_disk();
+        assert_eq!(diffs.len(), fs.written_files().len());
+        for (path, diff) in &diffs {
+            assert!(!path.exists(), "{:?}", path);
+            assert!(diff.starts_with("--- /dev/null\n"));
+        }
+        // the next step sees the files of the dry run
+        assert!(project(&fs, options).check_for_existing_project().unwrap());
+    }
 }
@@ -0,0 +1,190 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+use xcode_uniffi::plist::PlistRef;
+use xcode_uniffi::XcodePbxProject;
+
+const TARGET_NAME: &str = "objects/*[isa=PBXNativeTarget][name=HiTel]/productName";
+
+fn project() -> XcodePbxProject {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/project.pbxproj");
+    XcodePbxProject::parse_from_string(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn write_project(name: &str, project: &XcodePbxProject) -> PathBuf {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, project.serialize()).unwrap();
+    path
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_xcode_uniffi"))
+        .args(["--message-format", "json"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn run_with_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_xcode_uniffi"))
+        .args(["--message-format", "json"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Project text `fmt` rewrites: indented with spaces instead of tabs.
+fn unformatted() -> String {
+    project().serialize().replace('\t', "    ")
+}
+
+fn events(stream: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stream)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn query_set_writes_only_the_project_on_stdout() {
+    let input = write_project("query_set.pbxproj", &project());
+    let output = run(&[
+        "query",
+        input.to_str().unwrap(),
+        "--set",
+        TARGET_NAME,
+        "Renamed",
+        "--set",
+        "objects/*[isa=PBXNativeTarget][name=Missing]/productName",
+        "Nothing",
+    ]);
+    assert!(output.status.success());
+
+    let mut expected = project();
+    let renamed = PlistRef::String("Renamed".into(), None).into_value();
+    assert_eq!(expected.set(TARGET_NAME, renamed).unwrap(), 1);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        expected.serialize()
+    );
+    let events = events(&output.stderr);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["reason"], "warning");
+}
+
+#[test]
+fn merge_to_stdout_reports_conflicts_on_stderr() {
+    let base = project();
+    let mut ours = project();
+    let mut theirs = project();
+    let name = |name: &str| PlistRef::String(name.into(), None).into_value();
+    ours.set(TARGET_NAME, name("Ours")).unwrap();
+    theirs.set(TARGET_NAME, name("Theirs")).unwrap();
+    let base = write_project("merge_base.pbxproj", &base);
+    let theirs = write_project("merge_theirs.pbxproj", &theirs);
+    let output = run(&[
+        "merge",
+        base.to_str().unwrap(),
+        write_project("merge_ours.pbxproj", &ours).to_str().unwrap(),
+        theirs.to_str().unwrap(),
+        "-o",
+        "-",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), ours.serialize());
+    let events = events(&output.stderr);
+    let reasons: Vec<_> = events
+        .iter()
+        .map(|event| event["reason"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(reasons, ["merge-conflict", "error"]);
+    assert_eq!(events[1]["message"], "1 conflict, our side was kept");
+}
+
+#[test]
+fn fmt_rewrites_files_in_place() {
+    let unformatted_path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fmt_in_place.pbxproj");
+    fs::write(&unformatted_path, unformatted()).unwrap();
+    let formatted_path = write_project("fmt_formatted.pbxproj", &project());
+    let output = run(&[
+        "fmt",
+        unformatted_path.to_str().unwrap(),
+        formatted_path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+
+    assert_eq!(
+        fs::read_to_string(&unformatted_path).unwrap(),
+        project().serialize()
+    );
+    // already formatted files are left alone
+    let events = events(&output.stdout);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["reason"], "file-written");
+    assert_eq!(events[0]["path"], unformatted_path.to_str().unwrap());
+}
+
+#[test]
+fn fmt_of_standard_input_reports_on_stderr() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fmt_with_stdin.pbxproj");
+    fs::write(&path, unformatted()).unwrap();
+    let output = run_with_stdin(&["fmt", "-", path.to_str().unwrap()], &unformatted());
+    assert!(output.status.success());
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        project().serialize()
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), project().serialize());
+    let events = events(&output.stderr);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["reason"], "file-written");
+}
+
+#[test]
+fn fmt_check_fails_on_unformatted_files_without_writing() {
+    let unformatted_path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fmt_check.pbxproj");
+    fs::write(&unformatted_path, unformatted()).unwrap();
+    let formatted_path = write_project("fmt_check_formatted.pbxproj", &project());
+
+    let output = run(&["fmt", "--check", formatted_path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    let output = run(&[
+        "fmt",
+        "--check",
+        unformatted_path.to_str().unwrap(),
+        formatted_path.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        fs::read_to_string(&unformatted_path).unwrap(),
+        unformatted()
+    );
+    let events = events(&output.stdout);
+    let reasons: Vec<&str> = events
+        .iter()
+        .map(|event| event["reason"].as_str().unwrap())
+        .collect();
+    assert_eq!(reasons, ["file-diff", "error"]);
+    assert_eq!(events[0]["path"], unformatted_path.to_str().unwrap());
+    assert_eq!(events[1]["message"], "1 file not formatted");
+
+    let output = run_with_stdin(&["fmt", "--check", "-"], &unformatted());
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}